@@ -0,0 +1,642 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use sqlx::{QueryBuilder, Sqlite, SqlitePool};
+use tauri::State;
+use tauri_plugin_http::reqwest;
+
+use crate::calendar;
+use crate::incremental;
+use crate::settings;
+use crate::sqlite::connection::Db;
+use crate::sqlite::writer;
+use crate::types::{Bar, Timeframe};
+
+/// 證交所除權除息計算結果表
+const TWSE_EX_RIGHT_URL: &str = "https://www.twse.com.tw/rwd/zh/exRight/TWT49U";
+pub const PRICE_MODE_KEY: &str = "priceAdjustment";
+
+/// 選股與指標使用的價格
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PriceMode {
+    /// 原始下載價格
+    #[default]
+    Raw,
+    /// 還原除權息 / 減資後的向前調整價格，只套用於日線；週線跨越除權息日無法拆分，與小時線皆維持原始價格
+    Adjusted,
+}
+
+/// 一筆公司行動 (除權、除息、減資)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Adjustment {
+    pub stock_id: String,
+    pub t: String,
+    #[serde(default)]
+    pub cash_dividend: f64,
+    #[serde(default = "default_share_ratio")]
+    pub share_ratio: f64,
+    #[serde(default)]
+    pub prev_close: Option<f64>,
+    #[serde(default)]
+    pub ref_price: Option<f64>,
+    #[serde(default)]
+    pub source: Option<String>,
+}
+
+fn default_share_ratio() -> f64 {
+    1.0
+}
+
+impl Adjustment {
+    /// 前一日收盤價換算為除權息後價格的比例。
+    /// 有參考價時直接以 參考價 / 前收 計算，否則以 (前收 - 現金股利) / 前收 / 配股比例 推算。
+    fn price_factor(&self, prev_close: f64) -> Option<f64> {
+        let prev_close = self.prev_close.filter(|p| *p > 0.0).unwrap_or(prev_close);
+        if prev_close <= 0.0 {
+            return None;
+        }
+        let factor = match self.ref_price.filter(|p| *p > 0.0) {
+            Some(ref_price) => ref_price / prev_close,
+            None => (prev_close - self.cash_dividend) / prev_close / self.share_ratio,
+        };
+        (factor.is_finite() && factor > 0.0).then_some(factor)
+    }
+}
+
+/// 兩個除權息日之間共用同一組還原乘數，區間為 [t_from, t_to)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FactorSegment {
+    pub t_from: String,
+    pub t_to: String,
+    pub price_factor: f64,
+    pub volume_factor: f64,
+}
+
+/// 套用 K 線所在區間的還原乘數，不在任何區間內 (最後一次事件之後) 的 K 線不變
+pub fn scale(bar: Bar, segments: &[FactorSegment]) -> Bar {
+    let segment = segments
+        .iter()
+        .find(|s| bar.t.as_str() >= s.t_from.as_str() && bar.t.as_str() < s.t_to.as_str());
+    match segment {
+        Some(s) => Bar {
+            o: bar.o * s.price_factor,
+            h: bar.h * s.price_factor,
+            l: bar.l * s.price_factor,
+            c: bar.c * s.price_factor,
+            v: (bar.v as f64 * s.volume_factor) as i64,
+            ..bar
+        },
+        None => bar,
+    }
+}
+
+/// 向前還原：最新價格不變，除權息日之前的價格依序乘上之後所有事件的乘數
+pub fn back_adjust(bars: &[Bar], adjustments: &[Adjustment]) -> (Vec<Bar>, Vec<FactorSegment>) {
+    let mut events: Vec<(&str, f64, f64)> = Vec::new();
+    let mut sorted: Vec<&Adjustment> = adjustments.iter().collect();
+    sorted.sort_by(|a, b| a.t.cmp(&b.t));
+    for adjustment in sorted {
+        let index = bars.partition_point(|bar| bar.t.as_str() < adjustment.t.as_str());
+        // 除權息日早於第一筆 K 線或尚未發生，不影響現有資料
+        if index == 0 || index == bars.len() {
+            continue;
+        }
+        if let Some(factor) = adjustment.price_factor(bars[index - 1].c) {
+            events.push((adjustment.t.as_str(), factor, adjustment.share_ratio));
+        }
+    }
+
+    let mut segments = Vec::with_capacity(events.len());
+    let mut price_factor = 1.0;
+    let mut volume_factor = 1.0;
+    for i in (0..events.len()).rev() {
+        let (t_to, factor, share_ratio) = events[i];
+        price_factor *= factor;
+        volume_factor *= share_ratio;
+        segments.push(FactorSegment {
+            t_from: if i == 0 {
+                String::new()
+            } else {
+                events[i - 1].0.to_string()
+            },
+            t_to: t_to.to_string(),
+            price_factor,
+            volume_factor,
+        });
+    }
+    segments.reverse();
+
+    let adjusted = bars
+        .iter()
+        .map(|bar| scale(bar.clone(), &segments))
+        .collect();
+    (adjusted, segments)
+}
+
+pub async fn load_adjustments(
+    pool: &SqlitePool,
+    stock_id: &str,
+) -> Result<Vec<Adjustment>, String> {
+    type Row = (
+        String,
+        String,
+        Option<f64>,
+        Option<f64>,
+        Option<f64>,
+        Option<f64>,
+        Option<String>,
+    );
+    let rows: Vec<Row> = sqlx::query_as(
+        "SELECT stock_id, t, cash_dividend, share_ratio, prev_close, ref_price, source
+         FROM adjustments WHERE stock_id = $1 ORDER BY t",
+    )
+    .bind(stock_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("[Adjustment] load error: {}", e))?;
+    Ok(rows
+        .into_iter()
+        .map(
+            |(stock_id, t, cash, ratio, prev_close, ref_price, source)| Adjustment {
+                stock_id,
+                t,
+                cash_dividend: cash.unwrap_or(0.0),
+                share_ratio: ratio.filter(|r| *r > 0.0).unwrap_or(1.0),
+                prev_close,
+                ref_price,
+                source,
+            },
+        )
+        .collect())
+}
+
+pub async fn save_adjustments(pool: &SqlitePool, adjustments: &[Adjustment]) -> Result<(), String> {
+    if adjustments.is_empty() {
+        return Ok(());
+    }
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    for chunk in adjustments.chunks(100) {
+        let mut qb: QueryBuilder<Sqlite> = QueryBuilder::new(
+            "INSERT OR REPLACE INTO adjustments (stock_id, t, cash_dividend, share_ratio, prev_close, ref_price, source) ",
+        );
+        qb.push_values(chunk, |mut b, a| {
+            b.push_bind(&a.stock_id)
+                .push_bind(&a.t)
+                .push_bind(a.cash_dividend)
+                .push_bind(a.share_ratio)
+                .push_bind(a.prev_close)
+                .push_bind(a.ref_price)
+                .push_bind(&a.source);
+        });
+        qb.build()
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("[Adjustment] save error: {}", e))?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+/// 讀取某檔股票的向前還原日 K
+pub async fn adjusted_deals(pool: &SqlitePool, stock_id: &str) -> Result<Vec<Bar>, String> {
+    let bars = writer::load_deals(pool, Timeframe::Daily, stock_id).await?;
+    let adjustments = load_adjustments(pool, stock_id).await?;
+    Ok(back_adjust(&bars, &adjustments).0)
+}
+
+/// 讀取已保存的還原乘數，依區間排序
+pub async fn load_factors(pool: &SqlitePool, stock_id: &str) -> Result<Vec<FactorSegment>, String> {
+    let rows: Vec<(String, String, f64, f64)> = sqlx::query_as(
+        "SELECT t_from, t_to, price_factor, volume_factor FROM adjustment_factor
+         WHERE stock_id = $1 ORDER BY t_from",
+    )
+    .bind(stock_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("[Adjustment] load factors error: {}", e))?;
+    Ok(rows
+        .into_iter()
+        .map(
+            |(t_from, t_to, price_factor, volume_factor)| FactorSegment {
+                t_from,
+                t_to,
+                price_factor,
+                volume_factor,
+            },
+        )
+        .collect())
+}
+
+async fn save_factors(
+    pool: &SqlitePool,
+    stock_id: &str,
+    segments: &[FactorSegment],
+) -> Result<(), String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    sqlx::query("DELETE FROM adjustment_factor WHERE stock_id = $1")
+        .bind(stock_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    for s in segments {
+        sqlx::query(
+            "INSERT INTO adjustment_factor (stock_id, t_from, t_to, price_factor, volume_factor) VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(stock_id)
+        .bind(&s.t_from)
+        .bind(&s.t_to)
+        .bind(s.price_factor)
+        .bind(s.volume_factor)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+    writer::bump_versions(&mut *tx, &["adjustment_factor"]).await?;
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+async fn current_factors(pool: &SqlitePool, stock_id: &str) -> Result<Vec<FactorSegment>, String> {
+    let bars = writer::load_deals(pool, Timeframe::Daily, stock_id).await?;
+    let adjustments = load_adjustments(pool, stock_id).await?;
+    Ok(back_adjust(&bars, &adjustments).1)
+}
+
+/// 重建還原乘數並依價格模式完整重算 `daily_skills`
+pub async fn apply(pool: &SqlitePool, stock_id: &str, mode: PriceMode) -> Result<(), String> {
+    save_factors(pool, stock_id, &current_factors(pool, stock_id).await?).await?;
+    incremental::update(pool, Timeframe::Daily, stock_id, "", mode)
+        .await
+        .map(|_| ())
+}
+
+/// 只在還原乘數改變 (新的除權息日已有 K 線) 時才重建；還原模式下乘數一變，所有歷史價格都會改變而需完整重算
+pub async fn refresh(pool: &SqlitePool, stock_id: &str, mode: PriceMode) -> Result<bool, String> {
+    let segments = current_factors(pool, stock_id).await?;
+    if segments == load_factors(pool, stock_id).await? {
+        return Ok(false);
+    }
+    save_factors(pool, stock_id, &segments).await?;
+    if mode == PriceMode::Adjusted {
+        incremental::update(pool, Timeframe::Daily, stock_id, "", mode).await?;
+    }
+    Ok(true)
+}
+
+/// 重建有公司行動已進入 K 線範圍、但尚未計入還原乘數的股票
+pub async fn refresh_pending(pool: &SqlitePool, mode: PriceMode) -> Result<usize, String> {
+    let stock_ids: Vec<String> = sqlx::query_scalar(
+        "SELECT DISTINCT a.stock_id FROM adjustments a
+         WHERE a.t > (SELECT MIN(t) FROM daily_deal d WHERE d.stock_id = a.stock_id)
+           AND a.t <= (SELECT MAX(t) FROM daily_deal d WHERE d.stock_id = a.stock_id)
+           AND a.t > COALESCE((SELECT MAX(t_to) FROM adjustment_factor f WHERE f.stock_id = a.stock_id), '')",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("[Adjustment] load pending error: {}", e))?;
+    let mut count = 0;
+    for stock_id in &stock_ids {
+        match refresh(pool, stock_id, mode).await {
+            Ok(changed) => count += changed as usize,
+            Err(e) => log::error!("[Adjustment] refresh {} failed: {}", stock_id, e),
+        }
+    }
+    Ok(count)
+}
+
+/// 對所有有公司行動紀錄的股票重新套用
+pub async fn apply_all(pool: &SqlitePool, mode: PriceMode) -> Result<usize, String> {
+    let stock_ids: Vec<String> = sqlx::query_scalar("SELECT DISTINCT stock_id FROM adjustments")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    apply_stocks(pool, stock_ids.iter().map(String::as_str), mode).await
+}
+
+async fn apply_stocks<'a>(
+    pool: &SqlitePool,
+    stock_ids: impl IntoIterator<Item = &'a str>,
+    mode: PriceMode,
+) -> Result<usize, String> {
+    let mut count = 0;
+    for stock_id in stock_ids {
+        if let Err(e) = apply(pool, stock_id, mode).await {
+            log::error!("[Adjustment] apply {} failed: {}", stock_id, e);
+            continue;
+        }
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// 解析 CSV，需有標題列，欄位: stock_id, t, cash_dividend, share_ratio, prev_close, ref_price (後四欄可省略)
+pub fn parse_csv(text: &str) -> Result<Vec<Adjustment>, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(text.as_bytes());
+    let mut adjustments = Vec::new();
+    for (line, record) in reader.deserialize::<Adjustment>().enumerate() {
+        let mut adjustment =
+            record.map_err(|e| format!("[Adjustment] CSV line {}: {}", line + 2, e))?;
+        adjustment.t = adjustment.t.replace(['-', '/'], "");
+        if calendar::parse_day(&adjustment.t).is_none() {
+            return Err(format!(
+                "[Adjustment] CSV line {}: invalid date {}",
+                line + 2,
+                adjustment.t
+            ));
+        }
+        if adjustment.share_ratio <= 0.0 {
+            adjustment.share_ratio = 1.0;
+        }
+        adjustment.source = Some("csv".to_string());
+        adjustments.push(adjustment);
+    }
+    Ok(adjustments)
+}
+
+#[derive(Deserialize)]
+struct TwseResponse {
+    stat: String,
+    #[serde(default)]
+    fields: Vec<String>,
+    #[serde(default)]
+    data: Vec<Vec<serde_json::Value>>,
+}
+
+/// 民國年日期 `113年01月02日` 轉為 YYYYMMDD
+fn parse_roc_date(text: &str) -> Option<String> {
+    let digits: Vec<u32> = text
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.parse().ok())
+        .collect::<Option<_>>()?;
+    let [year, month, day] = digits[..] else {
+        return None;
+    };
+    Some(format!("{}{:02}{:02}", year + 1911, month, day))
+}
+
+/// 除權後每股變為幾股：除權前收盤價扣除現金股利後，與參考價的比值。
+/// TWT49U 的「權息」列只提供權值與息值的合計，無法拆出現金股利，此時以 前收 / 參考價 近似。
+fn share_ratio(prev_close: Option<f64>, ref_price: Option<f64>, cash_dividend: f64) -> f64 {
+    let (Some(prev_close), Some(ref_price)) = (prev_close, ref_price) else {
+        return 1.0;
+    };
+    let ratio = (prev_close - cash_dividend) / ref_price;
+    // 參考價已進位到升降單位，取到小數 4 位去掉誤差
+    if ratio.is_finite() && ratio > 0.0 {
+        (ratio * 10_000.0).round() / 10_000.0
+    } else {
+        1.0
+    }
+}
+
+fn parse_number(value: &serde_json::Value) -> Option<f64> {
+    match value {
+        serde_json::Value::Number(n) => n.as_f64(),
+        serde_json::Value::String(s) => s.replace(',', "").trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// 解析證交所 TWT49U 回應 (依欄位名稱對應，避免欄位順序調整)
+pub fn parse_twse(text: &str) -> Result<Vec<Adjustment>, String> {
+    let response: TwseResponse =
+        serde_json::from_str(text).map_err(|e| format!("[Adjustment] TWSE parse error: {}", e))?;
+    if response.stat != "OK" {
+        return Ok(Vec::new());
+    }
+    let column = |name: &str| response.fields.iter().position(|f| f.contains(name));
+    let (Some(date), Some(id), Some(prev), Some(reference), Some(value)) = (
+        column("資料日期"),
+        column("股票代號"),
+        column("前收盤價"),
+        column("參考價"),
+        column("權值"),
+    ) else {
+        return Err("[Adjustment] TWSE fields changed".to_string());
+    };
+    let kind = column("權/息");
+
+    Ok(response
+        .data
+        .iter()
+        .filter_map(|row| {
+            let t = parse_roc_date(row.get(date)?.as_str()?)?;
+            let stock_id = row.get(id)?.as_str()?.trim().to_string();
+            let kind = kind.and_then(|k| row.get(k)?.as_str()).unwrap_or("息");
+            let value = row.get(value).and_then(parse_number).unwrap_or(0.0);
+            let prev_close = row.get(prev).and_then(parse_number);
+            let ref_price = row.get(reference).and_then(parse_number);
+            // 純除權時權值並非現金股利，一律以參考價計算
+            let cash_dividend = if kind == "息" { value } else { 0.0 };
+            Some(Adjustment {
+                stock_id,
+                t,
+                cash_dividend,
+                // 除息不改變股數；除權 (配股) 的股數比例只能由參考價反推，成交量還原需要此比例
+                share_ratio: if kind == "息" {
+                    1.0
+                } else {
+                    share_ratio(prev_close, ref_price, cash_dividend)
+                },
+                prev_close,
+                ref_price,
+                source: Some("twse".to_string()),
+            })
+        })
+        .collect())
+}
+
+fn stock_ids(adjustments: &[Adjustment]) -> BTreeSet<&str> {
+    adjustments.iter().map(|a| a.stock_id.as_str()).collect()
+}
+
+pub fn price_mode(app: &tauri::AppHandle) -> PriceMode {
+    settings::get(app, PRICE_MODE_KEY).unwrap_or_default()
+}
+
+/// 同步時已以還原價格增量更新指標，這裡只處理同步後才生效的除權息事件
+pub async fn on_sync_success(app: &tauri::AppHandle, pool: &SqlitePool) -> Result<(), String> {
+    let count = refresh_pending(pool, price_mode(app)).await?;
+    log::info!("[Adjustment] refreshed factors for {} stocks", count);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn import_adjustments_csv(
+    app: tauri::AppHandle,
+    db: State<'_, Db>,
+    path: String,
+) -> Result<usize, String> {
+    let text = std::fs::read_to_string(Path::new(&path))
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let adjustments = parse_csv(&text)?;
+    save_adjustments(&db.0, &adjustments).await?;
+    apply_stocks(&db.0, stock_ids(&adjustments), price_mode(&app)).await?;
+    Ok(adjustments.len())
+}
+
+/// 下載 [start_date, end_date] (YYYYMMDD) 的證交所除權息資料
+#[tauri::command]
+pub async fn fetch_twse_adjustments(
+    app: tauri::AppHandle,
+    db: State<'_, Db>,
+    start_date: String,
+    end_date: String,
+) -> Result<usize, String> {
+    let response = reqwest::Client::new()
+        .get(TWSE_EX_RIGHT_URL)
+        .query(&[
+            ("response", "json"),
+            ("startDate", start_date.as_str()),
+            ("endDate", end_date.as_str()),
+        ])
+        .send()
+        .await
+        .map_err(|e| format!("[Adjustment] TWSE request error: {}", e))?;
+    let text = response.text().await.map_err(|e| e.to_string())?;
+    let adjustments = parse_twse(&text)?;
+    save_adjustments(&db.0, &adjustments).await?;
+    apply_stocks(&db.0, stock_ids(&adjustments), price_mode(&app)).await?;
+    Ok(adjustments.len())
+}
+
+#[tauri::command]
+pub async fn get_adjusted_deals(db: State<'_, Db>, stock_id: String) -> Result<Vec<Bar>, String> {
+    adjusted_deals(&db.0, &stock_id).await
+}
+
+#[tauri::command]
+pub fn get_price_mode(app: tauri::AppHandle) -> PriceMode {
+    price_mode(&app)
+}
+
+/// 切換價格模式並重算所有受公司行動影響的股票
+#[tauri::command]
+pub async fn set_price_mode(
+    app: tauri::AppHandle,
+    db: State<'_, Db>,
+    mode: PriceMode,
+) -> Result<usize, String> {
+    settings::set(&app, PRICE_MODE_KEY, mode)?;
+    apply_all(&db.0, mode).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bar(t: &str, c: f64, v: i64) -> Bar {
+        Bar {
+            t: t.to_string(),
+            o: c,
+            h: c,
+            l: c,
+            c,
+            v,
+        }
+    }
+
+    fn event(t: &str, cash_dividend: f64, share_ratio: f64) -> Adjustment {
+        Adjustment {
+            stock_id: "2330".to_string(),
+            t: t.to_string(),
+            cash_dividend,
+            share_ratio,
+            prev_close: None,
+            ref_price: None,
+            source: None,
+        }
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn cash_dividend_scales_prices_only() {
+        let bars = [bar("20240102", 100.0, 1000), bar("20240103", 95.0, 1000)];
+        let (adjusted, segments) = back_adjust(&bars, &[event("20240103", 5.0, 1.0)]);
+        assert_close(adjusted[0].c, 95.0);
+        assert_eq!(adjusted[0].v, 1000);
+        assert_eq!(adjusted[1], bars[1]);
+        assert_eq!(segments.len(), 1);
+        assert_eq!(segments[0].t_from, "");
+        assert_eq!(segments[0].t_to, "20240103");
+        assert_close(segments[0].volume_factor, 1.0);
+    }
+
+    #[test]
+    fn stock_dividend_scales_volume() {
+        // 每股配 0.1 股：參考價 100 = 前收 110 / 1.1
+        let adjustment = Adjustment {
+            prev_close: Some(110.0),
+            ref_price: Some(100.0),
+            ..event("20240103", 0.0, 1.1)
+        };
+        let bars = [bar("20240102", 110.0, 1000), bar("20240103", 100.0, 1100)];
+        let (adjusted, segments) = back_adjust(&bars, &[adjustment]);
+        assert_close(adjusted[0].c, 100.0);
+        assert_eq!(adjusted[0].v, 1100);
+        assert_close(segments[0].volume_factor, 1.1);
+    }
+
+    #[test]
+    fn capital_reduction_raises_prices_and_cuts_volume() {
+        // 減資一半：每股變為 0.5 股，價格加倍
+        let bars = [bar("20240102", 20.0, 1000), bar("20240110", 40.0, 500)];
+        let (adjusted, segments) = back_adjust(&bars, &[event("20240110", 0.0, 0.5)]);
+        assert_close(adjusted[0].c, 40.0);
+        assert_eq!(adjusted[0].v, 500);
+        assert_close(segments[0].price_factor, 2.0);
+        assert_close(segments[0].volume_factor, 0.5);
+    }
+
+    #[test]
+    fn events_compound_backwards() {
+        let bars = [
+            bar("20240102", 100.0, 1000),
+            bar("20240103", 95.0, 1000),
+            bar("20240104", 95.0, 1000),
+            bar("20240105", 47.5, 2000),
+        ];
+        let adjustments = [
+            event("20240103", 5.0, 1.0),
+            event("20240105", 0.0, 2.0),
+            // 早於第一根與尚未發生的事件不影響
+            event("20231201", 1.0, 1.0),
+            event("20240201", 1.0, 1.0),
+        ];
+        let (adjusted, segments) = back_adjust(&bars, &adjustments);
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[1].t_from, "20240103");
+        assert_close(adjusted[0].c, 47.5);
+        assert_eq!(adjusted[0].v, 2000);
+        assert_close(adjusted[1].c, 47.5);
+        assert_eq!(adjusted[3], bars[3]);
+        // 增量更新以保存的乘數換算，結果需與完整還原一致
+        let scaled: Vec<Bar> = bars.iter().map(|b| scale(b.clone(), &segments)).collect();
+        assert_eq!(scaled, adjusted);
+    }
+
+    #[test]
+    fn twse_rights_derive_share_ratio() {
+        let text = r#"{"stat":"OK","fields":["資料日期","股票代號","股票名稱","除權息前收盤價","除權息參考價","權值+息值","權/息"],
+            "data":[["113年07月15日","1101","台泥","110.00","100.00","10.00","權"],
+                    ["113年07月16日","2330","台積電","1,000.00","996.00","4.00","息"]]}"#;
+        let adjustments = parse_twse(text).unwrap();
+        assert_eq!(adjustments.len(), 2);
+        assert_eq!(adjustments[0].t, "20240715");
+        assert_close(adjustments[0].share_ratio, 1.1);
+        assert_close(adjustments[0].cash_dividend, 0.0);
+        assert_close(adjustments[1].share_ratio, 1.0);
+        assert_close(adjustments[1].cash_dividend, 4.0);
+    }
+}
@@ -0,0 +1,202 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tauri::State;
+use tauri_plugin_notification::NotificationExt;
+
+use crate::screen::{DateContext, PromptTemplate};
+use crate::settings;
+use crate::sqlite::connection::Db;
+use crate::watchlist;
+
+/// 前端 `useAlertRules` 寫入 store.json 的警示條件與監控股票
+const RULES_KEY: &str = "alertRules";
+const STOCKS_KEY: &str = "alertStocks";
+
+/// 通知內容最多列出的股票數
+const NOTIFY_STOCKS: usize = 5;
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct AlertRecord {
+    pub alarm_id: String,
+    pub alarm_name: String,
+    pub stock_id: String,
+    pub stock_name: Option<String>,
+    pub t: String,
+    /// 同一條件與股票前一交易日未觸發，才算新的觸發並送出通知
+    pub notified: bool,
+    pub fired_at: String,
+}
+
+async fn matched_stocks(
+    pool: &SqlitePool,
+    sql: &str,
+    stock_ids: &[String],
+) -> Result<Vec<String>, String> {
+    let placeholders = (1..=stock_ids.len())
+        .map(|i| format!("${}", i))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let query = format!(
+        "SELECT DISTINCT stock_id FROM ({}) WHERE stock_id IN ({})",
+        sql, placeholders
+    );
+    let mut q = sqlx::query_scalar(&query);
+    for stock_id in stock_ids {
+        q = q.bind(stock_id);
+    }
+    q.fetch_all(pool).await.map_err(|e| e.to_string())
+}
+
+/// 寫入觸發紀錄，回傳需要通知的股票 (當日首次寫入且前一交易日未觸發)
+async fn record(
+    pool: &SqlitePool,
+    rule: &PromptTemplate,
+    dates: &DateContext,
+    stock_ids: &[String],
+) -> Result<Vec<String>, String> {
+    let mut fresh = Vec::new();
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    for stock_id in stock_ids {
+        let continued: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM alert_history WHERE alarm_id = $1 AND stock_id = $2 AND t = $3)",
+        )
+        .bind(&rule.id)
+        .bind(stock_id)
+        .bind(dates.previous_day())
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let inserted = sqlx::query(
+            "INSERT OR IGNORE INTO alert_history (alarm_id, alarm_name, stock_id, t, notified, fired_at)
+             VALUES ($1, $2, $3, $4, $5, datetime('now', 'localtime'))",
+        )
+        .bind(&rule.id)
+        .bind(&rule.name)
+        .bind(stock_id)
+        .bind(dates.today())
+        .bind(!continued)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("[Alerts] save history error: {}", e))?
+        .rows_affected()
+            > 0;
+
+        if inserted && !continued {
+            fresh.push(stock_id.clone());
+        }
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(fresh)
+}
+
+async fn stock_names(pool: &SqlitePool) -> Result<BTreeMap<String, String>, String> {
+    let rows: Vec<(String, Option<String>)> =
+        sqlx::query_as("SELECT stock_id, stock_name FROM stock")
+            .fetch_all(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    Ok(rows
+        .into_iter()
+        .map(|(id, name)| (id, name.unwrap_or_default()))
+        .collect())
+}
+
+fn notify(
+    app: &tauri::AppHandle,
+    rule: &PromptTemplate,
+    stocks: &[String],
+    names: &BTreeMap<String, String>,
+) {
+    let mut body = stocks
+        .iter()
+        .take(NOTIFY_STOCKS)
+        .map(|id| match names.get(id) {
+            Some(name) if !name.is_empty() => format!("{} {}", id, name),
+            _ => id.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join("、");
+    if stocks.len() > NOTIFY_STOCKS {
+        body.push_str(&format!(" 等 {} 檔", stocks.len()));
+    }
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title(format!("警示觸發：{}", rule.name))
+        .body(body)
+        .show()
+    {
+        log::warn!("[Alerts] notification failed: {}", e);
+    }
+}
+
+/// 以最新資料評估所有警示條件，新觸發的條件送出系統通知
+pub async fn evaluate(app: &tauri::AppHandle, pool: &SqlitePool) -> Result<usize, String> {
+    let rules: Vec<PromptTemplate> = settings::get(app, RULES_KEY).unwrap_or_default();
+    // 雲端自選股與本地清單取聯集，離線時仍可評估本地清單
+    let mut stock_ids: Vec<String> = settings::get(app, STOCKS_KEY).unwrap_or_default();
+    stock_ids.extend(watchlist::all_stock_ids(pool).await?);
+    stock_ids.sort();
+    stock_ids.dedup();
+    if rules.is_empty() || stock_ids.is_empty() {
+        return Ok(0);
+    }
+    let Some(dates) = DateContext::load(pool, None).await? else {
+        return Ok(0);
+    };
+    let names = stock_names(pool).await?;
+
+    let mut fired = 0;
+    for rule in &rules {
+        let template = match rule.compile() {
+            Ok(template) => template,
+            Err(e) => {
+                log::warn!("[Alerts] {} skipped: {}", rule.name, e);
+                continue;
+            }
+        };
+        let Some(sql) = dates.render(&template) else {
+            log::warn!("[Alerts] {} skipped: not enough local dates", rule.name);
+            continue;
+        };
+        let matched = match matched_stocks(pool, &sql, &stock_ids).await {
+            Ok(matched) => matched,
+            Err(e) => {
+                log::warn!("[Alerts] {} failed: {}", rule.name, e);
+                continue;
+            }
+        };
+        let fresh = record(pool, rule, &dates, &matched).await?;
+        if !fresh.is_empty() {
+            notify(app, rule, &fresh, &names);
+            fired += fresh.len();
+        }
+    }
+    log::info!(
+        "[Alerts] evaluated {} rules, {} new alerts",
+        rules.len(),
+        fired
+    );
+    Ok(fired)
+}
+
+#[tauri::command]
+pub async fn get_alert_history(
+    db: State<'_, Db>,
+    limit: Option<i64>,
+) -> Result<Vec<AlertRecord>, String> {
+    sqlx::query_as(
+        "SELECT h.alarm_id, h.alarm_name, h.stock_id, s.stock_name, h.t, h.notified, h.fired_at
+         FROM alert_history h
+         LEFT JOIN stock s ON s.stock_id = h.stock_id
+         ORDER BY h.t DESC, h.fired_at DESC
+         LIMIT $1",
+    )
+    .bind(limit.unwrap_or(200))
+    .fetch_all(&db.0)
+    .await
+    .map_err(|e| format!("[Alerts] load history error: {}", e))
+}
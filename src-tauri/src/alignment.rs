@@ -0,0 +1,404 @@
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tauri::State;
+
+use crate::calendar::{self, TradingCalendar};
+use crate::sqlite::connection::Db;
+use crate::yahoo::TAIPEI_OFFSET_SECS;
+
+/// 台股一般交易時段
+const OPEN: (u32, u32) = (9, 0);
+const CLOSE: (u32, u32) = (13, 30);
+/// 判斷本週是否還有交易日時，往後查看的日 K 日期數 (涵蓋兩週含補班日)
+const LATER_SESSIONS: i64 = 12;
+const MINUTE_FORMAT: &str = "%Y%m%d%H%M";
+
+/// 某時框在評估時間的對齊結果，皆為資料表中的時間鍵
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Aligned {
+    /// 評估時間之前最後一根已收盤的 K 線
+    pub completed: Option<String>,
+    /// 評估時間所在、尚未收盤的 K 線
+    pub in_progress: Option<String>,
+}
+
+impl Aligned {
+    /// 條件可使用的最新 K 線。回看歷史時資料庫中的進行中 K 線已含評估時間之後的成交，只能取已收盤的
+    pub fn latest(&self, live: bool) -> Option<&str> {
+        self.in_progress
+            .as_deref()
+            .filter(|_| live)
+            .or(self.completed.as_deref())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Alignment {
+    /// 評估時間 YYYYMMDDHHmm (台北時間)
+    pub at: String,
+    /// 本地沒有評估日之後的日 K，進行中的 K 線只含評估時間以前的資料
+    pub live: bool,
+    pub daily: Aligned,
+    pub weekly: Aligned,
+    pub hourly: Aligned,
+}
+
+fn at_time(day: NaiveDate, (hour, minute): (u32, u32)) -> NaiveDateTime {
+    day.and_time(NaiveTime::from_hms_opt(hour, minute, 0).unwrap_or_default())
+}
+
+/// 該日 13:30 收盤，收盤後當日 K 線與最後一根小時 K 才算完成
+pub fn session_close(day: NaiveDate) -> NaiveDateTime {
+    at_time(day, CLOSE)
+}
+
+/// 目前的台北時間，不受系統時區影響
+pub fn now() -> NaiveDateTime {
+    chrono::Utc::now().naive_utc() + Duration::seconds(TAIPEI_OFFSET_SECS as i64)
+}
+
+pub fn format_at(at: NaiveDateTime) -> String {
+    at.format(MINUTE_FORMAT).to_string()
+}
+
+pub fn parse_at(text: &str) -> Option<NaiveDateTime> {
+    match text.len() {
+        8 => calendar::parse_day(text).map(session_close),
+        _ => NaiveDateTime::parse_from_str(text.get(0..12)?, MINUTE_FORMAT).ok(),
+    }
+}
+
+/// 週 K 以週一為起點分組，時間鍵為週一或該週最後交易日皆可
+fn week_start(day: NaiveDate) -> NaiveDate {
+    day - Duration::days(day.weekday().num_days_from_monday() as i64)
+}
+
+/// 日曆範圍外的日期無從得知是否休市，平日一律視為交易日
+fn maybe_session(calendar: &TradingCalendar, day: NaiveDate) -> bool {
+    calendar
+        .is_session(day)
+        .unwrap_or(day.weekday().num_days_from_monday() < 5)
+}
+
+/// 評估時間之後本週已沒有尚未收盤的交易日
+fn week_closed(at: NaiveDateTime, calendar: &TradingCalendar) -> bool {
+    let today = at.date();
+    if maybe_session(calendar, today) && at < session_close(today) {
+        return false;
+    }
+    let sunday = week_start(today) + Duration::days(6);
+    (1..7)
+        .map(|n| today + Duration::days(n))
+        .take_while(|day| *day <= sunday)
+        .all(|day| !maybe_session(calendar, day))
+}
+
+/// 日 K：評估日收盤前為進行中，開盤前當日 K 線尚未存在
+pub fn align_daily(at: NaiveDateTime, keys: &[String]) -> Aligned {
+    let today = at.date();
+    let day_of = |key: &String| calendar::parse_day(key);
+    Aligned {
+        completed: keys
+            .iter()
+            .filter(|key| {
+                day_of(key)
+                    .is_some_and(|day| day < today || (day == today && at >= session_close(day)))
+            })
+            .cloned()
+            .max(),
+        in_progress: keys
+            .iter()
+            .filter(|key| {
+                day_of(key).is_some_and(|day| {
+                    day == today && at >= at_time(day, OPEN) && at < session_close(day)
+                })
+            })
+            .cloned()
+            .max(),
+    }
+}
+
+/// 週 K：本週最後一個交易日收盤後才算完成，休市日由 `calendar` 判斷
+pub fn align_weekly(at: NaiveDateTime, keys: &[String], calendar: &TradingCalendar) -> Aligned {
+    let week = week_start(at.date());
+    let week_of = |key: &String| calendar::parse_day(key).map(week_start);
+    let previous = keys
+        .iter()
+        .filter(|key| week_of(key).is_some_and(|w| w < week))
+        .cloned()
+        .max();
+    let current = keys
+        .iter()
+        .filter(|key| week_of(key) == Some(week))
+        .cloned()
+        .max();
+    match current {
+        Some(current) if week_closed(at, calendar) => Aligned {
+            completed: Some(current),
+            in_progress: None,
+        },
+        current => Aligned {
+            completed: previous,
+            in_progress: current,
+        },
+    }
+}
+
+/// 小時 K：時間鍵為開始時間，整點結束；13:00 那根到 13:30 收盤才完成
+pub fn align_hourly(at: NaiveDateTime, keys: &[String]) -> Aligned {
+    let span = |key: &String| {
+        let start = NaiveDateTime::parse_from_str(key.get(0..12)?, MINUTE_FORMAT).ok()?;
+        let end = (start + Duration::hours(1)).min(session_close(start.date()));
+        Some((start, end.max(start)))
+    };
+    Aligned {
+        completed: keys
+            .iter()
+            .filter(|key| span(key).is_some_and(|(_, end)| end <= at))
+            .cloned()
+            .max(),
+        in_progress: keys
+            .iter()
+            .filter(|key| span(key).is_some_and(|(start, end)| start <= at && at < end))
+            .cloned()
+            .max(),
+    }
+}
+
+async fn keys(
+    pool: &SqlitePool,
+    sql: &str,
+    bound: String,
+    limit: i64,
+) -> Result<Vec<String>, String> {
+    sqlx::query_scalar(sql)
+        .bind(bound)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("[Alignment] load keys error: {}", e))
+}
+
+impl Alignment {
+    /// 由本地資料表解析 `at` 時各時框最後完成與進行中的 K 線
+    pub async fn load(pool: &SqlitePool, at: NaiveDateTime) -> Result<Self, String> {
+        let today = calendar::format_day(at.date());
+        let daily = keys(
+            pool,
+            "SELECT DISTINCT t FROM daily_deal WHERE t <= $1 ORDER BY t DESC LIMIT $2",
+            today.clone(),
+            LATER_SESSIONS,
+        )
+        .await?;
+        let later = keys(
+            pool,
+            "SELECT DISTINCT t FROM daily_deal WHERE t > $1 ORDER BY t LIMIT $2",
+            today,
+            LATER_SESSIONS,
+        )
+        .await?;
+        let sessions = TradingCalendar::from_sessions(
+            daily
+                .iter()
+                .chain(&later)
+                .filter_map(|t| calendar::parse_day(t)),
+        );
+        let sunday = week_start(at.date()) + Duration::days(6);
+        let weekly = keys(
+            pool,
+            "SELECT DISTINCT t FROM weekly_deal WHERE t <= $1 ORDER BY t DESC LIMIT $2",
+            calendar::format_day(sunday),
+            3,
+        )
+        .await?;
+        let hourly = keys(
+            pool,
+            "SELECT DISTINCT ts FROM hourly_deal WHERE ts <= $1 ORDER BY ts DESC LIMIT $2",
+            format_at(at),
+            3,
+        )
+        .await?;
+
+        Ok(Self {
+            at: format_at(at),
+            live: later.is_empty(),
+            daily: align_daily(at, &daily),
+            weekly: align_weekly(at, &weekly, &sessions),
+            hourly: align_hourly(at, &hourly),
+        })
+    }
+}
+
+/// `at` 為 YYYYMMDDHHmm 或 YYYYMMDD (該日收盤)，未指定時為現在
+#[tauri::command]
+pub async fn align_timeframes(db: State<'_, Db>, at: Option<String>) -> Result<Alignment, String> {
+    let at = match at {
+        Some(text) => parse_at(&text).ok_or(format!("invalid time {}", text))?,
+        None => now(),
+    };
+    Alignment::load(&db.0, at).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(text: &str) -> NaiveDateTime {
+        parse_at(text).unwrap()
+    }
+
+    fn keys(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn aligned(completed: Option<&str>, in_progress: Option<&str>) -> Aligned {
+        Aligned {
+            completed: completed.map(String::from),
+            in_progress: in_progress.map(String::from),
+        }
+    }
+
+    /// 2026-10-05 (一) 至 2026-10-16 (五)，10/9 (五) 國慶連假休市
+    fn sessions() -> TradingCalendar {
+        TradingCalendar::from_sessions(
+            [5, 6, 7, 8, 12, 13, 14, 15, 16]
+                .into_iter()
+                .map(|d| NaiveDate::from_ymd_opt(2026, 10, d).unwrap()),
+        )
+    }
+
+    #[test]
+    fn daily_bar_completes_at_the_close() {
+        let days = keys(&["20261006", "20261007"]);
+        assert_eq!(
+            align_daily(at("202610071329"), &days),
+            aligned(Some("20261006"), Some("20261007"))
+        );
+        assert_eq!(
+            align_daily(at("202610071330"), &days),
+            aligned(Some("20261007"), None)
+        );
+        // 開盤前當日 K 線若已存在必為事後資料
+        assert_eq!(
+            align_daily(at("202610070830"), &days),
+            aligned(Some("20261006"), None)
+        );
+    }
+
+    #[test]
+    fn daily_bar_never_looks_ahead() {
+        let days = keys(&["20261007", "20261008", "20261012"]);
+        assert_eq!(
+            align_daily(at("20261007"), &days),
+            aligned(Some("20261007"), None)
+        );
+        // 休市日對齊到前一交易日
+        assert_eq!(
+            align_daily(at("202610091000"), &days),
+            aligned(Some("20261008"), None)
+        );
+    }
+
+    #[test]
+    fn hourly_bars_end_on_the_hour_and_at_1330() {
+        let hours = keys(&["202610071100", "202610071200", "202610071300"]);
+        assert_eq!(
+            align_hourly(at("202610071215"), &hours[..2]),
+            aligned(Some("202610071100"), Some("202610071200"))
+        );
+        assert_eq!(
+            align_hourly(at("202610071300"), &hours),
+            aligned(Some("202610071200"), Some("202610071300"))
+        );
+        assert_eq!(
+            align_hourly(at("202610071329"), &hours),
+            aligned(Some("202610071200"), Some("202610071300"))
+        );
+        assert_eq!(
+            align_hourly(at("202610071330"), &hours),
+            aligned(Some("202610071300"), None)
+        );
+    }
+
+    #[test]
+    fn hourly_bars_skip_holidays() {
+        let hours = keys(&["202610081200", "202610081300"]);
+        assert_eq!(
+            align_hourly(at("202610091000"), &hours),
+            aligned(Some("202610081300"), None)
+        );
+        assert_eq!(
+            align_hourly(at("202610120900"), &hours),
+            aligned(Some("202610081300"), None)
+        );
+    }
+
+    #[test]
+    fn week_in_progress_until_last_session_closes() {
+        // 週 K 時間鍵為週一
+        let weeks = keys(&["20260928", "20261005", "20261012"]);
+        assert_eq!(
+            align_weekly(at("20261014"), &weeks, &sessions()),
+            aligned(Some("20261005"), Some("20261012"))
+        );
+        assert_eq!(
+            align_weekly(at("202610161329"), &weeks, &sessions()),
+            aligned(Some("20261005"), Some("20261012"))
+        );
+        assert_eq!(
+            align_weekly(at("20261016"), &weeks, &sessions()),
+            aligned(Some("20261012"), None)
+        );
+        // 週末與下週一開盤前，上週已完成
+        assert_eq!(
+            align_weekly(at("202610180900"), &weeks, &sessions()),
+            aligned(Some("20261012"), None)
+        );
+        assert_eq!(
+            align_weekly(at("202610190800"), &weeks, &sessions()),
+            aligned(Some("20261012"), None)
+        );
+    }
+
+    #[test]
+    fn week_completes_early_before_a_friday_holiday() {
+        // 週 K 時間鍵為該週最後交易日
+        let weeks = keys(&["20261002", "20261008", "20261016"]);
+        assert_eq!(
+            align_weekly(at("202610081200"), &weeks, &sessions()),
+            aligned(Some("20261002"), Some("20261008"))
+        );
+        assert_eq!(
+            align_weekly(at("20261008"), &weeks, &sessions()),
+            aligned(Some("20261008"), None)
+        );
+        assert_eq!(
+            align_weekly(at("202610091000"), &weeks, &sessions()),
+            aligned(Some("20261008"), None)
+        );
+    }
+
+    #[test]
+    fn unknown_future_weekdays_keep_the_week_open() {
+        // 本地日曆只到 10/14，週四、週五未知時視為交易日
+        let calendar = TradingCalendar::from_sessions(
+            [12, 13, 14]
+                .into_iter()
+                .map(|d| NaiveDate::from_ymd_opt(2026, 10, d).unwrap()),
+        );
+        let weeks = keys(&["20261005", "20261012"]);
+        assert_eq!(
+            align_weekly(at("20261014"), &weeks, &calendar),
+            aligned(Some("20261005"), Some("20261012"))
+        );
+    }
+
+    #[test]
+    fn latest_only_uses_in_progress_bars_when_live() {
+        let week = aligned(Some("20261005"), Some("20261012"));
+        assert_eq!(week.latest(true), Some("20261012"));
+        assert_eq!(week.latest(false), Some("20261005"));
+        assert_eq!(aligned(None, None).latest(true), None);
+    }
+}
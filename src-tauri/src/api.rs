@@ -0,0 +1,417 @@
+use std::collections::BTreeMap;
+use std::net::{Ipv4Addr, SocketAddr};
+
+use http_body_util::{BodyExt, Full, LengthLimitError, Limited};
+use hyper::body::{Body, Bytes, Incoming};
+use hyper::header::{AUTHORIZATION, CONTENT_TYPE};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sqlx::SqlitePool;
+use subtle::ConstantTimeEq;
+use tauri::Manager;
+
+use crate::calendar;
+use crate::export;
+use crate::indicators::SKILLS_COLUMNS;
+use crate::screen::PromptTemplate;
+use crate::screen_cache;
+use crate::settings;
+use crate::sqlite::connection::Db;
+
+/// store.json 中的開關、埠號與存取權杖，由「其他設定」頁面寫入
+const ENABLED_KEY: &str = "apiEnabled";
+const PORT_KEY: &str = "apiPort";
+const TOKEN_KEY: &str = "apiToken";
+/// 前端 `usePromptTemplates` 寫入的多空策略樣板
+const PROMPTS_KEY: &str = "promptTemplates";
+
+const DEFAULT_PORT: u16 = 17878;
+/// 單次查詢回傳的最多列數
+const MAX_ROWS: i64 = 5000;
+const MAX_BODY_BYTES: usize = 1 << 20;
+
+#[derive(Clone)]
+struct ApiState {
+    app: tauri::AppHandle,
+    pool: SqlitePool,
+    token: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApiInfo {
+    pub enabled: bool,
+    pub port: u16,
+    pub token: String,
+}
+
+/// 直接帶入的策略只接受基本面與排名條件，由 `PromptTemplate::compile` 依欄位白名單組成 SQL；
+/// 技術面 SQL 只能來自已儲存的策略
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct InlinePrompt {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    fundamentals: Vec<String>,
+    #[serde(default)]
+    rankings: Vec<String>,
+}
+
+impl From<InlinePrompt> for PromptTemplate {
+    fn from(prompt: InlinePrompt) -> Self {
+        PromptTemplate {
+            id: String::new(),
+            name: prompt.name,
+            sql: String::new(),
+            fundamentals: prompt.fundamentals,
+            rankings: prompt.rankings,
+        }
+    }
+}
+
+/// `POST /screen` 的內容，以 `prompt_id` / `name` 指定已儲存的策略，或直接帶入條件
+#[derive(Debug, Deserialize)]
+struct ScreenBody {
+    prompt_id: Option<String>,
+    name: Option<String>,
+    prompt: Option<InlinePrompt>,
+    date: Option<String>,
+}
+
+type ApiResult = Result<Value, (StatusCode, String)>;
+
+fn bad_request(message: impl Into<String>) -> (StatusCode, String) {
+    (StatusCode::BAD_REQUEST, message.into())
+}
+
+fn internal(message: String) -> (StatusCode, String) {
+    (StatusCode::INTERNAL_SERVER_ERROR, message)
+}
+
+/// 讀取請求內容，實際讀到的位元組超過 `MAX_BODY_BYTES` 即回傳 413，不依賴 Content-Length
+async fn read_body<B>(body: B) -> Result<Bytes, (StatusCode, String)>
+where
+    B: Body,
+    B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    match Limited::new(body, MAX_BODY_BYTES).collect().await {
+        Ok(collected) => Ok(collected.to_bytes()),
+        Err(e) if e.is::<LengthLimitError>() => {
+            Err((StatusCode::PAYLOAD_TOO_LARGE, "body too large".to_string()))
+        }
+        Err(e) => Err(bad_request(e.to_string())),
+    }
+}
+
+/// 以作業系統的密碼學亂數產生 128 bit 權杖
+fn generate_token() -> Result<String, String> {
+    let mut bytes = [0u8; 16];
+    getrandom::fill(&mut bytes).map_err(|e| format!("[Api] generate token error: {}", e))?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// 讀取設定，尚未產生權杖時建立並寫回 store.json
+fn load_info(app: &tauri::AppHandle) -> Result<ApiInfo, String> {
+    let token = match settings::get::<String>(app, TOKEN_KEY).filter(|t| !t.is_empty()) {
+        Some(token) => token,
+        None => {
+            let token = generate_token()?;
+            settings::set(app, TOKEN_KEY, &token)?;
+            token
+        }
+    };
+    Ok(ApiInfo {
+        enabled: settings::get(app, ENABLED_KEY).unwrap_or(false),
+        port: settings::get(app, PORT_KEY).unwrap_or(DEFAULT_PORT),
+        token,
+    })
+}
+
+fn query_params(req: &Request<Incoming>) -> BTreeMap<String, String> {
+    let uri = format!("http://localhost{}", req.uri());
+    tauri::Url::parse(&uri)
+        .map(|url| url.query_pairs().into_owned().collect())
+        .unwrap_or_default()
+}
+
+fn rows_json(rows: &[sqlx::sqlite::SqliteRow]) -> Value {
+    Value::Array(
+        rows.iter()
+            .map(|row| Value::Object(export::row_values(row).into_iter().collect()))
+            .collect(),
+    )
+}
+
+async fn fetch_rows(pool: &SqlitePool, sql: &str, binds: &[String]) -> ApiResult {
+    let mut query = sqlx::query(sql);
+    for bind in binds {
+        query = query.bind(bind);
+    }
+    let rows = query
+        .fetch_all(pool)
+        .await
+        .map_err(|e| internal(format!("[Api] query error: {}", e)))?;
+    Ok(rows_json(&rows))
+}
+
+async fn stocks(state: &ApiState) -> ApiResult {
+    fetch_rows(
+        &state.pool,
+        "SELECT stock_id, stock_name, industry_group, market_type FROM stock ORDER BY stock_id",
+        &[],
+    )
+    .await
+}
+
+/// `stock_id` 必填，`from` / `to` 為 YYYYMMDD，`limit` 預設且最多 5000
+async fn daily_range(
+    state: &ApiState,
+    params: &BTreeMap<String, String>,
+    columns: &str,
+    table: &str,
+) -> ApiResult {
+    let stock_id = params
+        .get("stock_id")
+        .ok_or_else(|| bad_request("stock_id is required"))?;
+    let limit = params
+        .get("limit")
+        .and_then(|l| l.parse::<i64>().ok())
+        .unwrap_or(MAX_ROWS)
+        .clamp(1, MAX_ROWS);
+    let sql = format!(
+        "SELECT stock_id, t, {} FROM {} WHERE stock_id = $1 AND t >= $2 AND t <= $3 ORDER BY t LIMIT {}",
+        columns, table, limit
+    );
+    fetch_rows(
+        &state.pool,
+        &sql,
+        &[
+            stock_id.clone(),
+            params.get("from").cloned().unwrap_or_default(),
+            params
+                .get("to")
+                .cloned()
+                .unwrap_or_else(|| "99999999".into()),
+        ],
+    )
+    .await
+}
+
+async fn health(state: &ApiState, params: &BTreeMap<String, String>) -> ApiResult {
+    match params.get("stock_id") {
+        Some(stock_id) => {
+            fetch_rows(
+                &state.pool,
+                "SELECT * FROM stock_health_view WHERE stock_id = $1",
+                std::slice::from_ref(stock_id),
+            )
+            .await
+        }
+        None => {
+            fetch_rows(
+                &state.pool,
+                "SELECT * FROM stock_health_view ORDER BY stock_id",
+                &[],
+            )
+            .await
+        }
+    }
+}
+
+fn saved_prompts(state: &ApiState) -> Vec<PromptTemplate> {
+    settings::get(&state.app, PROMPTS_KEY).unwrap_or_default()
+}
+
+async fn run_screen(state: &ApiState, body: Bytes) -> ApiResult {
+    let body: ScreenBody =
+        serde_json::from_slice(&body).map_err(|e| bad_request(format!("invalid body: {}", e)))?;
+    let template = match (body.prompt, body.prompt_id, body.name) {
+        (Some(prompt), _, _) => prompt.into(),
+        (None, id, name) => saved_prompts(state)
+            .into_iter()
+            .find(|p| Some(&p.id) == id.as_ref() || Some(&p.name) == name.as_ref())
+            .ok_or((StatusCode::NOT_FOUND, "prompt not found".to_string()))?,
+    };
+    let date = match body.date {
+        Some(date) => Some(
+            calendar::parse_day(&date.replace(['-', '/'], ""))
+                .ok_or_else(|| bad_request(format!("invalid date {}", date)))?,
+        ),
+        None => None,
+    };
+    let matches = screen_cache::screen(&state.pool, &template, date)
+        .await
+        .map_err(internal)?;
+    Ok(json!({ "name": template.name, "count": matches.len(), "stocks": matches }))
+}
+
+/// 以固定時間比較權杖，避免由回應時間逐字猜出權杖
+fn authorized(state: &ApiState, req: &Request<Incoming>) -> bool {
+    req.headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| bool::from(token.as_bytes().ct_eq(state.token.as_bytes())))
+}
+
+async fn route(state: &ApiState, req: Request<Incoming>) -> ApiResult {
+    if !authorized(state, &req) {
+        return Err((StatusCode::UNAUTHORIZED, "invalid token".to_string()));
+    }
+    let params = query_params(&req);
+    let skills = SKILLS_COLUMNS.join(", ");
+    match (req.method().clone(), req.uri().path()) {
+        (Method::GET, "/stocks") => stocks(state).await,
+        (Method::GET, "/daily_deal") => {
+            daily_range(state, &params, "o, h, l, c, v", "daily_deal").await
+        }
+        (Method::GET, "/daily_skills") => {
+            daily_range(state, &params, &skills, "daily_skills").await
+        }
+        (Method::GET, "/health") => health(state, &params).await,
+        (Method::GET, "/prompts") => Ok(json!(saved_prompts(state)
+            .into_iter()
+            .map(|p| json!({ "id": p.id, "name": p.name }))
+            .collect::<Vec<_>>())),
+        (Method::POST, "/screen") => {
+            let body = read_body(req.into_body()).await?;
+            run_screen(state, body).await
+        }
+        _ => Err((StatusCode::NOT_FOUND, "not found".to_string())),
+    }
+}
+
+async fn handle(
+    state: ApiState,
+    req: Request<Incoming>,
+) -> Result<Response<Full<Bytes>>, std::convert::Infallible> {
+    let (status, body) = match route(&state, req).await {
+        Ok(value) => (StatusCode::OK, value),
+        Err((status, message)) => (status, json!({ "error": message })),
+    };
+    let response = Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "application/json; charset=utf-8")
+        .body(Full::new(Bytes::from(body.to_string())))
+        .unwrap_or_default();
+    Ok(response)
+}
+
+async fn serve(state: ApiState, port: u16) -> Result<(), String> {
+    // 只綁定 loopback，外部主機無法連線
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .map_err(|e| format!("[Api] bind {} error: {}", addr, e))?;
+    log::info!("[Api] listening on http://{}", addr);
+    loop {
+        let (stream, _) = match listener.accept().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                log::warn!("[Api] accept error: {}", e);
+                continue;
+            }
+        };
+        let state = state.clone();
+        tauri::async_runtime::spawn(async move {
+            let service = service_fn(move |req| handle(state.clone(), req));
+            if let Err(e) = http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .await
+            {
+                log::warn!("[Api] connection error: {}", e);
+            }
+        });
+    }
+}
+
+/// 使用者啟用時於 `setup` 啟動本地 API，設定變更需重新啟動 App 生效
+pub fn start(app: &tauri::AppHandle) {
+    let info = match load_info(app) {
+        Ok(info) => info,
+        Err(e) => {
+            log::error!("[Api] load settings failed: {}", e);
+            return;
+        }
+    };
+    if !info.enabled {
+        return;
+    }
+    let state = ApiState {
+        app: app.clone(),
+        pool: app.state::<Db>().0.clone(),
+        token: info.token,
+    };
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = serve(state, info.port).await {
+            log::error!("{}", e);
+        }
+    });
+}
+
+/// 回傳埠號與權杖供設定頁面顯示
+#[tauri::command]
+pub fn get_api_info(app: tauri::AppHandle) -> Result<ApiInfo, String> {
+    load_info(&app)
+}
+
+/// 重新產生權杖，舊權杖於重新啟動後失效
+#[tauri::command]
+pub fn reset_api_token(app: tauri::AppHandle) -> Result<ApiInfo, String> {
+    settings::set(&app, TOKEN_KEY, generate_token()?)?;
+    load_info(&app)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inline_prompt_rejects_raw_sql() {
+        let body = r#"{"prompt": {"name": "x", "sql": "DELETE FROM stock"}}"#;
+        assert!(serde_json::from_str::<ScreenBody>(body).is_err());
+
+        let body = r#"{"prompt": {"fundamentals": ["pe < 15"], "rankings": ["roe desc top 10"]}}"#;
+        let template: PromptTemplate = serde_json::from_str::<ScreenBody>(body)
+            .unwrap()
+            .prompt
+            .unwrap()
+            .into();
+        assert!(template.sql.is_empty());
+        assert!(template.compile().is_ok());
+
+        // 白名單外的欄位在編譯時拒絕
+        let body = r#"{"prompt": {"fundamentals": ["1 = 1; DROP TABLE stock"]}}"#;
+        let template: PromptTemplate = serde_json::from_str::<ScreenBody>(body)
+            .unwrap()
+            .prompt
+            .unwrap()
+            .into();
+        assert!(template.compile().is_err());
+    }
+
+    #[test]
+    fn rejects_bodies_over_limit_without_content_length() {
+        tauri::async_runtime::block_on(async {
+            let body = Full::new(Bytes::from(vec![b' '; MAX_BODY_BYTES]));
+            assert_eq!(read_body(body).await.unwrap().len(), MAX_BODY_BYTES);
+
+            // 不看 Content-Length，實際內容超過上限即拒絕
+            let body = Full::new(Bytes::from(vec![b' '; MAX_BODY_BYTES + 1]));
+            let (status, _) = read_body(body).await.unwrap_err();
+            assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+        });
+    }
+
+    #[test]
+    fn generates_random_hex_tokens() {
+        let token = generate_token().unwrap();
+        assert_eq!(token.len(), 32);
+        assert!(token.bytes().all(|b| b.is_ascii_hexdigit()));
+        assert_ne!(token, generate_token().unwrap());
+    }
+}
@@ -0,0 +1,274 @@
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use serde::Serialize;
+use sqlx::SqlitePool;
+
+use crate::calendar::{self, TradingCalendar};
+use crate::portfolio;
+use crate::screen::{self, DateContext, PromptTemplate};
+use crate::types::{Bar, Timeframe};
+
+/// 回測參數，百分比欄位以 % 表示 (例如 8 代表 8%)
+#[derive(Debug, Clone)]
+pub struct BacktestOptions {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// 最長持有交易日數，到期以收盤價出場
+    pub hold_days: usize,
+    pub capital: f64,
+    /// 同時持有的股票數，每檔配置 capital / max_positions
+    pub max_positions: usize,
+    pub stop_loss: Option<f64>,
+    pub take_profit: Option<f64>,
+    pub fee_discount: f64,
+    pub min_fee: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExitReason {
+    Hold,
+    StopLoss,
+    TakeProfit,
+    /// 回測結束時仍持有，以最後收盤價結算
+    End,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BacktestTrade {
+    pub stock_id: String,
+    pub stock_name: Option<String>,
+    pub buy_date: String,
+    pub buy_price: f64,
+    pub sell_date: String,
+    pub sell_price: f64,
+    pub quantity: i64,
+    /// 扣除手續費與交易稅後的損益
+    pub profit: f64,
+    pub return_pct: f64,
+    pub reason: ExitReason,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BacktestReport {
+    pub name: String,
+    pub from: String,
+    pub to: String,
+    pub capital: f64,
+    pub final_equity: f64,
+    pub return_pct: f64,
+    pub win_rate: f64,
+    pub max_drawdown_pct: f64,
+    pub trades: Vec<BacktestTrade>,
+}
+
+#[derive(Debug, Clone)]
+struct Position {
+    stock_id: String,
+    entry_index: usize,
+    buy_date: String,
+    buy_price: f64,
+    quantity: i64,
+    /// 含買進手續費的成本
+    cost: f64,
+    last_close: f64,
+}
+
+async fn bar_on(pool: &SqlitePool, stock_id: &str, t: &str) -> Result<Option<Bar>, String> {
+    sqlx::query_as::<_, (String, f64, f64, f64, f64, i64)>(&format!(
+        "SELECT t, o, h, l, c, v FROM {} WHERE stock_id = $1 AND t = $2",
+        Timeframe::Daily.deal_table()
+    ))
+    .bind(stock_id)
+    .bind(t)
+    .fetch_optional(pool)
+    .await
+    .map(|row| row.map(|(t, o, h, l, c, v)| Bar { t, o, h, l, c, v }))
+    .map_err(|e| format!("[Backtest] load bar error: {}", e))
+}
+
+/// 當日收盤後的選股結果，交易日資料不足時視為無訊號
+async fn signals(
+    pool: &SqlitePool,
+    template: &PromptTemplate,
+    day: NaiveDate,
+) -> Result<Vec<String>, String> {
+    let Some(dates) = DateContext::load(pool, Some(day)).await? else {
+        return Ok(Vec::new());
+    };
+    // 本地缺當日資料時 DateContext 會落在前一交易日，不可重複使用舊訊號
+    if dates.today() != calendar::format_day(day) {
+        return Ok(Vec::new());
+    }
+    match dates.render(&template.compile()?) {
+        Some(sql) => screen::matched_stock_ids(pool, &sql, day, template.is_ranked()).await,
+        None => Ok(Vec::new()),
+    }
+}
+
+impl BacktestOptions {
+    fn sell(&self, position: &Position, price: f64) -> (f64, f64) {
+        let value = position.quantity as f64 * price;
+        let fee = portfolio::broker_fee(value, self.fee_discount, self.min_fee);
+        // 選股範圍僅含同步 K 線的股票與 TDR，一律以股票稅率計算
+        let tax = portfolio::transaction_tax("stock", &position.stock_id, value, false);
+        let proceeds = value - fee - tax;
+        (proceeds, proceeds - position.cost)
+    }
+
+    /// 依當日 K 線判斷出場價，停損 / 停利遇跳空時以開盤價成交
+    fn exit_price(&self, position: &Position, bar: &Bar, held: usize) -> Option<(f64, ExitReason)> {
+        if let Some(stop) = self.stop_loss {
+            let price = position.buy_price * (1.0 - stop / 100.0);
+            if bar.l <= price {
+                return Some((price.min(bar.o), ExitReason::StopLoss));
+            }
+        }
+        if let Some(target) = self.take_profit {
+            let price = position.buy_price * (1.0 + target / 100.0);
+            if bar.h >= price {
+                return Some((price.max(bar.o), ExitReason::TakeProfit));
+            }
+        }
+        (held >= self.hold_days).then_some((bar.c, ExitReason::Hold))
+    }
+}
+
+/// 每日收盤後選股，隔一交易日開盤買進，持有到停損、停利或到期。
+/// 只使用當日以前的資料產生訊號，避免前視偏誤。
+pub async fn run(
+    pool: &SqlitePool,
+    template: &PromptTemplate,
+    options: &BacktestOptions,
+) -> Result<BacktestReport, String> {
+    let calendar = TradingCalendar::load(pool).await?;
+    let sessions: Vec<NaiveDate> = calendar
+        .sessions_between(options.from, options.to)
+        .collect();
+    let names: BTreeMap<String, Option<String>> =
+        sqlx::query_as("SELECT stock_id, stock_name FROM stock")
+            .fetch_all(pool)
+            .await
+            .map_err(|e| format!("[Backtest] load stocks error: {}", e))?
+            .into_iter()
+            .collect();
+    let allocation = options.capital / options.max_positions.max(1) as f64;
+
+    let mut cash = options.capital;
+    let mut positions: Vec<Position> = Vec::new();
+    let mut pending: Vec<String> = Vec::new();
+    let mut trades = Vec::new();
+    let mut peak = options.capital;
+    let mut max_drawdown: f64 = 0.0;
+
+    let mut close_position = |position: Position, t: &str, price: f64, reason: ExitReason| {
+        let (proceeds, profit) = options.sell(&position, price);
+        trades.push(BacktestTrade {
+            stock_name: names.get(&position.stock_id).cloned().flatten(),
+            stock_id: position.stock_id,
+            buy_date: position.buy_date,
+            buy_price: position.buy_price,
+            sell_date: t.to_string(),
+            sell_price: price,
+            quantity: position.quantity,
+            profit,
+            return_pct: profit / position.cost * 100.0,
+            reason,
+        });
+        proceeds
+    };
+
+    for (i, day) in sessions.iter().enumerate() {
+        let t = calendar::format_day(*day);
+
+        // 出場
+        let mut remaining = Vec::with_capacity(positions.len());
+        for mut position in positions {
+            let Some(bar) = bar_on(pool, &position.stock_id, &t).await? else {
+                // 停牌日沿用前一收盤價
+                remaining.push(position);
+                continue;
+            };
+            position.last_close = bar.c;
+            match options.exit_price(&position, &bar, i - position.entry_index) {
+                Some((price, reason)) => cash += close_position(position, &t, price, reason),
+                None => remaining.push(position),
+            }
+        }
+        positions = remaining;
+
+        // 以開盤價執行前一交易日的訊號
+        for stock_id in std::mem::take(&mut pending) {
+            if positions.len() >= options.max_positions {
+                break;
+            }
+            let Some(bar) = bar_on(pool, &stock_id, &t).await? else {
+                continue;
+            };
+            let quantity = (allocation.min(cash) / bar.o).floor() as i64;
+            if quantity <= 0 {
+                continue;
+            }
+            let value = quantity as f64 * bar.o;
+            let cost = value + portfolio::broker_fee(value, options.fee_discount, options.min_fee);
+            if cost > cash {
+                continue;
+            }
+            cash -= cost;
+            positions.push(Position {
+                stock_id,
+                entry_index: i,
+                buy_date: t.clone(),
+                buy_price: bar.o,
+                quantity,
+                cost,
+                last_close: bar.c,
+            });
+        }
+
+        // 收盤後選股，排除已持有的股票
+        if i + 1 < sessions.len() {
+            pending = signals(pool, template, *day)
+                .await?
+                .into_iter()
+                .filter(|id| !positions.iter().any(|p| &p.stock_id == id))
+                .collect();
+        }
+
+        let equity = cash
+            + positions
+                .iter()
+                .map(|p| p.quantity as f64 * p.last_close)
+                .sum::<f64>();
+        peak = peak.max(equity);
+        if peak > 0.0 {
+            max_drawdown = max_drawdown.max((peak - equity) / peak * 100.0);
+        }
+    }
+
+    if let Some(last) = sessions.last() {
+        let t = calendar::format_day(*last);
+        for position in positions {
+            let price = position.last_close;
+            cash += close_position(position, &t, price, ExitReason::End);
+        }
+    }
+
+    let wins = trades.iter().filter(|trade| trade.profit > 0.0).count();
+    Ok(BacktestReport {
+        name: template.name.clone(),
+        from: calendar::format_day(options.from),
+        to: calendar::format_day(options.to),
+        capital: options.capital,
+        final_equity: cash,
+        return_pct: (cash - options.capital) / options.capital * 100.0,
+        win_rate: if trades.is_empty() {
+            0.0
+        } else {
+            wins as f64 / trades.len() as f64 * 100.0
+        },
+        max_drawdown_pct: max_drawdown,
+        trades,
+    })
+}
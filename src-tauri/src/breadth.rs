@@ -0,0 +1,224 @@
+use chrono::Duration;
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tauri::State;
+
+use crate::calendar;
+use crate::listing;
+use crate::sqlite::connection::Db;
+use crate::sqlite::writer;
+
+/// 可在選股條件中當作大盤濾網的欄位
+pub const BREADTH_COLUMNS: &[&str] = &[
+    "stock_count",
+    "advancers",
+    "decliners",
+    "unchanged",
+    "above_ma20_ratio",
+    "above_ma60_ratio",
+    "new_highs",
+    "new_lows",
+    "up_volume",
+    "down_volume",
+    "up_down_volume_ratio",
+];
+/// 每次同步重算的最近交易日數，涵蓋補抓或修正過的日 K
+const RECENT_DAYS: i64 = 5;
+/// 創新高/新低的比較區間 (約 52 週)
+const HIGH_LOW_BARS: i64 = 240;
+/// 往前載入的日曆天數，需足夠涵蓋 HIGH_LOW_BARS 根日 K
+const LOOKBACK_DAYS: i64 = 400;
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct MarketBreadth {
+    pub t: String,
+    pub stock_count: i64,
+    pub advancers: i64,
+    pub decliners: i64,
+    pub unchanged: i64,
+    pub above_ma20_ratio: Option<f64>,
+    pub above_ma60_ratio: Option<f64>,
+    pub new_highs: i64,
+    pub new_lows: i64,
+    pub up_volume: i64,
+    pub down_volume: i64,
+    pub up_down_volume_ratio: Option<f64>,
+}
+
+/// 重算 `from` (YYYYMMDD，含) 之後每個交易日的市場寬度，空字串為全部歷史
+pub async fn compute(pool: &SqlitePool, from: &str) -> Result<u64, String> {
+    let lookback = calendar::parse_day(from)
+        .map(|day| calendar::format_day(day - Duration::days(LOOKBACK_DAYS)))
+        .unwrap_or_default();
+    let sql = format!(
+        "WITH d AS (
+            SELECT dd.stock_id, dd.t, dd.c, dd.v,
+                LAG(dd.c) OVER w AS prev_c,
+                MAX(dd.h) OVER (w ROWS BETWEEN {bars} PRECEDING AND 1 PRECEDING) AS prev_high,
+                MIN(dd.l) OVER (w ROWS BETWEEN {bars} PRECEDING AND 1 PRECEDING) AS prev_low,
+                COUNT(*) OVER (w ROWS BETWEEN {bars} PRECEDING AND 1 PRECEDING) AS history
+            FROM daily_deal dd
+            JOIN stock s ON s.stock_id = dd.stock_id AND s.{filter}
+            WHERE dd.t >= $1
+            WINDOW w AS (PARTITION BY dd.stock_id ORDER BY dd.t)
+        )
+        INSERT OR REPLACE INTO market_breadth (
+            t, stock_count, advancers, decliners, unchanged, above_ma20_ratio, above_ma60_ratio,
+            new_highs, new_lows, up_volume, down_volume, up_down_volume_ratio
+        )
+        SELECT
+            d.t,
+            COUNT(*),
+            SUM(d.c > d.prev_c),
+            SUM(d.c < d.prev_c),
+            SUM(d.c = d.prev_c),
+            AVG(CASE WHEN k.ma20 IS NOT NULL THEN d.c > k.ma20 END) * 100,
+            AVG(CASE WHEN k.ma60 IS NOT NULL THEN d.c > k.ma60 END) * 100,
+            SUM(d.history = {bars} AND d.c > d.prev_high),
+            SUM(d.history = {bars} AND d.c < d.prev_low),
+            SUM(CASE WHEN d.c > d.prev_c THEN d.v ELSE 0 END),
+            SUM(CASE WHEN d.c < d.prev_c THEN d.v ELSE 0 END),
+            CAST(SUM(CASE WHEN d.c > d.prev_c THEN d.v ELSE 0 END) AS REAL)
+                / NULLIF(SUM(CASE WHEN d.c < d.prev_c THEN d.v ELSE 0 END), 0)
+        FROM d
+        LEFT JOIN daily_skills k ON k.stock_id = d.stock_id AND k.t = d.t
+        WHERE d.t >= $2 AND d.prev_c IS NOT NULL
+        GROUP BY d.t",
+        bars = HIGH_LOW_BARS,
+        filter = listing::SYNCED_TYPES_FILTER
+    );
+    let rows = sqlx::query(&sql)
+        .bind(lookback)
+        .bind(from)
+        .execute(pool)
+        .await
+        .map(|result| result.rows_affected())
+        .map_err(|e| format!("[Breadth] compute error: {}", e))?;
+    writer::bump_versions(pool, &["market_breadth"]).await?;
+    Ok(rows)
+}
+
+/// 同步完成後重算最近幾個交易日，並補齊上次計算之後缺少的日期
+pub async fn on_sync_success(pool: &SqlitePool) -> Result<(), String> {
+    let latest: Option<String> = sqlx::query_scalar("SELECT MAX(t) FROM market_breadth")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("[Breadth] load latest error: {}", e))?;
+    let recent: Option<String> = sqlx::query_scalar(
+        "SELECT MIN(t) FROM (SELECT DISTINCT t FROM daily_deal ORDER BY t DESC LIMIT $1)",
+    )
+    .bind(RECENT_DAYS)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| format!("[Breadth] load dates error: {}", e))?;
+    let from = match (latest, recent) {
+        (Some(latest), Some(recent)) => latest.min(recent),
+        // 尚未計算過時從頭計算全部歷史
+        _ => String::new(),
+    };
+    let days = compute(pool, &from).await?;
+    log::info!("[Breadth] computed {} days from {}", days, from);
+    Ok(())
+}
+
+/// `from` / `to` 為 YYYYMMDD (含)，未指定時不限
+#[tauri::command]
+pub async fn get_market_breadth(
+    db: State<'_, Db>,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<Vec<MarketBreadth>, String> {
+    sqlx::query_as(
+        "SELECT * FROM market_breadth
+         WHERE ($1 IS NULL OR t >= $1) AND ($2 IS NULL OR t <= $2)
+         ORDER BY t",
+    )
+    .bind(from)
+    .bind(to)
+    .fetch_all(&db.0)
+    .await
+    .map_err(|e| format!("[Breadth] query error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sqlite::connection;
+    use crate::types::{Bar, Timeframe};
+
+    const LAST_DAY: &str = "20241007";
+
+    /// 連續 `count` 天收盤價皆為 10，最後一天改為 `last` 並帶入成交量
+    async fn seed(
+        pool: &SqlitePool,
+        stock_id: &str,
+        security_type: &str,
+        count: i64,
+        last: f64,
+        v: i64,
+    ) {
+        sqlx::query("INSERT INTO stock (stock_id, stock_name, security_type) VALUES ($1, $1, $2)")
+            .bind(stock_id)
+            .bind(security_type)
+            .execute(pool)
+            .await
+            .unwrap();
+        let end = calendar::parse_day(LAST_DAY).unwrap();
+        let bars: Vec<Bar> = (0..count)
+            .map(|i| {
+                let c = if i == count - 1 { last } else { 10.0 };
+                Bar {
+                    t: calendar::format_day(end - Duration::days(count - 1 - i)),
+                    o: c,
+                    h: c,
+                    l: c,
+                    c,
+                    v,
+                }
+            })
+            .collect();
+        writer::save_deals(pool, Timeframe::Daily, stock_id, &bars)
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn computes_one_day_of_breadth() {
+        tauri::async_runtime::block_on(async {
+            let pool = connection::memory().await;
+            connection::migrate(&pool, ..).await;
+            let full = HIGH_LOW_BARS + 2;
+            // A 創 240 日新高、B 創新低、C 上漲但歷史不足 240 根、D 平盤，ETF 不計入
+            seed(&pool, "A", "stock", full, 20.0, 1000).await;
+            seed(&pool, "B", "stock", full, 5.0, 400).await;
+            seed(&pool, "C", "tdr", 10, 30.0, 600).await;
+            seed(&pool, "D", "stock", full, 10.0, 50).await;
+            seed(&pool, "E", "etf", full, 30.0, 9999).await;
+            sqlx::query(
+                "INSERT INTO daily_skills (stock_id, t, ma20, ma60) VALUES
+                    ('A', $1, 15, 25), ('B', $1, 8, NULL), ('D', $1, 9, 9)",
+            )
+            .bind(LAST_DAY)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+            assert_eq!(compute(&pool, LAST_DAY).await.unwrap(), 1);
+            let row: MarketBreadth = sqlx::query_as("SELECT * FROM market_breadth")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+            assert_eq!(row.t, LAST_DAY);
+            assert_eq!(
+                (row.stock_count, row.advancers, row.decliners, row.unchanged),
+                (4, 2, 1, 1)
+            );
+            // MA20 有值的 A、B、D 中 A、D 站上；MA60 有值的 A、D 中只有 D 站上
+            assert!((row.above_ma20_ratio.unwrap() - 200.0 / 3.0).abs() < 1e-9);
+            assert_eq!(row.above_ma60_ratio, Some(50.0));
+            assert_eq!((row.new_highs, row.new_lows), (1, 1));
+            assert_eq!((row.up_volume, row.down_volume), (1600, 400));
+            assert_eq!(row.up_down_volume_ratio, Some(4.0));
+        });
+    }
+}
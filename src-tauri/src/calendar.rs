@@ -0,0 +1,234 @@
+use std::collections::BTreeSet;
+use std::ops::RangeInclusive;
+
+use chrono::{Datelike, Duration, NaiveDate};
+use sqlx::SqlitePool;
+
+/// 當日成交檔數低於全市場最高值的此比例，視為資料殘缺而非交易日
+const SESSION_COVERAGE: f64 = 0.1;
+
+/// 已收錄完整休市日的年份，這些年份的平日除休市日外一律為交易日
+const HOLIDAY_YEARS: RangeInclusive<i32> = 2023..=2025;
+/// 證交所平日休市日：國定假日、春節封關 (含僅交割不交易日) 與颱風停市
+#[rustfmt::skip]
+const HOLIDAYS: &[&str] = &[
+    // 2023
+    "20230102", "20230118", "20230119", "20230120", "20230123", "20230124", "20230125",
+    "20230126", "20230127", "20230227", "20230228", "20230403", "20230404", "20230405",
+    "20230501", "20230622", "20230623", "20230929", "20231009", "20231010",
+    // 2024
+    "20240101", "20240206", "20240207", "20240208", "20240209", "20240212", "20240213",
+    "20240214", "20240228", "20240404", "20240405", "20240501", "20240610", "20240724",
+    "20240725", "20240917", "20241002", "20241003", "20241010", "20241031",
+    // 2025
+    "20250101", "20250123", "20250124", "20250127", "20250128", "20250129", "20250130",
+    "20250131", "20250228", "20250403", "20250404", "20250501", "20250530", "20250929",
+    "20251006", "20251010", "20251024", "20251225",
+];
+
+/// 解析 YYYYMMDD (或 YYYYMMDDHHmm 前 8 碼) 為日期
+pub fn parse_day(t: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(t.get(0..8)?, "%Y%m%d").ok()
+}
+
+pub fn format_day(date: NaiveDate) -> String {
+    date.format("%Y%m%d").to_string()
+}
+
+fn is_weekday(date: NaiveDate) -> bool {
+    date.weekday().num_days_from_monday() < 5
+}
+
+fn is_holiday(date: NaiveDate) -> bool {
+    HOLIDAYS.contains(&format_day(date).as_str())
+}
+
+/// 台股交易日曆。
+///
+/// 收錄休市日的年份 (`HOLIDAY_YEARS`) 以休市表為準，整個市場都沒有資料的平日仍是交易日，缺漏檢查才找得到同步失敗的日子。
+/// 其餘年份以 `daily_deal` 中全市場實際有成交的日期推得：有足夠多的股票有 K 線即為交易日 (含補班日)；
+/// 前後都有交易日、不在休市表卻沒有資料的平日列為可疑日，無從判斷是否開盤。
+#[derive(Debug, Clone, Default)]
+pub struct TradingCalendar {
+    sessions: BTreeSet<NaiveDate>,
+    suspects: BTreeSet<NaiveDate>,
+}
+
+impl TradingCalendar {
+    pub fn from_sessions(sessions: impl IntoIterator<Item = NaiveDate>) -> Self {
+        Self {
+            sessions: sessions.into_iter().collect(),
+            suspects: BTreeSet::new(),
+        }
+    }
+
+    /// 以有成交的日期補上休市表涵蓋年份的平日，並標出其他年份的可疑日
+    fn infer(covered: BTreeSet<NaiveDate>) -> Self {
+        let (Some(first), Some(last)) = (covered.first().copied(), covered.last().copied()) else {
+            return Self::default();
+        };
+        let mut calendar = Self::default();
+        let mut day = first;
+        while day <= last {
+            if covered.contains(&day) {
+                calendar.sessions.insert(day);
+            } else if is_weekday(day) && !is_holiday(day) {
+                if HOLIDAY_YEARS.contains(&day.year()) {
+                    calendar.sessions.insert(day);
+                } else {
+                    calendar.suspects.insert(day);
+                }
+            }
+            day += Duration::days(1);
+        }
+        calendar
+    }
+
+    pub async fn load(pool: &SqlitePool) -> Result<Self, String> {
+        let rows: Vec<(String, i64)> =
+            sqlx::query_as("SELECT t, COUNT(*) AS n FROM daily_deal GROUP BY t")
+                .fetch_all(pool)
+                .await
+                .map_err(|e| format!("[Calendar] load error: {}", e))?;
+        let max = rows.iter().map(|(_, n)| *n).max().unwrap_or(0);
+        let threshold = ((max as f64 * SESSION_COVERAGE).ceil() as i64).max(1);
+        Ok(Self::infer(
+            rows.into_iter()
+                .filter(|(_, n)| *n >= threshold)
+                .filter_map(|(t, _)| parse_day(&t))
+                .collect(),
+        ))
+    }
+
+    /// 是否為交易日，超出本地資料涵蓋範圍或為可疑日時無從判斷而回傳 None
+    pub fn is_session(&self, date: NaiveDate) -> Option<bool> {
+        let (first, last) = (self.sessions.first()?, self.sessions.last()?);
+        if self.suspects.contains(&date) {
+            return None;
+        }
+        (*first..=*last)
+            .contains(&date)
+            .then(|| self.sessions.contains(&date))
+    }
+
+    /// [from, to] 區間內的可疑日：全市場沒有資料、也不在休市表中的平日
+    pub fn suspects_between(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> impl Iterator<Item = NaiveDate> + '_ {
+        self.suspects.range(from..=to).copied()
+    }
+
+    /// [from, to] 區間內的所有交易日
+    pub fn sessions_between(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> impl Iterator<Item = NaiveDate> + '_ {
+        self.sessions.range(from..=to).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sqlite::connection;
+
+    #[test]
+    fn infers_sessions_from_market_coverage() {
+        tauri::async_runtime::block_on(async {
+            let pool = connection::memory().await;
+            connection::migrate(&pool, ..).await;
+            // 未收錄休市表的年份：10/3 全市場 20 檔；10/4 只有 2 檔 (剛好 10%) 仍為交易日；
+            // 10/5 只有 1 檔視為殘缺，與完全沒有資料的 10/6 同為可疑日
+            let mut rows = Vec::new();
+            for i in 0..20 {
+                rows.push((format!("{:04}", 1000 + i), "20221003"));
+            }
+            rows.push(("1000".to_string(), "20221004"));
+            rows.push(("1001".to_string(), "20221004"));
+            rows.push(("1000".to_string(), "20221005"));
+            rows.push(("1000".to_string(), "20221007"));
+            rows.push(("1001".to_string(), "20221007"));
+            for (stock_id, t) in rows {
+                sqlx::query("INSERT INTO daily_deal (stock_id, t, c, o, h, l, v) VALUES ($1, $2, 1, 1, 1, 1, 1)")
+                    .bind(stock_id)
+                    .bind(t)
+                    .execute(&pool)
+                    .await
+                    .unwrap();
+            }
+
+            let calendar = TradingCalendar::load(&pool).await.unwrap();
+            let day = |t: &str| parse_day(t).unwrap();
+            assert_eq!(calendar.is_session(day("20221003")), Some(true));
+            assert_eq!(calendar.is_session(day("20221004")), Some(true));
+            assert_eq!(calendar.is_session(day("20221005")), None);
+            assert_eq!(calendar.is_session(day("20221006")), None);
+            assert_eq!(calendar.is_session(day("20221007")), Some(true));
+            // 超出本地資料範圍無從判斷
+            assert_eq!(calendar.is_session(day("20220930")), None);
+            assert_eq!(calendar.is_session(day("20221011")), None);
+            let sessions: Vec<String> = calendar
+                .sessions_between(day("20221001"), day("20221031"))
+                .map(format_day)
+                .collect();
+            assert_eq!(sessions, ["20221003", "20221004", "20221007"]);
+            let suspects: Vec<String> = calendar
+                .suspects_between(day("20221001"), day("20221031"))
+                .map(format_day)
+                .collect();
+            assert_eq!(suspects, ["20221005", "20221006"]);
+        });
+    }
+
+    async fn insert_days(pool: &SqlitePool, days: &[&str]) {
+        for t in days {
+            sqlx::query("INSERT INTO daily_deal (stock_id, t, c, o, h, l, v) VALUES ('1000', $1, 1, 1, 1, 1, 1)")
+                .bind(t)
+                .execute(pool)
+                .await
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn holiday_years_keep_unsynced_weekdays_as_sessions() {
+        tauri::async_runtime::block_on(async {
+            let pool = connection::memory().await;
+            connection::migrate(&pool, ..).await;
+            // 2024/10/2、10/3 颱風停市，10/10 國慶日；10/8 全市場沒有資料屬同步失敗
+            insert_days(
+                &pool,
+                &["20241001", "20241004", "20241007", "20241009", "20241011"],
+            )
+            .await;
+            let calendar = TradingCalendar::load(&pool).await.unwrap();
+            let day = |t: &str| parse_day(t).unwrap();
+            assert_eq!(calendar.is_session(day("20241002")), Some(false));
+            assert_eq!(calendar.is_session(day("20241008")), Some(true));
+            assert_eq!(calendar.is_session(day("20241010")), Some(false));
+            let sessions: Vec<String> = calendar
+                .sessions_between(day("20241001"), day("20241031"))
+                .map(format_day)
+                .collect();
+            assert_eq!(
+                sessions,
+                ["20241001", "20241004", "20241007", "20241008", "20241009", "20241011"]
+            );
+            assert_eq!(
+                calendar
+                    .suspects_between(day("20241001"), day("20241031"))
+                    .count(),
+                0
+            );
+        });
+    }
+
+    #[test]
+    fn empty_calendar_is_unknown() {
+        let calendar = TradingCalendar::default();
+        assert_eq!(calendar.is_session(parse_day("20241007").unwrap()), None);
+    }
+}
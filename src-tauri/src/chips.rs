@@ -0,0 +1,555 @@
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{QueryBuilder, Sqlite, SqlitePool};
+use tauri::State;
+use tauri_plugin_http::reqwest;
+
+use crate::calendar;
+use crate::listing;
+use crate::settings;
+use crate::sqlite::connection::Db;
+use crate::sqlite::writer;
+
+/// store.json 中的資料來源設定，未設定時使用 `ChipSources::default`
+pub const SOURCES_KEY: &str = "chipSources";
+/// 同步後補齊最近幾個交易日的資料
+const RECENT_DAYS: i64 = 5;
+/// 證交所短時間內大量請求會被暫時封鎖
+const REQUEST_INTERVAL_SECS: u64 = 3;
+
+/// 策略編譯器可引用的欄位
+pub const INSTITUTIONAL_COLUMNS: &[&str] = &["foreign_net", "trust_net", "dealer_net", "total_net"];
+pub const MARGIN_COLUMNS: &[&str] = &[
+    "margin_buy",
+    "margin_sell",
+    "margin_balance",
+    "short_sell",
+    "short_buy",
+    "short_balance",
+];
+
+/// 下載網址樣板，`{date}` 為 YYYYMMDD、`{roc_date}` 為民國年 115/10/16、`{iso_date}` 為 2026-10-16
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ChipSources {
+    pub twse_institutional: String,
+    pub tpex_institutional: String,
+    pub twse_margin: String,
+    pub tpex_margin: String,
+}
+
+impl Default for ChipSources {
+    fn default() -> Self {
+        Self {
+            twse_institutional:
+                "https://www.twse.com.tw/rwd/zh/fund/T86?date={date}&selectType=ALLBUT0999&response=json"
+                    .to_string(),
+            tpex_institutional:
+                "https://www.tpex.org.tw/web/stock/3insti/daily_trade/3itrade_hedge_result.php?l=zh-tw&o=csv&se=EW&t=D&d={roc_date}"
+                    .to_string(),
+            twse_margin:
+                "https://www.twse.com.tw/rwd/zh/marginTrading/MI_MARGN?date={date}&selectType=ALL&response=json"
+                    .to_string(),
+            tpex_margin:
+                "https://www.tpex.org.tw/web/stock/margin_trading/margin_balance/margin_bal_result.php?l=zh-tw&o=csv&d={roc_date}"
+                    .to_string(),
+        }
+    }
+}
+
+impl ChipSources {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        settings::get(app, SOURCES_KEY).unwrap_or_default()
+    }
+}
+
+fn source_url(template: &str, day: NaiveDate) -> String {
+    template
+        .replace("{date}", &calendar::format_day(day))
+        .replace(
+            "{roc_date}",
+            &format!("{}/{:02}/{:02}", day.year() - 1911, day.month(), day.day()),
+        )
+        .replace("{iso_date}", &day.format("%Y-%m-%d").to_string())
+}
+
+/// 三大法人買賣超 (股)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct InstitutionalFlow {
+    pub stock_id: String,
+    /// 外資及陸資 (不含外資自營商)
+    pub foreign_net: i64,
+    /// 投信
+    pub trust_net: i64,
+    /// 自營商 (自行買賣 + 避險)
+    pub dealer_net: i64,
+    pub total_net: i64,
+}
+
+/// 融資融券 (張)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MarginBalance {
+    pub stock_id: String,
+    pub margin_buy: i64,
+    pub margin_sell: i64,
+    pub margin_balance: i64,
+    pub short_sell: i64,
+    pub short_buy: i64,
+    pub short_balance: i64,
+}
+
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct ChipsReport {
+    pub t: String,
+    pub institutional: usize,
+    pub margin: usize,
+}
+
+/// 表頭與資料列，JSON 與 CSV 皆轉為此格式再依欄名取值
+struct Table {
+    fields: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+fn is_stock_id(text: &str) -> bool {
+    (4..=6).contains(&text.len()) && text.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+fn is_id_header(field: &str) -> bool {
+    matches!(field, "代號" | "證券代號" | "股票代號")
+}
+
+fn shares(text: &str) -> Option<i64> {
+    let text = text.trim().replace(',', "");
+    if text.is_empty() || text == "-" {
+        return Some(0);
+    }
+    text.parse::<f64>().ok().map(|v| v as i64)
+}
+
+fn cell_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.trim().to_string(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// 證交所 rwd JSON：單一表格放在最上層 `fields` / `data`，多表格放在 `tables`
+fn json_tables(text: &str) -> Result<Vec<Table>, String> {
+    let json: Value =
+        serde_json::from_str(text).map_err(|e| format!("[Chips] invalid JSON: {}", e))?;
+    if let Some(stat) = json.get("stat").and_then(Value::as_str) {
+        if stat != "OK" {
+            // 假日或尚未公布時 stat 為說明文字
+            return Ok(Vec::new());
+        }
+    }
+    let mut tables = Vec::new();
+    let candidates = std::iter::once(&json).chain(
+        json.get("tables")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten(),
+    );
+    for table in candidates {
+        let (Some(fields), Some(data)) = (
+            table.get("fields").and_then(Value::as_array),
+            table.get("data").and_then(Value::as_array),
+        ) else {
+            continue;
+        };
+        tables.push(Table {
+            fields: fields.iter().map(cell_text).collect(),
+            rows: data
+                .iter()
+                .filter_map(Value::as_array)
+                .map(|row| row.iter().map(cell_text).collect())
+                .collect(),
+        });
+    }
+    Ok(tables)
+}
+
+/// 櫃買中心 CSV：前幾列為標題，表頭列第一欄為「代號」，表尾有合計與說明
+fn csv_tables(text: &str) -> Vec<Table> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+    let mut tables: Vec<Table> = Vec::new();
+    for record in reader.records().flatten() {
+        let cells: Vec<String> = record.iter().map(|c| c.trim().to_string()).collect();
+        let Some(first) = cells.first() else {
+            continue;
+        };
+        if is_id_header(first) {
+            tables.push(Table {
+                fields: cells,
+                rows: Vec::new(),
+            });
+        } else if let Some(table) = tables.last_mut() {
+            table.rows.push(cells);
+        }
+    }
+    tables
+}
+
+fn decode(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.trim_start_matches('\u{feff}').to_string(),
+        Err(_) => listing::decode_big5(bytes),
+    }
+}
+
+fn parse_tables(text: &str) -> Result<Vec<Table>, String> {
+    if text.trim_start().starts_with('{') {
+        json_tables(text)
+    } else {
+        Ok(csv_tables(text))
+    }
+}
+
+/// 第一個符合條件的欄位位置
+fn position(fields: &[String], predicate: impl Fn(&str) -> bool) -> Option<usize> {
+    fields.iter().position(|f| predicate(f))
+}
+
+/// 欄名完全相同者優先，否則取第 `nth` 個名為 `fallback` 的欄位 (證交所融資、融券欄名重複)
+fn named_column(fields: &[String], exact: &str, fallback: &str, nth: usize) -> Option<usize> {
+    position(fields, |f| f == exact).or_else(|| {
+        fields
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| *f == fallback)
+            .nth(nth)
+            .map(|(i, _)| i)
+    })
+}
+
+fn rows_with_id(table: &Table) -> impl Iterator<Item = &Vec<String>> {
+    table
+        .rows
+        .iter()
+        .filter(|row| row.first().is_some_and(|id| is_stock_id(id)))
+}
+
+/// 解析證交所 T86 或櫃買中心三大法人 CSV，依欄名判斷各法人的買賣超欄位
+pub fn parse_institutional(text: &str) -> Result<Vec<InstitutionalFlow>, String> {
+    let tables = parse_tables(text)?;
+    let Some(table) = tables
+        .iter()
+        .find(|t| t.fields.first().is_some_and(|f| is_id_header(f)))
+    else {
+        return Ok(Vec::new());
+    };
+    let fields = &table.fields;
+    let net = |f: &str| f.contains("買賣超");
+    let columns = (
+        position(fields, |f| net(f) && f.contains('外')),
+        position(fields, |f| net(f) && f.contains("投信")),
+        // 總計欄沒有括號，排除「自營商(自行買賣)」、「自營商(避險)」與外資自營商
+        position(fields, |f| {
+            net(f) && f.contains("自營商") && !f.contains('外') && !f.contains(['(', '（'])
+        }),
+        position(fields, |f| f.contains("三大法人")),
+    );
+    let (Some(foreign), Some(trust), Some(dealer), Some(total)) = columns else {
+        return Err(format!(
+            "[Chips] unknown institutional columns: {}",
+            fields.join(",")
+        ));
+    };
+    let value = |row: &[String], i: usize| row.get(i).and_then(|t| shares(t)).unwrap_or(0);
+    Ok(rows_with_id(table)
+        .map(|row| InstitutionalFlow {
+            stock_id: row[0].clone(),
+            foreign_net: value(row, foreign),
+            trust_net: value(row, trust),
+            dealer_net: value(row, dealer),
+            total_net: value(row, total),
+        })
+        .collect())
+}
+
+/// 解析證交所 MI_MARGN 或櫃買中心融資融券餘額 CSV
+pub fn parse_margin(text: &str) -> Result<Vec<MarginBalance>, String> {
+    let tables = parse_tables(text)?;
+    // 證交所另有一張以「項目」開頭的信用交易統計表
+    let Some(table) = tables
+        .iter()
+        .find(|t| t.fields.first().is_some_and(|f| is_id_header(f)))
+    else {
+        return Ok(Vec::new());
+    };
+    let fields = &table.fields;
+    let columns = [
+        named_column(fields, "資買", "買進", 0),
+        named_column(fields, "資賣", "賣出", 0),
+        named_column(fields, "資餘額", "今日餘額", 0),
+        named_column(fields, "券賣", "賣出", 1),
+        named_column(fields, "券買", "買進", 1),
+        named_column(fields, "券餘額", "今日餘額", 1),
+    ];
+    let [Some(mb), Some(ms), Some(mbal), Some(ss), Some(sb), Some(sbal)] = columns else {
+        return Err(format!(
+            "[Chips] unknown margin columns: {}",
+            fields.join(",")
+        ));
+    };
+    let value = |row: &[String], i: usize| row.get(i).and_then(|t| shares(t)).unwrap_or(0);
+    Ok(rows_with_id(table)
+        .map(|row| MarginBalance {
+            stock_id: row[0].clone(),
+            margin_buy: value(row, mb),
+            margin_sell: value(row, ms),
+            margin_balance: value(row, mbal),
+            short_sell: value(row, ss),
+            short_buy: value(row, sb),
+            short_balance: value(row, sbal),
+        })
+        .collect())
+}
+
+async fn fetch_text(url: &str) -> Result<String, String> {
+    let response = reqwest::get(url)
+        .await
+        .map_err(|e| format!("[Chips] {} request error: {}", url, e))?;
+    if !response.status().is_success() {
+        return Err(format!("[Chips] {} returned {}", url, response.status()));
+    }
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| format!("[Chips] {} read error: {}", url, e))?;
+    Ok(decode(&bytes))
+}
+
+async fn save_institutional(
+    pool: &SqlitePool,
+    t: &str,
+    flows: &[InstitutionalFlow],
+) -> Result<(), String> {
+    for chunk in flows.chunks(1000) {
+        let mut builder: QueryBuilder<Sqlite> = QueryBuilder::new(
+            "INSERT OR REPLACE INTO daily_institutional
+                (stock_id, t, foreign_net, trust_net, dealer_net, total_net) ",
+        );
+        builder.push_values(chunk, |mut row, flow| {
+            row.push_bind(&flow.stock_id)
+                .push_bind(t)
+                .push_bind(flow.foreign_net)
+                .push_bind(flow.trust_net)
+                .push_bind(flow.dealer_net)
+                .push_bind(flow.total_net);
+        });
+        builder
+            .build()
+            .execute(pool)
+            .await
+            .map_err(|e| format!("[Chips] save institutional error: {}", e))?;
+    }
+    writer::bump_versions(pool, &["daily_institutional"]).await
+}
+
+async fn save_margin(pool: &SqlitePool, t: &str, balances: &[MarginBalance]) -> Result<(), String> {
+    for chunk in balances.chunks(1000) {
+        let mut builder: QueryBuilder<Sqlite> = QueryBuilder::new(
+            "INSERT OR REPLACE INTO daily_margin
+                (stock_id, t, margin_buy, margin_sell, margin_balance, short_sell, short_buy, short_balance) ",
+        );
+        builder.push_values(chunk, |mut row, balance| {
+            row.push_bind(&balance.stock_id)
+                .push_bind(t)
+                .push_bind(balance.margin_buy)
+                .push_bind(balance.margin_sell)
+                .push_bind(balance.margin_balance)
+                .push_bind(balance.short_sell)
+                .push_bind(balance.short_buy)
+                .push_bind(balance.short_balance);
+        });
+        builder
+            .build()
+            .execute(pool)
+            .await
+            .map_err(|e| format!("[Chips] save margin error: {}", e))?;
+    }
+    writer::bump_versions(pool, &["daily_margin"]).await
+}
+
+/// 下載並寫入某交易日的上市、上櫃三大法人與融資融券；單一來源失敗時只記錄警告
+pub async fn ingest_day(
+    pool: &SqlitePool,
+    sources: &ChipSources,
+    day: NaiveDate,
+) -> Result<ChipsReport, String> {
+    let t = calendar::format_day(day);
+    let mut report = ChipsReport {
+        t: t.clone(),
+        ..Default::default()
+    };
+    for (i, template) in [&sources.twse_institutional, &sources.tpex_institutional]
+        .into_iter()
+        .enumerate()
+    {
+        if i > 0 {
+            tokio::time::sleep(std::time::Duration::from_secs(REQUEST_INTERVAL_SECS)).await;
+        }
+        let url = source_url(template, day);
+        match fetch_text(&url)
+            .await
+            .and_then(|text| parse_institutional(&text))
+        {
+            Ok(flows) => {
+                save_institutional(pool, &t, &flows).await?;
+                report.institutional += flows.len();
+            }
+            Err(e) => log::warn!("[Chips] {}", e),
+        }
+    }
+    for template in [&sources.twse_margin, &sources.tpex_margin] {
+        tokio::time::sleep(std::time::Duration::from_secs(REQUEST_INTERVAL_SECS)).await;
+        let url = source_url(template, day);
+        match fetch_text(&url).await.and_then(|text| parse_margin(&text)) {
+            Ok(balances) => {
+                save_margin(pool, &t, &balances).await?;
+                report.margin += balances.len();
+            }
+            Err(e) => log::warn!("[Chips] {}", e),
+        }
+    }
+    Ok(report)
+}
+
+/// 同步完成後補齊最近幾個有日 K 的交易日中，尚未寫入法人或融資融券資料的日期
+pub async fn on_sync_success(pool: &SqlitePool, sources: &ChipSources) -> Result<(), String> {
+    let days: Vec<String> =
+        sqlx::query_scalar("SELECT DISTINCT t FROM daily_deal ORDER BY t DESC LIMIT $1")
+            .bind(RECENT_DAYS)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| format!("[Chips] load dates error: {}", e))?;
+    for t in days.iter().rev() {
+        let complete: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM daily_institutional WHERE t = $1)
+                AND EXISTS (SELECT 1 FROM daily_margin WHERE t = $1)",
+        )
+        .bind(t)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("[Chips] check dates error: {}", e))?;
+        let Some(day) = calendar::parse_day(t).filter(|_| !complete) else {
+            continue;
+        };
+        let report = ingest_day(pool, sources, day).await?;
+        log::info!(
+            "[Chips] {} saved {} institutional, {} margin rows",
+            report.t,
+            report.institutional,
+            report.margin
+        );
+    }
+    Ok(())
+}
+
+/// `date` 為 YYYYMMDD，未指定時為本地最新交易日
+#[tauri::command]
+pub async fn sync_chips(
+    app: tauri::AppHandle,
+    db: State<'_, Db>,
+    date: Option<String>,
+) -> Result<ChipsReport, String> {
+    let t = match date {
+        Some(date) => date,
+        None => sqlx::query_scalar::<_, String>("SELECT MAX(t) FROM daily_deal")
+            .fetch_optional(&db.0)
+            .await
+            .map_err(|e| format!("[Chips] load dates error: {}", e))?
+            .ok_or("[Chips] no local trading days")?,
+    };
+    let day = calendar::parse_day(&t).ok_or(format!("invalid date {}", t))?;
+    ingest_day(&db.0, &ChipSources::load(&app), day).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> String {
+        let path = format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name);
+        decode(&std::fs::read(path).unwrap())
+    }
+
+    fn find<T>(rows: &[T], id: impl Fn(&T) -> bool) -> &T {
+        rows.iter().find(|row| id(row)).unwrap()
+    }
+
+    #[test]
+    fn parses_twse_institutional_json() {
+        let flows = parse_institutional(&fixture("twse_t86.json")).unwrap();
+        assert_eq!(flows.len(), 3);
+        let tsmc = find(&flows, |f| f.stock_id == "2330");
+        assert_eq!(tsmc.foreign_net, 8_123_456);
+        assert_eq!(tsmc.trust_net, -356_000);
+        assert_eq!(tsmc.dealer_net, 1_204_331);
+        assert_eq!(tsmc.total_net, 8_971_787);
+        // 空白欄位視為 0
+        assert_eq!(find(&flows, |f| f.stock_id == "00878").trust_net, 0);
+    }
+
+    #[test]
+    fn parses_tpex_institutional_csv() {
+        let flows = parse_institutional(&fixture("tpex_3insti.csv")).unwrap();
+        assert_eq!(flows.len(), 2);
+        let gw = find(&flows, |f| f.stock_id == "6488");
+        assert_eq!(gw.foreign_net, -512_000);
+        assert_eq!(gw.trust_net, 120_000);
+        // 自營商合計而非自行買賣或避險
+        assert_eq!(gw.dealer_net, -15_000);
+        assert_eq!(gw.total_net, -407_000);
+    }
+
+    #[test]
+    fn parses_margin_from_both_markets() {
+        let twse = parse_margin(&fixture("twse_margin.json")).unwrap();
+        assert_eq!(twse.len(), 2);
+        let tsmc = find(&twse, |m| m.stock_id == "2330");
+        assert_eq!(
+            (tsmc.margin_buy, tsmc.margin_sell, tsmc.margin_balance),
+            (1_520, 1_203, 27_551)
+        );
+        assert_eq!(
+            (tsmc.short_sell, tsmc.short_buy, tsmc.short_balance),
+            (85, 42, 1_306)
+        );
+
+        let tpex = parse_margin(&fixture("tpex_margin.csv")).unwrap();
+        assert_eq!(tpex.len(), 2);
+        let gw = find(&tpex, |m| m.stock_id == "6488");
+        assert_eq!(
+            (gw.margin_buy, gw.margin_sell, gw.margin_balance),
+            (210, 188, 5_873)
+        );
+        assert_eq!(
+            (gw.short_sell, gw.short_buy, gw.short_balance),
+            (12, 30, 402)
+        );
+    }
+
+    #[test]
+    fn skips_closed_days() {
+        let holiday = r#"{"stat":"很抱歉，沒有符合條件的資料!"}"#;
+        assert!(parse_institutional(holiday).unwrap().is_empty());
+        assert!(parse_margin(holiday).unwrap().is_empty());
+    }
+
+    #[test]
+    fn fills_url_templates() {
+        let day = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        assert_eq!(
+            source_url("a?d={date}&r={roc_date}&i={iso_date}", day),
+            "a?d=20261016&r=115/10/16&i=2026-10-16"
+        );
+    }
+}
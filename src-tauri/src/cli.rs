@@ -0,0 +1,458 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use chrono::{Local, NaiveDate};
+use sqlx::SqlitePool;
+
+use crate::adjustment::{self, PriceMode};
+use crate::backtest::{self, BacktestOptions, BacktestReport};
+use crate::breadth;
+use crate::calendar;
+use crate::chips;
+use crate::columnar::{self, ColumnarFormat, ColumnarOptions};
+use crate::incremental;
+use crate::industry;
+use crate::intraday;
+use crate::listing;
+use crate::screen::{self, PromptTemplate, ScreenMatch};
+use crate::settings;
+use crate::signals;
+use crate::sqlite::connection::{self, DB_FILE};
+use crate::types::Timeframe;
+use crate::yahoo;
+
+/// 與 tauri.conf.json 的 identifier 相同，決定資料目錄名稱
+const IDENTIFIER: &str = "schoice";
+const COMMANDS: &[&str] = &["sync", "screen", "backtest", "export", "db", "help"];
+/// Yahoo 限流時的等待秒數與重試次數
+const RATE_LIMIT_WAIT_SECS: u64 = 60;
+const RATE_LIMIT_RETRIES: usize = 3;
+
+const USAGE: &str = "Usage:
+  schoice sync [--stocks 2330,2317] [--timeframes daily,weekly,hourly] [--minutes]
+  schoice screen --prompt file.json [--date 2026-10-16] [--format table|csv|json] [--output file]
+  schoice backtest --prompt file.json --from 2026-01-02 [--to 2026-10-16] [--hold 5]
+                   [--capital 1000000] [--max-positions 10] [--stop-loss 8] [--take-profit 15]
+                   [--format table|json] [--output file]
+  schoice export --out dir [--format parquet|arrow] [--timeframes daily,weekly,hourly]
+                 [--from-year 2016] [--to-year 2026]
+  schoice db backup [file]
+
+Options:
+  --db <path>   SQLite database (defaults to the GUI's schoice.db)";
+
+#[derive(Debug, Default)]
+struct Args {
+    positional: Vec<String>,
+    options: BTreeMap<String, String>,
+}
+
+impl Args {
+    /// `--key value`、`--key=value`，後面沒有值的 `--flag` 記為 "true"
+    fn parse(args: &[String]) -> Self {
+        let mut parsed = Self::default();
+        let mut iter = args.iter().peekable();
+        while let Some(arg) = iter.next() {
+            let Some(key) = arg.strip_prefix("--") else {
+                parsed.positional.push(arg.clone());
+                continue;
+            };
+            let (key, value) = match key.split_once('=') {
+                Some((key, value)) => (key.to_string(), value.to_string()),
+                None => match iter.peek() {
+                    Some(next) if !next.starts_with("--") => {
+                        (key.to_string(), iter.next().cloned().unwrap_or_default())
+                    }
+                    _ => (key.to_string(), "true".to_string()),
+                },
+            };
+            parsed.options.insert(key, value);
+        }
+        parsed
+    }
+
+    fn get(&self, key: &str) -> Option<&str> {
+        self.options.get(key).map(String::as_str)
+    }
+
+    fn number<T: std::str::FromStr>(&self, key: &str) -> Result<Option<T>, String> {
+        self.get(key)
+            .map(|v| v.parse().map_err(|_| format!("invalid --{}: {}", key, v)))
+            .transpose()
+    }
+
+    fn date(&self, key: &str) -> Result<Option<NaiveDate>, String> {
+        self.get(key)
+            .map(|v| parse_date(v).ok_or_else(|| format!("invalid --{}: {}", key, v)))
+            .transpose()
+    }
+
+    fn list(&self, key: &str) -> Option<Vec<String>> {
+        self.get(key).map(|v| {
+            v.split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect()
+        })
+    }
+}
+
+/// 接受 `2026-10-16`、`2026/10/16` 與 `20261016`
+fn parse_date(text: &str) -> Option<NaiveDate> {
+    calendar::parse_day(&text.replace(['-', '/'], ""))
+}
+
+fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+}
+
+/// 對應 Tauri 的 `app_config_dir` (資料庫) 與 `app_data_dir` (store.json)
+fn app_dir(config: bool) -> Option<PathBuf> {
+    let base = if cfg!(target_os = "windows") {
+        std::env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        home_dir().map(|home| home.join("Library/Application Support"))
+    } else {
+        let (var, fallback) = match config {
+            true => ("XDG_CONFIG_HOME", ".config"),
+            false => ("XDG_DATA_HOME", ".local/share"),
+        };
+        std::env::var_os(var)
+            .map(PathBuf::from)
+            .or_else(|| home_dir().map(|home| home.join(fallback)))
+    }?;
+    Some(base.join(IDENTIFIER))
+}
+
+async fn open_db(args: &Args) -> Result<SqlitePool, String> {
+    let path = match args.get("db") {
+        Some(path) => PathBuf::from(path),
+        None => app_dir(true)
+            .ok_or("cannot locate the app config directory, use --db")?
+            .join(DB_FILE),
+    };
+    // 資料表由 GUI 啟動時的 migration 建立，CLI 不自行建立空資料庫
+    if !path.exists() {
+        return Err(format!(
+            "{} not found, launch the app once to initialize it",
+            path.display()
+        ));
+    }
+    connection::open(&path).await
+}
+
+fn price_mode() -> PriceMode {
+    app_dir(false)
+        .and_then(|dir| {
+            settings::get_from_file(&dir.join(settings::STORE_FILE), adjustment::PRICE_MODE_KEY)
+        })
+        .unwrap_or_default()
+}
+
+/// 策略檔由 GUI 的 `save_prompt_template` 產生，內容為含日期佔位符的 SQL 樣板
+fn load_prompt(args: &Args) -> Result<PromptTemplate, String> {
+    let path = args.get("prompt").ok_or("--prompt is required")?;
+    let text =
+        std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    serde_json::from_str(&text).map_err(|e| format!("Invalid prompt file {}: {}", path, e))
+}
+
+fn write_output(args: &Args, text: &str) -> Result<(), String> {
+    match args.get("output") {
+        Some(path) => {
+            std::fs::write(path, text).map_err(|e| format!("Failed to write {}: {}", path, e))
+        }
+        None => {
+            println!("{}", text);
+            Ok(())
+        }
+    }
+}
+
+/// 只寫入本地最後一根 (含) 之後的 K 線，讓指標以保存的狀態增量更新
+async fn sync_stock(
+    pool: &SqlitePool,
+    timeframe: Timeframe,
+    stock_id: &str,
+) -> Result<usize, String> {
+    let mut bars = yahoo::fetch_bars(stock_id, timeframe).await;
+    for _ in 0..RATE_LIMIT_RETRIES {
+        match &bars {
+            Err(e) if e.contains("rate limited") || e.contains("cooling down") => {
+                eprintln!("{}, waiting {}s", e, RATE_LIMIT_WAIT_SECS);
+                tokio::time::sleep(Duration::from_secs(RATE_LIMIT_WAIT_SECS)).await;
+                bars = yahoo::fetch_bars(stock_id, timeframe).await;
+            }
+            _ => break,
+        }
+    }
+    let bars = bars?;
+    let latest: Option<String> = sqlx::query_scalar(&format!(
+        "SELECT MAX({}) FROM {} WHERE stock_id = $1",
+        timeframe.time_column(),
+        timeframe.deal_table()
+    ))
+    .bind(stock_id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let bars: Vec<_> = match latest {
+        Some(latest) => bars.into_iter().filter(|bar| bar.t >= latest).collect(),
+        None => bars,
+    };
+    incremental::append(pool, timeframe, stock_id, &bars, price_mode()).await?;
+    Ok(bars.len())
+}
+
+/// `--timeframes` 未指定時為全部時框
+fn timeframes(args: &Args) -> Result<Vec<Timeframe>, String> {
+    args.list("timeframes")
+        .unwrap_or_else(|| vec!["daily".into(), "weekly".into(), "hourly".into()])
+        .iter()
+        .map(|tf| {
+            serde_json::from_value(serde_json::Value::from(tf.as_str()))
+                .map_err(|_| format!("invalid timeframe: {}", tf))
+        })
+        .collect()
+}
+
+async fn sync(args: &Args) -> Result<(), String> {
+    let pool = open_db(args).await?;
+    let stock_ids = match args.list("stocks") {
+        Some(ids) => ids,
+        None => sqlx::query_scalar(&format!(
+            "SELECT stock_id FROM stock WHERE {} ORDER BY stock_id",
+            listing::SYNCED_TYPES_FILTER
+        ))
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| e.to_string())?,
+    };
+    let timeframes = timeframes(args)?;
+
+    let mut failed = 0;
+    for (i, stock_id) in stock_ids.iter().enumerate() {
+        for timeframe in &timeframes {
+            if let Err(e) = sync_stock(&pool, *timeframe, stock_id).await {
+                eprintln!("{} {}: {}", stock_id, timeframe.as_str(), e);
+                failed += 1;
+            }
+        }
+        if (i + 1) % 100 == 0 {
+            eprintln!("synced {}/{}", i + 1, stock_ids.len());
+        }
+    }
+
+    // 與 GUI 的 post_sync 相同的後續工作 (警示通知需要視窗，不在 CLI 執行)
+    adjustment::refresh_pending(&pool, price_mode()).await?;
+    let sources = app_dir(false)
+        .and_then(|dir| {
+            settings::get_from_file(&dir.join(settings::STORE_FILE), chips::SOURCES_KEY)
+        })
+        .unwrap_or_default();
+    chips::on_sync_success(&pool, &sources).await?;
+    // 分時資料只保留近期，--minutes 時才另外下載
+    if args.get("minutes").is_some() {
+        let retention = app_dir(false)
+            .and_then(|dir| {
+                settings::get_from_file(&dir.join(settings::STORE_FILE), intraday::RETENTION_KEY)
+            })
+            .unwrap_or(intraday::DEFAULT_RETENTION_DAYS);
+        let report = intraday::sync(&pool, &stock_ids, retention).await?;
+        println!(
+            "synced {} minute bars, {} failed, {} pruned",
+            report.minutes, report.failed, report.pruned
+        );
+    }
+    breadth::on_sync_success(&pool).await?;
+    industry::on_sync_success(&pool).await?;
+    signals::on_sync_success(&pool).await?;
+    println!(
+        "synced {} stocks, {} failed requests",
+        stock_ids.len(),
+        failed
+    );
+    Ok(())
+}
+
+fn format_matches(format: &str, matches: &[ScreenMatch]) -> Result<String, String> {
+    match format {
+        "json" => serde_json::to_string_pretty(matches).map_err(|e| e.to_string()),
+        "csv" => {
+            let mut writer = csv::Writer::from_writer(Vec::new());
+            writer
+                .write_record(["stock_id", "stock_name", "industry_group", "market_type"])
+                .map_err(|e| e.to_string())?;
+            for m in matches {
+                writer
+                    .write_record([
+                        m.stock_id.as_str(),
+                        m.stock_name.as_deref().unwrap_or_default(),
+                        m.industry_group.as_deref().unwrap_or_default(),
+                        m.market_type.as_deref().unwrap_or_default(),
+                    ])
+                    .map_err(|e| e.to_string())?;
+            }
+            let bytes = writer.into_inner().map_err(|e| e.to_string())?;
+            String::from_utf8(bytes).map_err(|e| e.to_string())
+        }
+        _ => Ok(matches
+            .iter()
+            .map(|m| {
+                format!(
+                    "{}\t{}\t{}",
+                    m.stock_id,
+                    m.stock_name.as_deref().unwrap_or_default(),
+                    m.industry_group.as_deref().unwrap_or_default()
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")),
+    }
+}
+
+async fn run_screen(args: &Args) -> Result<(), String> {
+    let template = load_prompt(args)?;
+    let date = args.date("date")?;
+    let pool = open_db(args).await?;
+    let matches = screen::screen(&pool, &template, date).await?;
+    eprintln!("{}: {} matches", template.name, matches.len());
+    write_output(
+        args,
+        &format_matches(args.get("format").unwrap_or("table"), &matches)?,
+    )
+}
+
+fn format_report(format: &str, report: &BacktestReport) -> Result<String, String> {
+    if format == "json" {
+        return serde_json::to_string_pretty(report).map_err(|e| e.to_string());
+    }
+    let mut lines = vec![
+        format!("{} {} ~ {}", report.name, report.from, report.to),
+        format!(
+            "return {:.2}%  win rate {:.2}%  max drawdown {:.2}%  trades {}",
+            report.return_pct,
+            report.win_rate,
+            report.max_drawdown_pct,
+            report.trades.len()
+        ),
+    ];
+    lines.extend(report.trades.iter().map(|trade| {
+        format!(
+            "{}\t{} {:.2}\t{} {:.2}\t{:+.2}%",
+            trade.stock_id,
+            trade.buy_date,
+            trade.buy_price,
+            trade.sell_date,
+            trade.sell_price,
+            trade.return_pct
+        )
+    }));
+    Ok(lines.join("\n"))
+}
+
+async fn run_backtest(args: &Args) -> Result<(), String> {
+    let template = load_prompt(args)?;
+    let from = args.date("from")?.ok_or("--from is required")?;
+    let options = BacktestOptions {
+        from,
+        to: args
+            .date("to")?
+            .unwrap_or_else(|| Local::now().date_naive()),
+        hold_days: args.number("hold")?.unwrap_or(5),
+        capital: args.number("capital")?.unwrap_or(1_000_000.0),
+        max_positions: args.number("max-positions")?.unwrap_or(10),
+        stop_loss: args.number("stop-loss")?,
+        take_profit: args.number("take-profit")?,
+        fee_discount: args.number("fee-discount")?.unwrap_or(1.0),
+        min_fee: args.number("min-fee")?.unwrap_or(20.0),
+    };
+    let pool = open_db(args).await?;
+    let report = backtest::run(&pool, &template, &options).await?;
+    write_output(
+        args,
+        &format_report(args.get("format").unwrap_or("table"), &report)?,
+    )
+}
+
+async fn export(args: &Args) -> Result<(), String> {
+    let dir = PathBuf::from(args.get("out").ok_or("--out is required")?);
+    let format = match args.get("format").unwrap_or("parquet") {
+        "parquet" => ColumnarFormat::Parquet,
+        "arrow" => ColumnarFormat::Arrow,
+        other => return Err(format!("invalid --format: {}", other)),
+    };
+    let options = ColumnarOptions {
+        format,
+        timeframes: timeframes(args)?,
+        from_year: args.number("from-year")?,
+        to_year: args.number("to-year")?,
+    };
+    let pool = open_db(args).await?;
+    let report = columnar::write_dataset(&pool, &dir, &options).await?;
+    for file in &report.files {
+        println!("{}\t{}", file.path, file.rows);
+    }
+    println!("exported {} rows to {}", report.rows, report.dir);
+    Ok(())
+}
+
+/// 以 `VACUUM INTO` 產生一致的快照，GUI 開啟中也能安全備份
+async fn backup(args: &Args) -> Result<(), String> {
+    let target = match args.positional.get(2) {
+        Some(path) => PathBuf::from(path),
+        None => PathBuf::from(format!(
+            "schoice-{}.db",
+            Local::now().format("%Y%m%d-%H%M%S")
+        )),
+    };
+    if target.exists() {
+        return Err(format!("{} already exists", target.display()));
+    }
+    let pool = open_db(args).await?;
+    sqlx::query("VACUUM INTO $1")
+        .bind(target.to_string_lossy().to_string())
+        .execute(&pool)
+        .await
+        .map_err(|e| format!("backup failed: {}", e))?;
+    println!("backup written to {}", target.display());
+    Ok(())
+}
+
+/// 無視窗的命令列模式，與 GUI 共用同一個 `schoice.db`，供排程 (cron) 每日同步與選股。
+/// 第一個參數為子命令時執行並回傳結束碼，否則回傳 None 交由 GUI 啟動
+pub fn run(args: Vec<String>) -> Option<i32> {
+    let command = args.first()?.as_str();
+    if !COMMANDS.contains(&command) {
+        return None;
+    }
+    let args = Args::parse(&args);
+    let result = tauri::async_runtime::block_on(async {
+        match (command, args.positional.get(1).map(String::as_str)) {
+            ("sync", _) => sync(&args).await,
+            ("screen", _) => run_screen(&args).await,
+            ("backtest", _) => run_backtest(&args).await,
+            ("export", _) => export(&args).await,
+            ("db", Some("backup")) => backup(&args).await,
+            _ => Err(USAGE.to_string()),
+        }
+    });
+    Some(match result {
+        Ok(()) => 0,
+        Err(e) if e == USAGE => {
+            println!("{}", USAGE);
+            if command == "help" {
+                0
+            } else {
+                2
+            }
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            1
+        }
+    })
+}
@@ -0,0 +1,487 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use arrow_array::builder::{
+    Date32Builder, Float64Builder, Int64Builder, StringBuilder, TimestampMillisecondBuilder,
+};
+use arrow_array::{ArrayRef, RecordBatch};
+use arrow_ipc::writer::StreamWriter;
+use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use chrono::{FixedOffset, NaiveDate, NaiveDateTime};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqlitePool};
+use tauri::State;
+use tauri_plugin_dialog::DialogExt;
+
+use crate::indicators::SKILLS_COLUMNS;
+use crate::sqlite::connection::Db;
+use crate::types::Timeframe;
+use crate::yahoo::TAIPEI_OFFSET_SECS;
+
+/// 每次查詢的股票檔數，控制單批記憶體用量
+const STOCK_CHUNK: usize = 50;
+const TIMEZONE: &str = "Asia/Taipei";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColumnarFormat {
+    Parquet,
+    /// Arrow IPC stream (`pyarrow.ipc.open_stream` / `polars.read_ipc_stream`)
+    Arrow,
+}
+
+impl ColumnarFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            ColumnarFormat::Parquet => "parquet",
+            ColumnarFormat::Arrow => "arrows",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ColumnarOptions {
+    pub format: ColumnarFormat,
+    pub timeframes: Vec<Timeframe>,
+    pub from_year: Option<i32>,
+    pub to_year: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DatasetFile {
+    pub timeframe: Timeframe,
+    pub year: i32,
+    pub path: String,
+    pub rows: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DatasetReport {
+    pub dir: String,
+    pub rows: usize,
+    pub files: Vec<DatasetFile>,
+}
+
+/// 日/週線的 `t` 轉為 Date32，小時線的 `ts` 轉為台北時區的毫秒時間戳
+fn schema(timeframe: Timeframe) -> SchemaRef {
+    let time = match timeframe {
+        Timeframe::Hourly => Field::new(
+            "ts",
+            DataType::Timestamp(TimeUnit::Millisecond, Some(TIMEZONE.into())),
+            false,
+        ),
+        _ => Field::new("t", DataType::Date32, false),
+    };
+    let mut fields = vec![Field::new("stock_id", DataType::Utf8, false), time];
+    fields.extend(["o", "h", "l", "c"].map(|c| Field::new(c, DataType::Float64, true)));
+    fields.push(Field::new("v", DataType::Int64, true));
+    fields.extend(
+        SKILLS_COLUMNS
+            .iter()
+            .map(|c| Field::new(*c, DataType::Float64, true)),
+    );
+    Arc::new(Schema::new(fields))
+}
+
+fn epoch() -> NaiveDate {
+    NaiveDate::from_ymd_opt(1970, 1, 1).unwrap_or_default()
+}
+
+/// `YYYYMMDD` 為距 1970-01-01 的天數，`YYYYMMDDHHmm` 為 UTC 毫秒；
+/// 舊版 `YYYY-MM-DD HH:MM:00` 經 `select_sql` 去除分隔符後多出的秒數忽略
+fn time_value(timeframe: Timeframe, text: &str) -> Option<i64> {
+    match timeframe {
+        Timeframe::Hourly => {
+            let offset = FixedOffset::east_opt(TAIPEI_OFFSET_SECS)?;
+            NaiveDateTime::parse_from_str(text.get(0..12)?, "%Y%m%d%H%M")
+                .ok()?
+                .and_local_timezone(offset)
+                .single()
+                .map(|time| time.timestamp_millis())
+        }
+        _ => NaiveDate::parse_from_str(text, "%Y%m%d")
+            .ok()
+            .map(|date| (date - epoch()).num_days()),
+    }
+}
+
+/// 年度的起始值，與去除分隔符後的 `t` / `ts` 比較
+fn year_bound(timeframe: Timeframe, year: i32) -> String {
+    match timeframe {
+        Timeframe::Hourly => format!("{:04}01010000", year),
+        _ => format!("{:04}0101", year),
+    }
+}
+
+enum TimeBuilder {
+    Date(Date32Builder),
+    Timestamp(TimestampMillisecondBuilder),
+}
+
+/// 單一年度分區的欄位緩衝
+struct Columns {
+    stock_id: StringBuilder,
+    time: TimeBuilder,
+    prices: [Float64Builder; 4],
+    v: Int64Builder,
+    skills: Vec<Float64Builder>,
+    len: usize,
+}
+
+impl Columns {
+    fn new(timeframe: Timeframe) -> Self {
+        Self {
+            stock_id: StringBuilder::new(),
+            time: match timeframe {
+                Timeframe::Hourly => TimeBuilder::Timestamp(
+                    TimestampMillisecondBuilder::new().with_timezone(TIMEZONE),
+                ),
+                _ => TimeBuilder::Date(Date32Builder::new()),
+            },
+            prices: std::array::from_fn(|_| Float64Builder::new()),
+            v: Int64Builder::new(),
+            skills: SKILLS_COLUMNS
+                .iter()
+                .map(|_| Float64Builder::new())
+                .collect(),
+            len: 0,
+        }
+    }
+
+    /// 欄位順序同 `select_sql`：stock_id, 時間, o, h, l, c, v, 指標
+    fn push(&mut self, row: &sqlx::sqlite::SqliteRow, time: i64) -> Result<(), sqlx::Error> {
+        self.stock_id.append_value(row.try_get::<String, _>(0)?);
+        match &mut self.time {
+            TimeBuilder::Date(builder) => builder.append_value(time as i32),
+            TimeBuilder::Timestamp(builder) => builder.append_value(time),
+        }
+        for (i, builder) in self.prices.iter_mut().enumerate() {
+            builder.append_option(row.try_get::<Option<f64>, _>(i + 2)?);
+        }
+        self.v.append_option(row.try_get::<Option<i64>, _>(6)?);
+        for (i, builder) in self.skills.iter_mut().enumerate() {
+            builder.append_option(row.try_get::<Option<f64>, _>(i + 7)?);
+        }
+        self.len += 1;
+        Ok(())
+    }
+
+    fn finish(mut self, schema: SchemaRef) -> Result<RecordBatch, String> {
+        let mut columns: Vec<ArrayRef> = vec![
+            Arc::new(self.stock_id.finish()),
+            match &mut self.time {
+                TimeBuilder::Date(builder) => Arc::new(builder.finish()),
+                TimeBuilder::Timestamp(builder) => Arc::new(builder.finish()),
+            },
+        ];
+        columns.extend(
+            self.prices
+                .iter_mut()
+                .map(|builder| Arc::new(builder.finish()) as ArrayRef),
+        );
+        columns.push(Arc::new(self.v.finish()));
+        columns.extend(
+            self.skills
+                .iter_mut()
+                .map(|builder| Arc::new(builder.finish()) as ArrayRef),
+        );
+        RecordBatch::try_new(schema, columns).map_err(|e| format!("[Columnar] batch error: {}", e))
+    }
+}
+
+enum DatasetWriter {
+    Parquet(ArrowWriter<File>),
+    Arrow(StreamWriter<BufWriter<File>>),
+}
+
+impl DatasetWriter {
+    fn create(path: &Path, format: ColumnarFormat, schema: &SchemaRef) -> Result<Self, String> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("[Columnar] create {} error: {}", parent.display(), e))?;
+        }
+        let file = File::create(path)
+            .map_err(|e| format!("[Columnar] create {} error: {}", path.display(), e))?;
+        match format {
+            ColumnarFormat::Parquet => {
+                let props = WriterProperties::builder()
+                    .set_compression(Compression::SNAPPY)
+                    .build();
+                ArrowWriter::try_new(file, schema.clone(), Some(props))
+                    .map(DatasetWriter::Parquet)
+                    .map_err(|e| format!("[Columnar] parquet writer error: {}", e))
+            }
+            ColumnarFormat::Arrow => StreamWriter::try_new_buffered(file, schema)
+                .map(DatasetWriter::Arrow)
+                .map_err(|e| format!("[Columnar] arrow writer error: {}", e)),
+        }
+    }
+
+    fn write(&mut self, batch: &RecordBatch) -> Result<(), String> {
+        match self {
+            DatasetWriter::Parquet(writer) => writer.write(batch).map_err(|e| e.to_string()),
+            DatasetWriter::Arrow(writer) => writer.write(batch).map_err(|e| e.to_string()),
+        }
+        .map_err(|e| format!("[Columnar] write error: {}", e))
+    }
+
+    fn close(self) -> Result<(), String> {
+        match self {
+            DatasetWriter::Parquet(writer) => writer.close().map(|_| ()).map_err(|e| e.to_string()),
+            DatasetWriter::Arrow(mut writer) => writer.finish().map_err(|e| e.to_string()),
+        }
+        .map_err(|e| format!("[Columnar] close error: {}", e))
+    }
+}
+
+/// 去除 `-`、空白與 `:`，使舊版 `YYYY-MM-DD HH:MM:00` 與 `YYYYMMDDHHmm` 可一併比較與解析
+fn normalized_time(column: &str) -> String {
+    format!(
+        "REPLACE(REPLACE(REPLACE(CAST({} AS TEXT), '-', ''), ' ', ''), ':', '')",
+        column
+    )
+}
+
+/// `*_deal` LEFT JOIN `*_skills`，數值一律 CAST 避免同欄混存整數與浮點
+fn select_sql(timeframe: Timeframe, stocks: usize) -> String {
+    let time = timeframe.time_column();
+    let normalized = normalized_time(&format!("d.{}", time));
+    let skills = SKILLS_COLUMNS
+        .iter()
+        .map(|c| format!("CAST(s.{} AS REAL)", c))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let placeholders = (3..stocks + 3)
+        .map(|i| format!("${}", i))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        "SELECT d.stock_id, {normalized},
+                CAST(d.o AS REAL), CAST(d.h AS REAL), CAST(d.l AS REAL), CAST(d.c AS REAL),
+                CAST(d.v AS INTEGER), {skills}
+         FROM {deal} d
+         LEFT JOIN {skills_table} s ON s.stock_id = d.stock_id AND s.{time} = d.{time}
+         WHERE {normalized} >= $1 AND {normalized} < $2 AND d.stock_id IN ({placeholders})
+         ORDER BY d.stock_id, {normalized}",
+        deal = timeframe.deal_table(),
+        skills_table = timeframe.skills_table(),
+    )
+}
+
+struct Partition {
+    writer: DatasetWriter,
+    path: PathBuf,
+    rows: usize,
+}
+
+async fn write_timeframe(
+    pool: &SqlitePool,
+    dir: &Path,
+    timeframe: Timeframe,
+    options: &ColumnarOptions,
+) -> Result<Vec<DatasetFile>, String> {
+    let schema = schema(timeframe);
+    let stock_ids: Vec<String> = sqlx::query_scalar(&format!(
+        "SELECT DISTINCT stock_id FROM {} ORDER BY stock_id",
+        timeframe.deal_table()
+    ))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("[Columnar] load stocks error: {}", e))?;
+    let from = options
+        .from_year
+        .map_or("0".to_string(), |y| year_bound(timeframe, y));
+    let to = options.to_year.map_or("99999999999999".to_string(), |y| {
+        year_bound(timeframe, y + 1)
+    });
+
+    let mut partitions: BTreeMap<i32, Partition> = BTreeMap::new();
+    let mut skipped = 0;
+    for chunk in stock_ids.chunks(STOCK_CHUNK) {
+        let sql = select_sql(timeframe, chunk.len());
+        let mut query = sqlx::query(&sql).bind(&from).bind(&to);
+        for stock_id in chunk {
+            query = query.bind(stock_id);
+        }
+        let rows = query
+            .fetch_all(pool)
+            .await
+            .map_err(|e| format!("[Columnar] query {} error: {}", timeframe.as_str(), e))?;
+
+        let mut years: BTreeMap<i32, Columns> = BTreeMap::new();
+        for row in &rows {
+            let text: String = row
+                .try_get(1)
+                .map_err(|e| format!("[Columnar] decode error: {}", e))?;
+            let (Some(year), Some(time)) = (
+                text.get(0..4).and_then(|y| y.parse::<i32>().ok()),
+                time_value(timeframe, &text),
+            ) else {
+                skipped += 1;
+                continue;
+            };
+            years
+                .entry(year)
+                .or_insert_with(|| Columns::new(timeframe))
+                .push(row, time)
+                .map_err(|e| format!("[Columnar] decode error: {}", e))?;
+        }
+
+        for (year, columns) in years {
+            let rows = columns.len;
+            let batch = columns.finish(schema.clone())?;
+            let partition = match partitions.entry(year) {
+                std::collections::btree_map::Entry::Occupied(entry) => entry.into_mut(),
+                std::collections::btree_map::Entry::Vacant(entry) => {
+                    // Hive 分區路徑，pandas / polars 可直接以資料夾讀入並還原 timeframe、year 欄位
+                    let path = dir
+                        .join(format!("timeframe={}", timeframe.as_str()))
+                        .join(format!("year={}", year))
+                        .join(format!("part-0.{}", options.format.extension()));
+                    entry.insert(Partition {
+                        writer: DatasetWriter::create(&path, options.format, &schema)?,
+                        path,
+                        rows: 0,
+                    })
+                }
+            };
+            partition.writer.write(&batch)?;
+            partition.rows += rows;
+        }
+    }
+    if skipped > 0 {
+        log::warn!(
+            "[Columnar] {} skipped {} rows with invalid time",
+            timeframe.as_str(),
+            skipped
+        );
+    }
+
+    partitions
+        .into_iter()
+        .map(|(year, partition)| {
+            partition.writer.close()?;
+            Ok(DatasetFile {
+                timeframe,
+                year,
+                path: partition.path.display().to_string(),
+                rows: partition.rows,
+            })
+        })
+        .collect()
+}
+
+/// 將 K 線與技術指標依時框、年度寫成分區檔案，既有分區會被覆寫
+pub async fn write_dataset(
+    pool: &SqlitePool,
+    dir: &Path,
+    options: &ColumnarOptions,
+) -> Result<DatasetReport, String> {
+    let mut files = Vec::new();
+    for timeframe in &options.timeframes {
+        files.extend(write_timeframe(pool, dir, *timeframe, options).await?);
+    }
+    Ok(DatasetReport {
+        dir: dir.display().to_string(),
+        rows: files.iter().map(|f| f.rows).sum(),
+        files,
+    })
+}
+
+/// 選擇輸出資料夾後匯出，取消時回傳 None
+#[tauri::command]
+pub async fn export_history(
+    app: tauri::AppHandle,
+    db: State<'_, Db>,
+    options: ColumnarOptions,
+) -> Result<Option<DatasetReport>, String> {
+    let dialog = app.dialog().file();
+    let picked = tauri::async_runtime::spawn_blocking(move || dialog.blocking_pick_folder())
+        .await
+        .map_err(|e| e.to_string())?;
+    let Some(dir) = picked else {
+        return Ok(None);
+    };
+    let dir = dir.into_path().map_err(|e| e.to_string())?;
+    write_dataset(&db.0, &dir, &options).await.map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::{Date32Type, TimestampMillisecondType};
+    use arrow_ipc::reader::StreamReader;
+
+    use super::*;
+    use crate::sqlite::connection;
+
+    fn read(path: &str) -> Vec<RecordBatch> {
+        let reader = StreamReader::try_new(File::open(path).unwrap(), None).unwrap();
+        reader.map(Result::unwrap).collect()
+    }
+
+    #[test]
+    fn exports_both_hourly_ts_formats() {
+        tauri::async_runtime::block_on(async {
+            let pool = connection::memory().await;
+            connection::migrate(&pool, ..).await;
+            sqlx::query(
+                "INSERT INTO hourly_deal (stock_id, ts, o, h, l, c, v) VALUES
+                    ('2330', '2023-12-29 13:00:00', 1, 1, 1, 1, 1),
+                    ('2330', '2024-01-02 09:00:00', 1, 1, 1, 1, 1),
+                    ('2330', '202401021000', 1, 1, 1, 1, 1)",
+            )
+            .execute(&pool)
+            .await
+            .unwrap();
+            sqlx::query(
+                "INSERT INTO daily_deal (stock_id, t, o, h, l, c, v) VALUES ('2330', '20240102', 1, 1, 1, 1, 1)",
+            )
+            .execute(&pool)
+            .await
+            .unwrap();
+
+            let dir = std::env::temp_dir().join(format!("schoice-columnar-{}", std::process::id()));
+            let options = ColumnarOptions {
+                format: ColumnarFormat::Arrow,
+                timeframes: vec![Timeframe::Daily, Timeframe::Hourly],
+                from_year: Some(2024),
+                to_year: None,
+            };
+            let report = write_dataset(&pool, &dir, &options).await.unwrap();
+            assert_eq!(report.rows, 3);
+
+            let days: Vec<i32> = read(&report.files[0].path)
+                .iter()
+                .flat_map(|batch| {
+                    batch
+                        .column(1)
+                        .as_primitive::<Date32Type>()
+                        .values()
+                        .to_vec()
+                })
+                .collect();
+            assert_eq!(days, vec![19724]);
+            // 2024-01-02 09:00 / 10:00 台北時間
+            let times: Vec<i64> = read(&report.files[1].path)
+                .iter()
+                .flat_map(|batch| {
+                    batch
+                        .column(1)
+                        .as_primitive::<TimestampMillisecondType>()
+                        .values()
+                        .to_vec()
+                })
+                .collect();
+            assert_eq!(report.files[1].year, 2024);
+            assert_eq!(times, vec![1_704_157_200_000, 1_704_160_800_000]);
+            std::fs::remove_dir_all(&dir).ok();
+        });
+    }
+}
@@ -0,0 +1,723 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use serde::Serialize;
+use sqlx::SqlitePool;
+use tauri::State;
+
+use crate::adjustment::{self, PriceMode};
+use crate::calendar::{self, TradingCalendar};
+use crate::incremental;
+use crate::sqlite::connection::Db;
+use crate::sqlite::writer;
+use crate::types::{Bar, Timeframe};
+use crate::yahoo;
+
+const TIMEFRAMES: [Timeframe; 3] = [Timeframe::Daily, Timeframe::Weekly, Timeframe::Hourly];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    /// 交易日曆上有開盤但缺少 K 線 (僅檢查日線與小時線)；可疑日 (全市場都沒有資料的平日) 會在 detail 註明
+    MissingDay,
+    /// 開高低收為 0、負數或 NULL
+    NonPositivePrice,
+    /// 最高價低於開/收盤價，或最低價高於開/收盤價
+    OhlcInconsistent,
+    /// `*_skills` 有資料但 `*_deal` 沒有對應 K 線
+    OrphanSkills,
+    /// 小時線同一時間同時以 `YYYY-MM-DD HH:mm:00` 與 YYYYMMDDHHmm 寫入，`t` 為多出的舊格式 ts
+    DuplicateBar,
+}
+
+impl IssueKind {
+    /// 需要重新下載 K 線才能修復
+    fn needs_refetch(self) -> bool {
+        !matches!(self, IssueKind::OrphanSkills | IssueKind::DuplicateBar)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DataIssue {
+    pub stock_id: String,
+    pub timeframe: Timeframe,
+    pub kind: IssueKind,
+    /// 日/週線為 YYYYMMDD，小時線為 ts；MissingDay 一律為交易日 YYYYMMDD
+    pub t: String,
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RepairOutcome {
+    pub stock_id: String,
+    pub timeframe: Timeframe,
+    /// 自此時間點起重算技術指標
+    pub from: String,
+    pub refetched: usize,
+    pub recomputed: usize,
+    pub unresolved: usize,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DataQualityReport {
+    pub checked_stocks: usize,
+    pub issues: Vec<DataIssue>,
+    pub repaired: Vec<RepairOutcome>,
+}
+
+/// 小時線 ts 可能為 YYYYMMDDHHmm 或 `YYYY-MM-DD HH:mm:00`，統一取前 8 碼日期
+fn day_expr(timeframe: Timeframe) -> &'static str {
+    match timeframe {
+        Timeframe::Hourly => "substr(replace(ts, '-', ''), 1, 8)",
+        _ => "t",
+    }
+}
+
+/// 不分格式的排序鍵 YYYYMMDDHHmm，只有日期時補 0000
+fn time_key(t: &str) -> String {
+    let digits: String = t.chars().filter(char::is_ascii_digit).take(12).collect();
+    format!("{:0<12}", digits)
+}
+
+/// 將排序鍵轉回資料表的時間格式，小時線依該股票既有 ts 是否為 `YYYY-MM-DD HH:mm:00`
+pub(crate) fn table_time(timeframe: Timeframe, key: &str, dashed: bool) -> String {
+    match timeframe {
+        Timeframe::Hourly if dashed => format!(
+            "{}-{}-{} {}:{}:00",
+            &key[0..4],
+            &key[4..6],
+            &key[6..8],
+            &key[8..10],
+            &key[10..12]
+        ),
+        Timeframe::Hourly => key.to_string(),
+        _ => key[0..8].to_string(),
+    }
+}
+
+/// 以最新一筆小時線判斷該股票的 ts 格式，重算起點與補抓的 K 線需與之一致才能正確比較字串
+pub(crate) async fn uses_dashed_ts(
+    pool: &SqlitePool,
+    timeframe: Timeframe,
+    stock_id: &str,
+) -> Result<bool, String> {
+    if timeframe != Timeframe::Hourly {
+        return Ok(false);
+    }
+    let latest: Option<String> =
+        sqlx::query_scalar("SELECT MAX(ts) FROM hourly_deal WHERE stock_id = $1")
+            .bind(stock_id)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| format!("[DataQuality] load ts format error: {}", e))?;
+    Ok(latest.is_some_and(|ts| ts.contains('-')))
+}
+
+async fn check_prices(
+    pool: &SqlitePool,
+    timeframe: Timeframe,
+    stock_id: Option<&str>,
+    issues: &mut Vec<DataIssue>,
+) -> Result<(), String> {
+    let sql = format!(
+        "SELECT stock_id, {col}, o, h, l, c FROM {table}
+         WHERE ($1 IS NULL OR stock_id = $1)
+           AND (o IS NULL OR h IS NULL OR l IS NULL OR c IS NULL
+                OR o <= 0 OR h <= 0 OR l <= 0 OR c <= 0
+                OR h < max(o, c) OR l > min(o, c) OR h < l)
+         ORDER BY stock_id, {col}",
+        col = timeframe.time_column(),
+        table = timeframe.deal_table()
+    );
+    type Row = (
+        String,
+        String,
+        Option<f64>,
+        Option<f64>,
+        Option<f64>,
+        Option<f64>,
+    );
+    let rows: Vec<Row> = sqlx::query_as(&sql)
+        .bind(stock_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| {
+            format!(
+                "[DataQuality] {} price check error: {}",
+                timeframe.deal_table(),
+                e
+            )
+        })?;
+
+    for (stock_id, t, o, h, l, c) in rows {
+        let detail = format!(
+            "o={} h={} l={} c={}",
+            fmt_price(o),
+            fmt_price(h),
+            fmt_price(l),
+            fmt_price(c)
+        );
+        let prices = [o, h, l, c];
+        let kind = if prices.iter().any(|p| p.is_none_or(|p| p <= 0.0)) {
+            IssueKind::NonPositivePrice
+        } else {
+            IssueKind::OhlcInconsistent
+        };
+        issues.push(DataIssue {
+            stock_id,
+            timeframe,
+            kind,
+            t,
+            detail: Some(detail),
+        });
+    }
+    Ok(())
+}
+
+fn fmt_price(value: Option<f64>) -> String {
+    value.map_or("NULL".to_string(), |v| v.to_string())
+}
+
+/// 小時線同一時間有兩種 ts 格式時，回報舊的 `YYYY-MM-DD HH:mm:00` 那筆
+async fn check_duplicates(
+    pool: &SqlitePool,
+    stock_id: Option<&str>,
+    issues: &mut Vec<DataIssue>,
+) -> Result<(), String> {
+    let rows: Vec<(String, String)> = sqlx::query_as(
+        "SELECT d.stock_id, d.ts FROM hourly_deal d
+         JOIN hourly_deal o ON o.stock_id = d.stock_id AND o.ts <> d.ts
+              AND o.ts = replace(replace(replace(substr(d.ts, 1, 16), '-', ''), ' ', ''), ':', '')
+         WHERE ($1 IS NULL OR d.stock_id = $1) AND d.ts LIKE '____-__-__ %'
+         ORDER BY d.stock_id, d.ts",
+    )
+    .bind(stock_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("[DataQuality] hourly_deal duplicate check error: {}", e))?;
+
+    issues.extend(rows.into_iter().map(|(stock_id, t)| DataIssue {
+        stock_id,
+        timeframe: Timeframe::Hourly,
+        kind: IssueKind::DuplicateBar,
+        t,
+        detail: None,
+    }));
+    Ok(())
+}
+
+async fn check_orphan_skills(
+    pool: &SqlitePool,
+    timeframe: Timeframe,
+    stock_id: Option<&str>,
+    issues: &mut Vec<DataIssue>,
+) -> Result<(), String> {
+    let sql = format!(
+        "SELECT s.stock_id, s.{col} FROM {skills} s
+         LEFT JOIN {deal} d ON d.stock_id = s.stock_id AND d.{col} = s.{col}
+         WHERE ($1 IS NULL OR s.stock_id = $1) AND d.stock_id IS NULL
+         ORDER BY s.stock_id, s.{col}",
+        col = timeframe.time_column(),
+        skills = timeframe.skills_table(),
+        deal = timeframe.deal_table()
+    );
+    let rows: Vec<(String, String)> = sqlx::query_as(&sql)
+        .bind(stock_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| {
+            format!(
+                "[DataQuality] {} orphan check error: {}",
+                timeframe.skills_table(),
+                e
+            )
+        })?;
+
+    issues.extend(rows.into_iter().map(|(stock_id, t)| DataIssue {
+        stock_id,
+        timeframe,
+        kind: IssueKind::OrphanSkills,
+        t,
+        detail: None,
+    }));
+    Ok(())
+}
+
+/// 以交易日曆比對每檔股票首尾日期之間缺少的交易日與可疑日。
+/// 先以筆數快速篩選，只有筆數不足的股票才載入完整日期清單。
+async fn check_missing_days(
+    pool: &SqlitePool,
+    calendar: &TradingCalendar,
+    timeframe: Timeframe,
+    stock_id: Option<&str>,
+    issues: &mut Vec<DataIssue>,
+) -> Result<(), String> {
+    let day = day_expr(timeframe);
+    let sql = format!(
+        "SELECT stock_id, MIN({day}), MAX({day}), COUNT(DISTINCT {day}) FROM {table}
+         WHERE ($1 IS NULL OR stock_id = $1)
+         GROUP BY stock_id",
+        table = timeframe.deal_table()
+    );
+    let ranges: Vec<(String, String, String, i64)> = sqlx::query_as(&sql)
+        .bind(stock_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| {
+            format!(
+                "[DataQuality] {} range error: {}",
+                timeframe.deal_table(),
+                e
+            )
+        })?;
+
+    let days_sql = format!(
+        "SELECT DISTINCT {day} FROM {table} WHERE stock_id = $1",
+        table = timeframe.deal_table()
+    );
+    for (stock_id, first, last, count) in ranges {
+        let (Some(first), Some(last)) = (calendar::parse_day(&first), calendar::parse_day(&last))
+        else {
+            continue;
+        };
+        let mut expected: Vec<_> = calendar
+            .sessions_between(first, last)
+            .chain(calendar.suspects_between(first, last))
+            .collect();
+        expected.sort();
+        if (count as usize) >= expected.len() {
+            continue;
+        }
+
+        let existing: HashSet<String> = sqlx::query_scalar(&days_sql)
+            .bind(&stock_id)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .collect();
+        for date in expected {
+            let t = calendar::format_day(date);
+            if !existing.contains(&t) {
+                issues.push(DataIssue {
+                    stock_id: stock_id.clone(),
+                    timeframe,
+                    kind: IssueKind::MissingDay,
+                    t,
+                    detail: calendar
+                        .is_session(date)
+                        .is_none()
+                        .then(|| "market has no bars; possibly an unlisted holiday".to_string()),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn collect_issues(
+    pool: &SqlitePool,
+    calendar: &TradingCalendar,
+    stock_id: Option<&str>,
+) -> Result<Vec<DataIssue>, String> {
+    let mut issues = Vec::new();
+    for timeframe in TIMEFRAMES {
+        if timeframe != Timeframe::Weekly {
+            check_missing_days(pool, calendar, timeframe, stock_id, &mut issues).await?;
+        }
+        if timeframe == Timeframe::Hourly {
+            check_duplicates(pool, stock_id, &mut issues).await?;
+        }
+        check_prices(pool, timeframe, stock_id, &mut issues).await?;
+        check_orphan_skills(pool, timeframe, stock_id, &mut issues).await?;
+    }
+    Ok(issues)
+}
+
+/// 檢查本地 K 線與技術指標的資料品質
+pub async fn validate(
+    pool: &SqlitePool,
+    stock_id: Option<&str>,
+) -> Result<DataQualityReport, String> {
+    let calendar = TradingCalendar::load(pool).await?;
+    let checked_stocks: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM stock WHERE ($1 IS NULL OR stock_id = $1)")
+            .bind(stock_id)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+
+    let issues = collect_issues(pool, &calendar, stock_id).await?;
+    log::info!(
+        "[DataQuality] checked {} stocks, found {} issues",
+        checked_stocks,
+        issues.len()
+    );
+    Ok(DataQualityReport {
+        checked_stocks: checked_stocks as usize,
+        issues,
+        repaired: Vec::new(),
+    })
+}
+
+/// 需要重新下載的交易日 (YYYYMMDD)
+fn refetch_days<'a>(issues: &[&'a DataIssue]) -> BTreeSet<&'a str> {
+    issues
+        .iter()
+        .filter(|issue| issue.kind.needs_refetch())
+        .filter_map(|issue| issue.t.get(0..8))
+        .collect()
+}
+
+/// 只針對有問題的 (股票, 時框) 重新下載受影響的 K 線，並自最早受影響的時間點起重算指標
+async fn repair_one(
+    pool: &SqlitePool,
+    stock_id: &str,
+    timeframe: Timeframe,
+    issues: &[&DataIssue],
+    mode: PriceMode,
+) -> Result<RepairOutcome, String> {
+    let days = refetch_days(issues);
+    let fetched = if days.is_empty() {
+        Vec::new()
+    } else {
+        yahoo::fetch_bars(stock_id, timeframe).await?
+    };
+    apply_repair(pool, stock_id, timeframe, issues, fetched, mode).await
+}
+
+/// 寫入補抓的 K 線、刪除重複與孤兒資料後重算指標
+async fn apply_repair(
+    pool: &SqlitePool,
+    stock_id: &str,
+    timeframe: Timeframe,
+    issues: &[&DataIssue],
+    fetched: Vec<Bar>,
+    mode: PriceMode,
+) -> Result<RepairOutcome, String> {
+    let dashed = uses_dashed_ts(pool, timeframe, stock_id).await?;
+    // MissingDay 為 YYYYMMDD，其餘為資料表原始格式，需換算成同一格式才能比較先後
+    let from = issues
+        .iter()
+        .map(|issue| time_key(&issue.t))
+        .min()
+        .map(|key| table_time(timeframe, &key, dashed))
+        .unwrap_or_default();
+
+    let days = refetch_days(issues);
+    let bars: Vec<Bar> = fetched
+        .into_iter()
+        .filter(|bar| bar.is_valid())
+        .filter(|bar| bar.t.get(0..8).is_some_and(|day| days.contains(day)))
+        .map(|bar| Bar {
+            t: table_time(timeframe, &time_key(&bar.t), dashed),
+            ..bar
+        })
+        .collect();
+    writer::save_deals(pool, timeframe, stock_id, &bars).await?;
+    let refetched = bars.len();
+
+    let duplicates: Vec<String> = issues
+        .iter()
+        .filter(|issue| issue.kind == IssueKind::DuplicateBar)
+        .map(|issue| issue.t.clone())
+        .collect();
+    writer::delete_deals(pool, timeframe, stock_id, &duplicates).await?;
+    if !duplicates.is_empty() {
+        // 兩種格式的指標狀態排序交錯，保存的狀態可能已計入重複的 K 線，改為完整重算
+        incremental::invalidate(pool, timeframe, stock_id, None).await?;
+    }
+
+    let deals = writer::load_deals(pool, timeframe, stock_id).await?;
+    let deal_times: HashSet<&str> = deals.iter().map(|bar| bar.t.as_str()).collect();
+    let orphans: Vec<String> = issues
+        .iter()
+        .filter(|issue| {
+            matches!(
+                issue.kind,
+                IssueKind::OrphanSkills | IssueKind::DuplicateBar
+            )
+        })
+        .filter(|issue| !deal_times.contains(issue.t.as_str()))
+        .map(|issue| issue.t.clone())
+        .collect();
+    writer::delete_skills(pool, timeframe, stock_id, &orphans).await?;
+
+    // 指標具有遞迴性，自最早受影響的時間點之前的狀態接續重算
+    let recomputed = incremental::update(pool, timeframe, stock_id, &from, mode).await?;
+
+    Ok(RepairOutcome {
+        stock_id: stock_id.to_string(),
+        timeframe,
+        from,
+        refetched,
+        recomputed,
+        unresolved: 0,
+        error: None,
+    })
+}
+
+/// 修復報告中的問題，完成後重新檢查並回報仍未解決的數量
+pub async fn repair(
+    pool: &SqlitePool,
+    report: &mut DataQualityReport,
+    mode: PriceMode,
+) -> Result<(), String> {
+    let mut groups: BTreeMap<(&str, Timeframe), Vec<&DataIssue>> = BTreeMap::new();
+    for issue in &report.issues {
+        groups
+            .entry((issue.stock_id.as_str(), issue.timeframe))
+            .or_default()
+            .push(issue);
+    }
+
+    let calendar = TradingCalendar::load(pool).await?;
+    let mut outcomes = Vec::with_capacity(groups.len());
+    for ((stock_id, timeframe), issues) in groups {
+        let mut outcome = match repair_one(pool, stock_id, timeframe, &issues, mode).await {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                log::error!(
+                    "[DataQuality] repair {} ({:?}) failed: {}",
+                    stock_id,
+                    timeframe,
+                    e
+                );
+                RepairOutcome {
+                    stock_id: stock_id.to_string(),
+                    timeframe,
+                    from: String::new(),
+                    refetched: 0,
+                    recomputed: 0,
+                    unresolved: issues.len(),
+                    error: Some(e),
+                }
+            }
+        };
+        if outcome.error.is_none() {
+            outcome.unresolved = collect_issues(pool, &calendar, Some(stock_id))
+                .await?
+                .iter()
+                .filter(|issue| issue.timeframe == timeframe)
+                .count();
+        }
+        outcomes.push(outcome);
+    }
+    report.repaired = outcomes;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn validate_data(
+    app: tauri::AppHandle,
+    db: State<'_, Db>,
+    stock_id: Option<String>,
+    repair: Option<bool>,
+) -> Result<DataQualityReport, String> {
+    let mut report = validate(&db.0, stock_id.as_deref()).await?;
+    if repair.unwrap_or(false) && !report.issues.is_empty() {
+        self::repair(&db.0, &mut report, adjustment::price_mode(&app)).await?;
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sqlite::connection;
+
+    fn hourly(t: &str, c: f64) -> Bar {
+        Bar {
+            t: t.to_string(),
+            o: c,
+            h: c + 1.0,
+            l: c - 1.0,
+            c,
+            v: 1000,
+        }
+    }
+
+    async fn insert_hourly(pool: &SqlitePool, stock_id: &str, ts: &[&str]) {
+        let bars: Vec<Bar> = ts.iter().map(|t| hourly(t, 100.0)).collect();
+        writer::save_deals(pool, Timeframe::Hourly, stock_id, &bars)
+            .await
+            .unwrap();
+    }
+
+    fn day(t: &str) -> chrono::NaiveDate {
+        calendar::parse_day(t).unwrap()
+    }
+
+    #[test]
+    fn converts_between_time_formats() {
+        assert_eq!(time_key("20241008"), "202410080000");
+        assert_eq!(time_key("2024-10-08 09:00:00"), "202410080900");
+        assert_eq!(time_key("202410081300"), "202410081300");
+        assert_eq!(
+            table_time(Timeframe::Hourly, "202410080000", true),
+            "2024-10-08 00:00:00"
+        );
+        assert_eq!(
+            table_time(Timeframe::Hourly, "202410080900", false),
+            "202410080900"
+        );
+        assert_eq!(
+            table_time(Timeframe::Daily, "202410080000", false),
+            "20241008"
+        );
+    }
+
+    #[test]
+    fn detects_missing_days_against_calendar() {
+        tauri::async_runtime::block_on(async {
+            let pool = connection::memory().await;
+            connection::migrate(&pool, ..).await;
+            insert_hourly(
+                &pool,
+                "2330",
+                &["2024-10-07 09:00:00", "2024-10-09 09:00:00"],
+            )
+            .await;
+            // 10/10 國慶日休市，不應列為缺漏
+            let calendar = TradingCalendar::from_sessions(
+                ["20241007", "20241008", "20241009", "20241011"].map(day),
+            );
+            let mut issues = Vec::new();
+            check_missing_days(&pool, &calendar, Timeframe::Hourly, None, &mut issues)
+                .await
+                .unwrap();
+            let missing: Vec<&str> = issues.iter().map(|issue| issue.t.as_str()).collect();
+            assert_eq!(missing, ["20241008"]);
+            assert!(issues
+                .iter()
+                .all(|issue| issue.kind == IssueKind::MissingDay));
+        });
+    }
+
+    #[test]
+    fn detects_duplicate_hourly_formats() {
+        tauri::async_runtime::block_on(async {
+            let pool = connection::memory().await;
+            connection::migrate(&pool, ..).await;
+            insert_hourly(
+                &pool,
+                "2330",
+                &["2024-10-07 09:00:00", "202410070900", "2024-10-07 10:00:00"],
+            )
+            .await;
+            insert_hourly(&pool, "2317", &["2024-10-07 09:00:00"]).await;
+            let mut issues = Vec::new();
+            check_duplicates(&pool, None, &mut issues).await.unwrap();
+            assert_eq!(issues.len(), 1);
+            assert_eq!(issues[0].stock_id, "2330");
+            assert_eq!(issues[0].t, "2024-10-07 09:00:00");
+            assert_eq!(issues[0].kind, IssueKind::DuplicateBar);
+        });
+    }
+
+    #[test]
+    fn repairs_hourly_gap_in_table_format() {
+        tauri::async_runtime::block_on(async {
+            let pool = connection::memory().await;
+            connection::migrate(&pool, ..).await;
+            insert_hourly(
+                &pool,
+                "2330",
+                &[
+                    "2024-10-07 09:00:00",
+                    "2024-10-07 10:00:00",
+                    "2024-10-09 09:00:00",
+                    "2024-10-09 10:00:00",
+                ],
+            )
+            .await;
+            incremental::update(&pool, Timeframe::Hourly, "2330", "", PriceMode::Raw)
+                .await
+                .unwrap();
+
+            let issue = DataIssue {
+                stock_id: "2330".to_string(),
+                timeframe: Timeframe::Hourly,
+                kind: IssueKind::MissingDay,
+                t: "20241008".to_string(),
+                detail: None,
+            };
+            // Yahoo 回傳 YYYYMMDDHHmm，且包含非缺漏日的 K 線
+            let fetched = vec![
+                hourly("202410070900", 100.0),
+                hourly("202410080900", 101.0),
+                hourly("202410081000", 102.0),
+            ];
+            let outcome = apply_repair(
+                &pool,
+                "2330",
+                Timeframe::Hourly,
+                &[&issue],
+                fetched,
+                PriceMode::Raw,
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(outcome.from, "2024-10-08 00:00:00");
+            assert_eq!(outcome.refetched, 2);
+            // 10/8 兩根與其後 10/9 兩根
+            assert_eq!(outcome.recomputed, 4);
+            let ts: Vec<String> = sqlx::query_scalar(
+                "SELECT ts FROM hourly_deal WHERE stock_id = '2330' ORDER BY ts",
+            )
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+            assert_eq!(
+                ts,
+                [
+                    "2024-10-07 09:00:00",
+                    "2024-10-07 10:00:00",
+                    "2024-10-08 09:00:00",
+                    "2024-10-08 10:00:00",
+                    "2024-10-09 09:00:00",
+                    "2024-10-09 10:00:00",
+                ]
+            );
+            let skills: i64 =
+                sqlx::query_scalar("SELECT COUNT(*) FROM hourly_skills WHERE stock_id = '2330'")
+                    .fetch_one(&pool)
+                    .await
+                    .unwrap();
+            assert_eq!(skills, 6);
+        });
+    }
+
+    #[test]
+    fn repairs_duplicate_hourly_bars() {
+        tauri::async_runtime::block_on(async {
+            let pool = connection::memory().await;
+            connection::migrate(&pool, ..).await;
+            insert_hourly(&pool, "2330", &["2024-10-07 09:00:00", "202410070900"]).await;
+            incremental::update(&pool, Timeframe::Hourly, "2330", "", PriceMode::Raw)
+                .await
+                .unwrap();
+            let mut issues = Vec::new();
+            check_duplicates(&pool, Some("2330"), &mut issues)
+                .await
+                .unwrap();
+            let issues: Vec<&DataIssue> = issues.iter().collect();
+            apply_repair(
+                &pool,
+                "2330",
+                Timeframe::Hourly,
+                &issues,
+                Vec::new(),
+                PriceMode::Raw,
+            )
+            .await
+            .unwrap();
+            let ts: Vec<String> = sqlx::query_scalar("SELECT ts FROM hourly_skills ORDER BY ts")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+            assert_eq!(ts, ["202410070900"]);
+        });
+    }
+}
@@ -0,0 +1,704 @@
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::sqlite::SqliteRow;
+use sqlx::{Column, QueryBuilder, Row, Sqlite, SqlitePool, TypeInfo, ValueRef};
+use tauri::State;
+use tauri_plugin_dialog::DialogExt;
+
+use crate::indicators::SKILLS_COLUMNS;
+use crate::sqlite::connection::Db;
+
+/// 匯出時略過的基本面欄位 (與選股結果重複或僅供同步使用)
+const SKIPPED_FUNDAMENTAL_COLUMNS: &[&str] = &["stock_id", "updated_at"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Xlsx,
+    Json,
+}
+
+impl ExportFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Xlsx => "xlsx",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+/// 對應前端 `StockTableType`
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScreenRow {
+    pub stock_id: String,
+    pub stock_name: Option<String>,
+    pub industry_group: Option<String>,
+    pub market_type: Option<String>,
+}
+
+/// 對應 backtest-lib `ctx.record.history` 的項目 (日期可為數字或字串)，
+/// 也接受 `backtest::BacktestTrade` 的欄位名稱；損益與報酬率沿用交易本身的值，沒有則留空
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BacktestTrade {
+    #[serde(alias = "stock_id")]
+    pub id: String,
+    #[serde(alias = "stock_name")]
+    pub name: Option<String>,
+    #[serde(alias = "buy_date")]
+    pub buy_date: Value,
+    #[serde(alias = "buy_price")]
+    pub buy_price: f64,
+    #[serde(alias = "sell_date")]
+    pub sell_date: Value,
+    #[serde(alias = "sell_price")]
+    pub sell_price: f64,
+    pub quantity: Option<i64>,
+    pub profit: Option<f64>,
+    #[serde(alias = "return_pct")]
+    pub return_pct: Option<f64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "source", rename_all = "lowercase")]
+pub enum ExportData {
+    /// 選股結果，指標取選股日期 (YYYYMMDD) 的值
+    Screen { date: String, rows: Vec<ScreenRow> },
+    /// 回測交易，指標取買進日的值
+    Backtest { trades: Vec<BacktestTrade> },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExportRequest {
+    #[serde(flatten)]
+    pub data: ExportData,
+    pub format: ExportFormat,
+    /// 一併匯出 `financial_metric` / `recent_fundamental`
+    #[serde(default)]
+    pub include_fundamentals: bool,
+    pub file_name: Option<String>,
+}
+
+/// 依欄位順序排列的一列資料
+pub type Record = Vec<(String, Value)>;
+
+fn date_text(value: &Value) -> String {
+    match value {
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => s.replace(['-', '/'], ""),
+        _ => String::new(),
+    }
+}
+
+/// 依 SQLite 實際儲存型別轉為 JSON 值
+pub fn row_values(row: &SqliteRow) -> Record {
+    row.columns()
+        .iter()
+        .map(|column| {
+            let i = column.ordinal();
+            let type_name = row
+                .try_get_raw(i)
+                .ok()
+                .filter(|raw| !raw.is_null())
+                .map(|raw| raw.type_info().name().to_string());
+            let value = match type_name.as_deref() {
+                Some("INTEGER") => row.try_get::<i64, _>(i).map(Value::from).ok(),
+                Some("REAL") => row.try_get::<f64, _>(i).map(Value::from).ok(),
+                Some("TEXT") => row.try_get::<String, _>(i).map(Value::from).ok(),
+                _ => None,
+            };
+            (column.name().to_string(), value.unwrap_or(Value::Null))
+        })
+        .collect()
+}
+
+/// 每次查詢帶入的股票代號上限，避免超過 SQLite 參數數量限制
+const BATCH_SIZE: usize = 500;
+
+/// 沒有當日資料仍保留欄位，讓每列欄位一致
+fn empty_indicators() -> Record {
+    ["o", "h", "l", "c", "v"]
+        .iter()
+        .chain(SKILLS_COLUMNS)
+        .map(|c| (c.to_string(), Value::Null))
+        .collect()
+}
+
+/// 以 `prefix ... IN (...)` 分批查詢，回傳依第一欄 stock_id 分組的其餘欄位
+async fn batched_values(
+    pool: &SqlitePool,
+    prefix: &str,
+    t: Option<&str>,
+    stock_ids: &[&str],
+) -> Result<HashMap<String, Record>, String> {
+    let mut values = HashMap::new();
+    for chunk in stock_ids.chunks(BATCH_SIZE) {
+        let mut builder = QueryBuilder::<Sqlite>::new(prefix);
+        if let Some(t) = t {
+            builder.push_bind(t).push(" AND ");
+        }
+        builder.push("s.stock_id IN (");
+        let mut ids = builder.separated(", ");
+        for stock_id in chunk {
+            ids.push_bind(*stock_id);
+        }
+        builder.push(")");
+        let rows = builder
+            .build()
+            .fetch_all(pool)
+            .await
+            .map_err(|e| format!("[Export] load values error: {}", e))?;
+        for row in &rows {
+            let mut record = row_values(row);
+            let stock_id = cell_text(&record.remove(0).1);
+            values.insert(stock_id, record);
+        }
+    }
+    Ok(values)
+}
+
+/// 依日期分批讀取當日 K 線與全部技術指標，鍵為 (stock_id, t)
+async fn indicator_values(
+    pool: &SqlitePool,
+    keys: &[(&str, &str)],
+) -> Result<HashMap<(String, String), Record>, String> {
+    let prefix = format!(
+        "SELECT s.stock_id, s.o, s.h, s.l, s.c, s.v, {}
+         FROM daily_deal s
+         LEFT JOIN daily_skills k ON k.stock_id = s.stock_id AND k.t = s.t
+         WHERE s.t = ",
+        SKILLS_COLUMNS
+            .iter()
+            .map(|c| format!("k.{}", c))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    let mut by_date: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for (stock_id, t) in keys {
+        by_date.entry(t).or_default().push(stock_id);
+    }
+    let mut values = HashMap::new();
+    for (t, stock_ids) in by_date {
+        for (stock_id, record) in batched_values(pool, &prefix, Some(t), &stock_ids).await? {
+            values.insert((stock_id, t.to_string()), record);
+        }
+    }
+    Ok(values)
+}
+
+async fn fundamental_columns(pool: &SqlitePool, table: &str) -> Result<Vec<String>, String> {
+    let columns: Vec<String> = sqlx::query_scalar("SELECT name FROM pragma_table_info($1)")
+        .bind(table)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("[Export] load {} columns error: {}", table, e))?;
+    Ok(columns
+        .into_iter()
+        .filter(|c| !SKIPPED_FUNDAMENTAL_COLUMNS.contains(&c.as_str()))
+        .collect())
+}
+
+/// 一次讀取所有股票的基本面，鍵為 stock_id
+async fn fundamental_values(
+    pool: &SqlitePool,
+    stock_ids: &[&str],
+) -> Result<HashMap<String, Record>, String> {
+    let mut columns = vec!["s.stock_id".to_string()];
+    for (alias, table) in [("f", "financial_metric"), ("r", "recent_fundamental")] {
+        columns.extend(
+            fundamental_columns(pool, table)
+                .await?
+                .into_iter()
+                .map(|c| format!("{}.{}", alias, c)),
+        );
+    }
+    let prefix = format!(
+        "SELECT {} FROM stock s
+         LEFT JOIN financial_metric f ON f.stock_id = s.stock_id
+         LEFT JOIN recent_fundamental r ON r.stock_id = s.stock_id
+         WHERE ",
+        columns.join(", ")
+    );
+    batched_values(pool, &prefix, None, stock_ids).await
+}
+
+/// 組出每列資料：基本欄位、指標、(選用) 基本面
+async fn build_records(pool: &SqlitePool, request: &ExportRequest) -> Result<Vec<Record>, String> {
+    let base: Vec<(String, String, Record)> = match &request.data {
+        ExportData::Screen { date, rows } => rows
+            .iter()
+            .map(|row| {
+                let record = vec![
+                    ("t".to_string(), Value::from(date.as_str())),
+                    ("stock_id".to_string(), Value::from(row.stock_id.as_str())),
+                    (
+                        "stock_name".to_string(),
+                        Value::from(row.stock_name.clone()),
+                    ),
+                    (
+                        "industry_group".to_string(),
+                        Value::from(row.industry_group.clone()),
+                    ),
+                    (
+                        "market_type".to_string(),
+                        Value::from(row.market_type.clone()),
+                    ),
+                ];
+                (row.stock_id.clone(), date.clone(), record)
+            })
+            .collect(),
+        ExportData::Backtest { trades } => trades
+            .iter()
+            .map(|trade| {
+                let buy_date = date_text(&trade.buy_date);
+                let record = vec![
+                    ("stock_id".to_string(), Value::from(trade.id.as_str())),
+                    ("stock_name".to_string(), Value::from(trade.name.clone())),
+                    ("buy_date".to_string(), Value::from(buy_date.as_str())),
+                    ("buy_price".to_string(), Value::from(trade.buy_price)),
+                    (
+                        "sell_date".to_string(),
+                        Value::from(date_text(&trade.sell_date)),
+                    ),
+                    ("sell_price".to_string(), Value::from(trade.sell_price)),
+                    ("quantity".to_string(), Value::from(trade.quantity)),
+                    ("profit".to_string(), Value::from(trade.profit)),
+                    ("return_pct".to_string(), Value::from(trade.return_pct)),
+                ];
+                (trade.id.clone(), buy_date, record)
+            })
+            .collect(),
+    };
+
+    let keys: Vec<(&str, &str)> = base
+        .iter()
+        .map(|(stock_id, t, _)| (stock_id.as_str(), t.as_str()))
+        .collect();
+    let indicators = indicator_values(pool, &keys).await?;
+    let fundamentals = match request.include_fundamentals {
+        true => {
+            let mut stock_ids: Vec<&str> = keys.iter().map(|(stock_id, _)| *stock_id).collect();
+            stock_ids.sort_unstable();
+            stock_ids.dedup();
+            Some(fundamental_values(pool, &stock_ids).await?)
+        }
+        false => None,
+    };
+    let mut records = Vec::with_capacity(base.len());
+    for (stock_id, t, mut record) in base {
+        let fundamental = fundamentals
+            .as_ref()
+            .and_then(|values| values.get(&stock_id).cloned());
+        record.extend(
+            indicators
+                .get(&(stock_id, t))
+                .cloned()
+                .unwrap_or_else(empty_indicators),
+        );
+        record.extend(fundamental.unwrap_or_default());
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// 以出現順序收集所有欄位名稱
+fn headers(records: &[Record]) -> Vec<String> {
+    let mut headers: Vec<String> = Vec::new();
+    for record in records {
+        for (key, _) in record {
+            if !headers.contains(key) {
+                headers.push(key.clone());
+            }
+        }
+    }
+    headers
+}
+
+fn cell_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn cell<'a>(record: &'a Record, header: &str) -> Option<&'a Value> {
+    record.iter().find(|(key, _)| key == header).map(|(_, v)| v)
+}
+
+fn write_csv(path: &Path, records: &[Record]) -> Result<(), String> {
+    let headers = headers(records);
+    let mut file =
+        std::fs::File::create(path).map_err(|e| format!("[Export] create file error: {}", e))?;
+    // 加上 BOM 讓 Excel 以 UTF-8 開啟中文
+    file.write_all(b"\xEF\xBB\xBF")
+        .map_err(|e| format!("[Export] write error: {}", e))?;
+    let mut writer = csv::Writer::from_writer(file);
+    writer
+        .write_record(&headers)
+        .map_err(|e| format!("[Export] write error: {}", e))?;
+    for record in records {
+        writer
+            .write_record(
+                headers
+                    .iter()
+                    .map(|h| cell(record, h).map(cell_text).unwrap_or_default()),
+            )
+            .map_err(|e| format!("[Export] write error: {}", e))?;
+    }
+    writer
+        .flush()
+        .map_err(|e| format!("[Export] write error: {}", e))
+}
+
+fn write_json(path: &Path, records: &[Record]) -> Result<(), String> {
+    let rows: Vec<serde_json::Map<String, Value>> = records
+        .iter()
+        .map(|record| record.iter().cloned().collect())
+        .collect();
+    let text = serde_json::to_string_pretty(&rows).map_err(|e| e.to_string())?;
+    std::fs::write(path, text).map_err(|e| format!("[Export] write error: {}", e))
+}
+
+/// 欄位索引 (從 0 開始) 轉為 Excel 欄名 `A`、`AB`
+fn column_name(mut index: usize) -> String {
+    let mut name = Vec::new();
+    loop {
+        name.push(b'A' + (index % 26) as u8);
+        if index < 26 {
+            break;
+        }
+        index = index / 26 - 1;
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap_or_default()
+}
+
+fn xlsx_cell(reference: &str, value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::Number(n) => format!(r#"<c r="{}"><v>{}</v></c>"#, reference, n),
+        Value::Bool(b) => format!(r#"<c r="{}" t="b"><v>{}</v></c>"#, reference, *b as u8),
+        other => format!(
+            r#"<c r="{}" t="inlineStr"><is><t>{}</t></is></c>"#,
+            reference,
+            quick_xml::escape::escape(cell_text(other).as_str())
+        ),
+    }
+}
+
+fn sheet_xml(records: &[Record]) -> String {
+    let headers = headers(records);
+    let mut xml = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#,
+    );
+    let header_values: Record = headers
+        .iter()
+        .map(|h| (h.clone(), Value::from(h.as_str())))
+        .collect();
+    for (i, record) in std::iter::once(&header_values).chain(records).enumerate() {
+        xml.push_str(&format!(r#"<row r="{}">"#, i + 1));
+        for (j, header) in headers.iter().enumerate() {
+            if let Some(value) = cell(record, header) {
+                xml.push_str(&xlsx_cell(&format!("{}{}", column_name(j), i + 1), value));
+            }
+        }
+        xml.push_str("</row>");
+    }
+    xml.push_str("</sheetData></worksheet>");
+    xml
+}
+
+/// 以最小的 OOXML 結構輸出單一工作表
+fn write_xlsx(path: &Path, records: &[Record]) -> Result<(), String> {
+    const PARTS: [(&str, &str); 4] = [
+        (
+            "[Content_Types].xml",
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/><Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/></Types>"#,
+        ),
+        (
+            "_rels/.rels",
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>"#,
+        ),
+        (
+            "xl/workbook.xml",
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="Sheet1" sheetId="1" r:id="rId1"/></sheets></workbook>"#,
+        ),
+        (
+            "xl/_rels/workbook.xml.rels",
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/></Relationships>"#,
+        ),
+    ];
+    let file =
+        std::fs::File::create(path).map_err(|e| format!("[Export] create file error: {}", e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    let sheet = sheet_xml(records);
+    for (name, content) in PARTS
+        .iter()
+        .copied()
+        .chain([("xl/worksheets/sheet1.xml", sheet.as_str())])
+    {
+        zip.start_file(name, options)
+            .map_err(|e| format!("[Export] write xlsx error: {}", e))?;
+        zip.write_all(content.as_bytes())
+            .map_err(|e| format!("[Export] write xlsx error: {}", e))?;
+    }
+    zip.finish()
+        .map_err(|e| format!("[Export] write xlsx error: {}", e))?;
+    Ok(())
+}
+
+async fn pick_path(
+    app: &tauri::AppHandle,
+    format: ExportFormat,
+    file_name: String,
+) -> Result<Option<PathBuf>, String> {
+    let dialog = app
+        .dialog()
+        .file()
+        .add_filter(format.extension().to_uppercase(), &[format.extension()])
+        .set_file_name(file_name);
+    let picked = tauri::async_runtime::spawn_blocking(move || dialog.blocking_save_file())
+        .await
+        .map_err(|e| e.to_string())?;
+    picked
+        .map(|path| path.into_path().map_err(|e| e.to_string()))
+        .transpose()
+}
+
+/// 以存檔對話框選擇路徑並匯出，使用者取消時回傳 None
+#[tauri::command]
+pub async fn export_results(
+    app: tauri::AppHandle,
+    db: State<'_, Db>,
+    request: ExportRequest,
+) -> Result<Option<String>, String> {
+    let default_name = match &request.data {
+        ExportData::Screen { date, .. } => format!("screen_{}", date),
+        ExportData::Backtest { .. } => "backtest".to_string(),
+    };
+    let file_name = format!(
+        "{}.{}",
+        request.file_name.clone().unwrap_or(default_name),
+        request.format.extension()
+    );
+    let Some(path) = pick_path(&app, request.format, file_name).await? else {
+        return Ok(None);
+    };
+
+    let records = build_records(&db.0, &request).await?;
+    match request.format {
+        ExportFormat::Csv => write_csv(&path, &records)?,
+        ExportFormat::Xlsx => write_xlsx(&path, &records)?,
+        ExportFormat::Json => write_json(&path, &records)?,
+    }
+    log::info!(
+        "[Export] {} rows written to {}",
+        records.len(),
+        path.display()
+    );
+    Ok(Some(path.display().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    use crate::sqlite::connection;
+
+    fn records() -> Vec<Record> {
+        vec![
+            vec![
+                ("stock_id".to_string(), Value::from("2330")),
+                ("stock_name".to_string(), Value::from("台積電")),
+                ("c".to_string(), Value::from(580.5)),
+            ],
+            vec![
+                ("stock_id".to_string(), Value::from("2317")),
+                ("stock_name".to_string(), Value::from("A & <B>")),
+                ("c".to_string(), Value::Null),
+                ("v".to_string(), Value::from(12)),
+            ],
+        ]
+    }
+
+    fn temp_path(extension: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "schoice-export-{}.{}",
+            std::process::id(),
+            extension
+        ))
+    }
+
+    #[test]
+    fn converts_column_names() {
+        assert_eq!(column_name(0), "A");
+        assert_eq!(column_name(25), "Z");
+        assert_eq!(column_name(26), "AA");
+        assert_eq!(column_name(51), "AZ");
+        assert_eq!(column_name(52), "BA");
+        assert_eq!(column_name(701), "ZZ");
+        assert_eq!(column_name(702), "AAA");
+    }
+
+    #[test]
+    fn normalizes_date_text() {
+        assert_eq!(date_text(&Value::from(20240102)), "20240102");
+        assert_eq!(date_text(&Value::from("2024-01-02")), "20240102");
+        assert_eq!(date_text(&Value::from("2024/01/02")), "20240102");
+        assert_eq!(date_text(&Value::Null), "");
+    }
+
+    #[test]
+    fn writes_csv_with_bom_and_union_headers() {
+        let path = temp_path("csv");
+        write_csv(&path, &records()).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let text = String::from_utf8(bytes).unwrap();
+        assert_eq!(
+            text,
+            "\u{feff}stock_id,stock_name,c,v\n2330,台積電,580.5,\n2317,A & <B>,,12\n"
+        );
+    }
+
+    #[test]
+    fn writes_json_rows() {
+        let path = temp_path("json");
+        write_json(&path, &records()).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let rows: Vec<serde_json::Map<String, Value>> = serde_json::from_str(&text).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["c"], Value::from(580.5));
+        assert_eq!(rows[1]["c"], Value::Null);
+        assert_eq!(rows[1]["v"], Value::from(12));
+    }
+
+    #[test]
+    fn writes_xlsx_sheet() {
+        let path = temp_path("xlsx");
+        write_xlsx(&path, &records()).unwrap();
+        let file = std::fs::File::open(&path).unwrap();
+        let mut archive = zip::ZipArchive::new(file).unwrap();
+        assert!(archive.by_name("xl/workbook.xml").is_ok());
+        let mut sheet = String::new();
+        archive
+            .by_name("xl/worksheets/sheet1.xml")
+            .unwrap()
+            .read_to_string(&mut sheet)
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(sheet.contains(r#"<c r="D1" t="inlineStr"><is><t>v</t></is></c>"#));
+        assert!(sheet.contains(r#"<c r="C2"><v>580.5</v></c>"#));
+        assert!(sheet.contains(r#"<c r="B3" t="inlineStr"><is><t>A &amp; &lt;B&gt;</t></is></c>"#));
+        // NULL 不輸出儲存格
+        assert!(!sheet.contains(r#"r="C3""#));
+        assert!(sheet.contains(r#"<c r="D3"><v>12</v></c>"#));
+    }
+
+    #[test]
+    fn accepts_rust_backtest_trades() {
+        let trade = crate::backtest::BacktestTrade {
+            stock_id: "2330".to_string(),
+            stock_name: Some("台積電".to_string()),
+            buy_date: "20240102".to_string(),
+            buy_price: 590.0,
+            sell_date: "20240109".to_string(),
+            sell_price: 600.0,
+            quantity: 1000,
+            profit: 7_850.0,
+            return_pct: 1.33,
+            reason: crate::backtest::ExitReason::Hold,
+        };
+        let parsed: BacktestTrade =
+            serde_json::from_value(serde_json::to_value(&trade).unwrap()).unwrap();
+        assert_eq!(parsed.id, "2330");
+        assert_eq!(parsed.buy_date, Value::from("20240102"));
+        assert_eq!(parsed.quantity, Some(1000));
+        assert_eq!(parsed.profit, Some(7_850.0));
+        assert_eq!(parsed.return_pct, Some(1.33));
+    }
+
+    #[test]
+    fn builds_records_in_batches() {
+        tauri::async_runtime::block_on(async {
+            let pool = connection::memory().await;
+            connection::migrate(&pool, ..).await;
+            for (stock_id, t, c) in [
+                ("2330", "20240102", 590.0),
+                ("2330", "20240103", 580.0),
+                ("2317", "20240102", 105.0),
+            ] {
+                sqlx::query(
+                    "INSERT INTO daily_deal (stock_id, t, c, o, h, l, v) VALUES ($1, $2, $3, $3, $3, $3, 1000)",
+                )
+                .bind(stock_id)
+                .bind(t)
+                .bind(c)
+                .execute(&pool)
+                .await
+                .unwrap();
+            }
+            sqlx::query(
+                "INSERT INTO daily_skills (stock_id, t, ma5) VALUES ('2330', '20240103', 585)",
+            )
+            .execute(&pool)
+            .await
+            .unwrap();
+            sqlx::query("INSERT INTO stock (stock_id, stock_name) VALUES ('2330', '台積電')")
+                .execute(&pool)
+                .await
+                .unwrap();
+
+            let trade = |id: &str, buy_date: Value| BacktestTrade {
+                id: id.to_string(),
+                name: None,
+                buy_date,
+                buy_price: 100.0,
+                sell_date: Value::from("2024-01-10"),
+                sell_price: 110.0,
+                quantity: Some(1000),
+                profit: Some(9_500.0),
+                return_pct: Some(9.5),
+            };
+            let request = ExportRequest {
+                data: ExportData::Backtest {
+                    trades: vec![
+                        trade("2330", Value::from("2024-01-03")),
+                        trade("2317", Value::from(20240102)),
+                        trade("2330", Value::from("2024/01/02")),
+                        trade("9999", Value::from("2024-01-02")),
+                    ],
+                },
+                format: ExportFormat::Csv,
+                include_fundamentals: true,
+                file_name: None,
+            };
+            let records = build_records(&pool, &request).await.unwrap();
+            let value = |i: usize, key: &str| cell(&records[i], key).cloned();
+            assert_eq!(records.len(), 4);
+            assert_eq!(value(0, "c"), Some(Value::from(580.0)));
+            assert_eq!(value(0, "ma5"), Some(Value::from(585.0)));
+            assert_eq!(value(1, "c"), Some(Value::from(105.0)));
+            assert_eq!(value(2, "c"), Some(Value::from(590.0)));
+            assert_eq!(value(2, "ma5"), Some(Value::Null));
+            assert_eq!(value(3, "c"), Some(Value::Null));
+            // 損益沿用交易本身 (已扣費用) 的值，不以價差重算
+            assert_eq!(value(0, "profit"), Some(Value::from(9_500.0)));
+            assert_eq!(value(0, "return_pct"), Some(Value::from(9.5)));
+            // 每列欄位一致，有基本面資料的股票多出基本面欄位
+            assert_eq!(records[1].len(), records[3].len());
+            assert!(records[0].len() > records[1].len());
+        });
+    }
+}
@@ -0,0 +1,650 @@
+use scraper::{ElementRef, Html, Selector};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tauri_plugin_http::reqwest;
+
+/// 取最近幾期的月營收、EPS 與籌碼，對應資料表的 `_m1`~`_m4` / `_q1`~`_q4` / `_w1`~`_w4`
+const RECENT_PERIODS: usize = 4;
+
+/// profile 頁面的指標標籤與 `financial_metric` 欄位
+const METRIC_LABELS: &[(&str, &str)] = &[
+    ("營業毛利率", "gross_profit_margin"),
+    ("營業利益率", "operating_margin"),
+    ("稅前淨利率", "pre_tax_profit_margin"),
+    ("資產報酬率", "roa"),
+    ("股東權益報酬率", "roe"),
+    ("每股淨值", "book_value_per_share"),
+    ("本益比", "pe"),
+    ("股價淨值比", "pb"),
+    ("殖利率", "dividend_yield"),
+];
+const REPORT_PERIOD_LABEL: &str = "財報季度";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Page {
+    Profile,
+    Revenue,
+    MajorHolders,
+}
+
+impl Page {
+    fn path(self) -> &'static str {
+        match self {
+            Page::Profile => "profile",
+            Page::Revenue => "revenue",
+            Page::MajorHolders => "major-holders",
+        }
+    }
+}
+
+fn quote_url(stock_id: &str, suffix: &str, page: Page) -> String {
+    format!(
+        "https://tw.stock.yahoo.com/quote/{}{}/{}",
+        stock_id,
+        suffix,
+        page.path()
+    )
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct FinancialMetric {
+    pub stock_id: String,
+    pub pe: Option<f64>,
+    pub pb: Option<f64>,
+    pub dividend_yield: Option<f64>,
+    pub report_period: Option<String>,
+    pub gross_profit_margin: Option<f64>,
+    pub operating_margin: Option<f64>,
+    pub pre_tax_profit_margin: Option<f64>,
+    pub roa: Option<f64>,
+    pub roe: Option<f64>,
+    pub book_value_per_share: Option<f64>,
+}
+
+impl FinancialMetric {
+    fn field_mut(&mut self, column: &str) -> Option<&mut Option<f64>> {
+        Some(match column {
+            "pe" => &mut self.pe,
+            "pb" => &mut self.pb,
+            "dividend_yield" => &mut self.dividend_yield,
+            "gross_profit_margin" => &mut self.gross_profit_margin,
+            "operating_margin" => &mut self.operating_margin,
+            "pre_tax_profit_margin" => &mut self.pre_tax_profit_margin,
+            "roa" => &mut self.roa,
+            "roe" => &mut self.roe,
+            "book_value_per_share" => &mut self.book_value_per_share,
+            _ => return None,
+        })
+    }
+
+    fn missing(&self) -> Vec<String> {
+        let mut metric = self.clone();
+        let mut missing: Vec<String> = METRIC_LABELS
+            .iter()
+            .filter(|(_, column)| metric.field_mut(column).is_some_and(|v| v.is_none()))
+            .map(|(_, column)| column.to_string())
+            .collect();
+        if self.report_period.is_none() {
+            missing.push("report_period".to_string());
+        }
+        missing
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MonthlyRevenue {
+    /// 例如 2026/09
+    pub name: String,
+    /// 當月營收 (仟元)
+    pub revenue: Option<f64>,
+    pub mom: Option<f64>,
+    pub yoy: Option<f64>,
+    pub yoy_acc: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Eps {
+    /// 季度為 `2026 Q2`，年度為 `2025`
+    pub name: String,
+    pub value: f64,
+}
+
+/// 對應 `recent_fundamental`，各清單由新到舊排列
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RecentFundamental {
+    pub stock_id: String,
+    pub revenue: Vec<MonthlyRevenue>,
+    pub eps_quarters: Vec<Eps>,
+    pub eps_years: Vec<Eps>,
+}
+
+impl RecentFundamental {
+    /// 攤平成資料表欄位，只輸出有找到的期數
+    pub fn columns(&self) -> Map<String, Value> {
+        let mut map = Map::new();
+        map.insert("stock_id".into(), self.stock_id.clone().into());
+        for (i, month) in self.revenue.iter().enumerate() {
+            let prefix = format!("revenue_recent_m{}", i + 1);
+            map.insert(format!("{}_name", prefix), month.name.clone().into());
+            map.insert(prefix.clone(), month.revenue.into());
+            map.insert(format!("{}_mom", prefix), month.mom.into());
+            map.insert(format!("{}_yoy", prefix), month.yoy.into());
+            map.insert(format!("{}_yoy_acc", prefix), month.yoy_acc.into());
+        }
+        for (kind, list) in [("q", &self.eps_quarters), ("y", &self.eps_years)] {
+            for (i, eps) in list.iter().enumerate() {
+                let prefix = format!("eps_recent_{}{}", kind, i + 1);
+                map.insert(format!("{}_name", prefix), eps.name.clone().into());
+                map.insert(prefix, eps.value.into());
+            }
+        }
+        map
+    }
+
+    fn missing(&self) -> Vec<String> {
+        let mut missing = Vec::new();
+        for (prefix, found) in [
+            ("revenue_recent_m", self.revenue.len()),
+            ("eps_recent_q", self.eps_quarters.len()),
+            ("eps_recent_y", self.eps_years.len()),
+        ] {
+            missing.extend((found + 1..=RECENT_PERIODS).map(|i| format!("{}{}", prefix, i)));
+        }
+        missing
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WeeklyHolding {
+    /// 週別日期，例如 2026/10/17
+    pub name: String,
+    pub foreign_ratio: Option<f64>,
+    pub big_investor_ratio: Option<f64>,
+}
+
+/// 對應 `investor_positions`，由新到舊排列
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct InvestorPositions {
+    pub stock_id: String,
+    pub weeks: Vec<WeeklyHolding>,
+}
+
+impl InvestorPositions {
+    pub fn columns(&self) -> Map<String, Value> {
+        let mut map = Map::new();
+        map.insert("stock_id".into(), self.stock_id.clone().into());
+        for (i, week) in self.weeks.iter().enumerate() {
+            let prefix = format!("recent_w{}", i + 1);
+            map.insert(format!("{}_name", prefix), week.name.clone().into());
+            map.insert(
+                format!("{}_foreign_ratio", prefix),
+                week.foreign_ratio.into(),
+            );
+            map.insert(
+                format!("{}_big_investor_ratio", prefix),
+                week.big_investor_ratio.into(),
+            );
+        }
+        map
+    }
+
+    fn missing(&self) -> Vec<String> {
+        (self.weeks.len() + 1..=RECENT_PERIODS)
+            .map(|i| format!("recent_w{}", i))
+            .collect()
+    }
+}
+
+/// 前端 `fetchStockExtData` 使用的格式：三張表攤平成欄位，並附上缺漏欄位
+#[derive(Debug, Clone, Serialize)]
+pub struct YahooFundamentals {
+    /// 實際取得資料的代號後綴 `.TW` / `.TWO`
+    pub suffix: String,
+    pub metrics: FinancialMetric,
+    pub fundamentals: Map<String, Value>,
+    pub positions: Map<String, Value>,
+    pub missing: Vec<String>,
+}
+
+fn selector(css: &str) -> Selector {
+    Selector::parse(css).unwrap_or_else(|_| panic!("invalid selector {}", css))
+}
+
+fn text_of(el: &ElementRef) -> String {
+    el.text().collect::<String>().trim().to_string()
+}
+
+fn element_children<'a>(el: &ElementRef<'a>) -> Vec<ElementRef<'a>> {
+    el.children().filter_map(ElementRef::wrap).collect()
+}
+
+fn has_class(el: &ElementRef, class: &str) -> bool {
+    el.value().attr("class").unwrap_or("").contains(class)
+}
+
+/// 與前端 `pf` 相同，去除數字以外的字元 (含 %、逗號、單位) 後解析
+fn number(text: &str) -> Option<f64> {
+    let digits: String = text
+        .chars()
+        .filter(|c| c.is_ascii_digit() || *c == '.' || *c == '-')
+        .collect();
+    digits.parse().ok()
+}
+
+/// 只取開頭的數值，例如 `25.31倍`；`-` 或 `N/A` 回傳 None
+fn leading_number(text: &str) -> Option<f64> {
+    let text = text.trim().replace([',', '%'], "");
+    let end = text
+        .char_indices()
+        .find(|(i, c)| !(c.is_ascii_digit() || *c == '.' || (*i == 0 && *c == '-')))
+        .map_or(text.len(), |(i, _)| i);
+    text[..end].parse().ok()
+}
+
+/// 標籤所在元素的數值：同層最後一個元素、同層第一個元素 (數值在上方的版型)、下一個兄弟元素
+fn labelled_text<'a>(label: &ElementRef<'a>) -> Vec<String> {
+    let own = text_of(label);
+    let mut candidates = Vec::new();
+    if let Some(parent) = label.parent().and_then(ElementRef::wrap) {
+        let siblings = element_children(&parent);
+        candidates.extend(siblings.last().map(text_of));
+        candidates.extend(siblings.first().map(text_of));
+    }
+    candidates.extend(
+        label
+            .next_siblings()
+            .filter_map(ElementRef::wrap)
+            .next()
+            .map(|el| text_of(&el)),
+    );
+    candidates.retain(|text| !text.is_empty() && *text != own);
+    candidates
+}
+
+fn matches_label(text: &str, label: &str) -> bool {
+    text == label || (text.contains(label) && text.chars().count() < 15)
+}
+
+/// profile 頁：財務比率與報表季度
+pub fn parse_metrics(stock_id: &str, html: &str) -> FinancialMetric {
+    let document = Html::parse_document(html);
+    let mut metric = FinancialMetric {
+        stock_id: stock_id.to_string(),
+        ..Default::default()
+    };
+    for el in document.select(&selector("div, span")) {
+        if !element_children(&el).is_empty() {
+            continue;
+        }
+        let text = text_of(&el);
+        if text.is_empty() {
+            continue;
+        }
+        for (label, column) in METRIC_LABELS {
+            if !matches_label(&text, label) {
+                continue;
+            }
+            let value = labelled_text(&el).iter().find_map(|t| leading_number(t));
+            if let (Some(value), Some(field)) = (value, metric.field_mut(column)) {
+                *field = Some((value * 100.0).round() / 100.0);
+            }
+        }
+        if text.contains(REPORT_PERIOD_LABEL) && metric.report_period.is_none() {
+            metric.report_period = labelled_text(&el).into_iter().next();
+        }
+    }
+    metric
+}
+
+fn parse_eps_name(label: &str) -> Option<(bool, String)> {
+    let label = label.split_whitespace().collect::<Vec<_>>().join(" ");
+    let quarterly = label.len() == 7
+        && label[..4].chars().all(|c| c.is_ascii_digit())
+        && &label[4..6] == " Q"
+        && label[6..].chars().all(|c| c.is_ascii_digit());
+    let yearly = label.len() == 4 && label.chars().all(|c| c.is_ascii_digit());
+    (quarterly || yearly).then_some((quarterly, label))
+}
+
+/// profile 頁：EPS 表格的標籤 (`As(st)`) 與數值 (`Py(8px)`) 依順序配對
+pub fn parse_eps(html: &str) -> (Vec<Eps>, Vec<Eps>) {
+    let document = Html::parse_document(html);
+    let labels: Vec<String> = document
+        .select(&selector(".table-grid .grid-item span"))
+        .filter(|el| has_class(el, "As(st)"))
+        .map(|el| text_of(&el))
+        .collect();
+    let values: Vec<String> = document
+        .select(&selector(".table-grid .grid-item div"))
+        .filter(|el| has_class(el, "Py(8px)"))
+        .map(|el| text_of(&el))
+        .collect();
+
+    let mut quarters: Vec<Eps> = Vec::new();
+    let mut years: Vec<Eps> = Vec::new();
+    for (label, value) in labels.iter().zip(values.iter()) {
+        let (Some((quarterly, name)), Some(value)) = (
+            parse_eps_name(label),
+            leading_number(&value.replace('元', "")),
+        ) else {
+            continue;
+        };
+        let list = if quarterly { &mut quarters } else { &mut years };
+        if !list.iter().any(|eps| eps.name == name) {
+            list.push(Eps { name, value });
+        }
+    }
+    // `2026 Q2` 與 `2025` 皆可直接以字串由新到舊排序
+    quarters.sort_by(|a, b| b.name.cmp(&a.name));
+    years.sort_by(|a, b| b.name.cmp(&a.name));
+    quarters.truncate(RECENT_PERIODS);
+    years.truncate(RECENT_PERIODS);
+    (quarters, years)
+}
+
+/// 新版 `.table-row` 表格的每一列，回傳各欄元素
+fn table_rows<'a>(document: &'a Html) -> Vec<Vec<ElementRef<'a>>> {
+    document
+        .select(&selector(".table-body-wrapper .table-row"))
+        .map(|row| element_children(&row))
+        .collect()
+}
+
+/// 舊版 `.table-grid` 版型的所有數值格
+fn grid_values(document: &Html) -> Vec<String> {
+    document
+        .select(&selector(".table-grid .grid-item div"))
+        .filter(|el| has_class(el, "Py(8px)"))
+        .map(|el| text_of(&el))
+        .collect()
+}
+
+fn list_items(cell: &ElementRef) -> Vec<String> {
+    cell.select(&selector("li"))
+        .map(|el| text_of(&el))
+        .collect()
+}
+
+/// revenue 頁：月營收、月增率、年增率與累計年增率
+pub fn parse_revenue(html: &str) -> Vec<MonthlyRevenue> {
+    let document = Html::parse_document(html);
+    let rows = table_rows(&document);
+    let mut months = Vec::new();
+    if !rows.is_empty() {
+        for cols in rows {
+            if months.len() >= RECENT_PERIODS {
+                break;
+            }
+            let monthly = cols.get(1).map(list_items).unwrap_or_default();
+            if monthly.len() < 4 {
+                continue;
+            }
+            let accumulated = cols.get(2).map(list_items).unwrap_or_default();
+            months.push(MonthlyRevenue {
+                name: cols.first().map(text_of).unwrap_or_default(),
+                revenue: number(&monthly[0]),
+                mom: number(&monthly[1]),
+                yoy: number(&monthly[3]),
+                yoy_acc: accumulated.get(2).and_then(|t| number(t)),
+            });
+        }
+        return months;
+    }
+    // 舊版每月 8 格：月份、營收、月增率、去年同月、年增率、累計營收、去年累計、累計年增率
+    for chunk in grid_values(&document).chunks_exact(8).take(RECENT_PERIODS) {
+        months.push(MonthlyRevenue {
+            name: chunk[0].clone(),
+            revenue: number(&chunk[1]),
+            mom: number(&chunk[2]),
+            yoy: number(&chunk[4]),
+            yoy_acc: number(&chunk[7]),
+        });
+    }
+    months
+}
+
+/// major-holders 頁：每週外資與大戶持股比例
+pub fn parse_holders(html: &str) -> Vec<WeeklyHolding> {
+    let document = Html::parse_document(html);
+    let rows = table_rows(&document);
+    if !rows.is_empty() {
+        return rows
+            .into_iter()
+            .filter(|cols| cols.len() >= 4)
+            .take(RECENT_PERIODS)
+            .map(|cols| WeeklyHolding {
+                name: text_of(&cols[0]),
+                foreign_ratio: number(&text_of(&cols[1])),
+                big_investor_ratio: number(&text_of(&cols[2])),
+            })
+            .collect();
+    }
+    // 舊版每週 5 格：日期、外資、大戶、董監、股東人數
+    grid_values(&document)
+        .chunks(5)
+        .filter(|chunk| chunk.len() >= 3)
+        .take(RECENT_PERIODS)
+        .map(|chunk| WeeklyHolding {
+            name: chunk[0].clone(),
+            foreign_ratio: number(&chunk[1]),
+            big_investor_ratio: number(&chunk[2]),
+        })
+        .collect()
+}
+
+/// 三個頁面的原始 HTML，無法取得的頁面為 None
+#[derive(Debug, Default)]
+pub struct QuotePages {
+    pub profile: Option<String>,
+    pub revenue: Option<String>,
+    pub holders: Option<String>,
+}
+
+pub fn parse_pages(stock_id: &str, suffix: &str, pages: &QuotePages) -> YahooFundamentals {
+    let mut metrics = FinancialMetric {
+        stock_id: stock_id.to_string(),
+        ..Default::default()
+    };
+    let mut fundamental = RecentFundamental {
+        stock_id: stock_id.to_string(),
+        ..Default::default()
+    };
+    if let Some(html) = &pages.profile {
+        metrics = parse_metrics(stock_id, html);
+        (fundamental.eps_quarters, fundamental.eps_years) = parse_eps(html);
+    }
+    if let Some(html) = &pages.revenue {
+        fundamental.revenue = parse_revenue(html);
+    }
+    let positions = InvestorPositions {
+        stock_id: stock_id.to_string(),
+        weeks: pages
+            .holders
+            .as_deref()
+            .map(parse_holders)
+            .unwrap_or_default(),
+    };
+
+    let mut missing = metrics.missing();
+    missing.extend(fundamental.missing());
+    missing.extend(positions.missing());
+    YahooFundamentals {
+        suffix: suffix.to_string(),
+        metrics,
+        fundamentals: fundamental.columns(),
+        positions: positions.columns(),
+        missing,
+    }
+}
+
+async fn fetch_page(stock_id: &str, suffix: &str, page: Page) -> Option<String> {
+    let url = quote_url(stock_id, suffix, page);
+    let response = match reqwest::get(&url).await {
+        Ok(response) if response.status().is_success() => response,
+        Ok(response) => {
+            log::warn!("[Fundamentals] {} returned {}", url, response.status());
+            return None;
+        }
+        Err(e) => {
+            log::warn!("[Fundamentals] {} request error: {}", url, e);
+            return None;
+        }
+    };
+    response.text().await.ok()
+}
+
+async fn scrape(stock_id: &str, suffix: &str) -> YahooFundamentals {
+    let pages = QuotePages {
+        profile: fetch_page(stock_id, suffix, Page::Profile).await,
+        revenue: fetch_page(stock_id, suffix, Page::Revenue).await,
+        holders: fetch_page(stock_id, suffix, Page::MajorHolders).await,
+    };
+    parse_pages(stock_id, suffix, &pages)
+}
+
+/// 上櫃先試 `.TWO`，其餘先試 `.TW`
+fn suffixes(market_type: Option<&str>) -> [&'static str; 2] {
+    match market_type {
+        Some(market) if ["上櫃", "OTC", "TWO"].iter().any(|m| market.contains(m)) => {
+            [".TWO", ".TW"]
+        }
+        _ => [".TW", ".TWO"],
+    }
+}
+
+/// 抓取 profile / revenue / major-holders 三頁；缺營收或本益比時改用另一個後綴重試，取缺漏較少的結果
+pub async fn fetch(stock_id: &str, market_type: Option<&str>) -> YahooFundamentals {
+    let [primary, fallback] = suffixes(market_type);
+    let first = scrape(stock_id, primary).await;
+    if first.metrics.pe.is_some() && first.fundamentals.contains_key("revenue_recent_m1_name") {
+        return first;
+    }
+    log::info!(
+        "[Fundamentals] {}{} incomplete ({} missing), retrying with {}",
+        stock_id,
+        primary,
+        first.missing.len(),
+        fallback
+    );
+    let second = scrape(stock_id, fallback).await;
+    if second.missing.len() < first.missing.len() {
+        second
+    } else {
+        first
+    }
+}
+
+#[tauri::command]
+pub async fn scrape_fundamentals(
+    stock_id: String,
+    market_type: Option<String>,
+) -> Result<YahooFundamentals, String> {
+    let result = fetch(&stock_id, market_type.as_deref()).await;
+    if !result.missing.is_empty() {
+        log::warn!(
+            "[Fundamentals] {}{} missing: {}",
+            stock_id,
+            result.suffix,
+            result.missing.join(", ")
+        );
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> String {
+        let path = format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name);
+        std::fs::read_to_string(path).unwrap()
+    }
+
+    #[test]
+    fn parses_profile_metrics_and_eps() {
+        let html = fixture("yahoo_profile.html");
+        let metric = parse_metrics("2330", &html);
+        assert_eq!(metric.gross_profit_margin, Some(59.02));
+        assert_eq!(metric.operating_margin, Some(49.55));
+        assert_eq!(metric.pre_tax_profit_margin, Some(51.2));
+        assert_eq!(metric.roa, Some(7.35));
+        assert_eq!(metric.roe, Some(9.61));
+        assert_eq!(metric.book_value_per_share, Some(192.65));
+        assert_eq!(metric.pe, Some(25.31));
+        assert_eq!(metric.pb, Some(7.52));
+        // 數值在標籤上方的版型
+        assert_eq!(metric.dividend_yield, Some(1.52));
+        assert_eq!(metric.report_period.as_deref(), Some("2026 Q2"));
+        assert!(metric.missing().is_empty());
+
+        let (quarters, years) = parse_eps(&html);
+        let names: Vec<&str> = quarters.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["2026 Q2", "2026 Q1", "2025 Q4", "2025 Q3"]);
+        assert_eq!(quarters[0].value, 15.36);
+        let names: Vec<&str> = years.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["2025", "2024", "2023"]);
+        assert_eq!(years[2].value, 32.34);
+    }
+
+    #[test]
+    fn parses_revenue_table_rows() {
+        let revenue = parse_revenue(&fixture("yahoo_revenue.html"));
+        assert_eq!(revenue.len(), 4);
+        assert_eq!(revenue[0].name, "2026/09");
+        assert_eq!(revenue[0].revenue, Some(330980640.0));
+        assert_eq!(revenue[0].mom, Some(-2.61));
+        assert_eq!(revenue[0].yoy, Some(31.41));
+        assert_eq!(revenue[0].yoy_acc, Some(36.4));
+        assert_eq!(revenue[3].name, "2026/06");
+    }
+
+    #[test]
+    fn parses_legacy_grid_layout() {
+        let revenue = parse_revenue(&fixture("yahoo_revenue_legacy.html"));
+        assert_eq!(revenue.len(), 2);
+        assert_eq!(revenue[1].name, "2026/08");
+        assert_eq!(revenue[1].mom, Some(4.1));
+        assert_eq!(revenue[1].yoy, Some(-12.5));
+        assert_eq!(revenue[1].yoy_acc, Some(8.03));
+    }
+
+    #[test]
+    fn parses_major_holders() {
+        let weeks = parse_holders(&fixture("yahoo_holders.html"));
+        assert_eq!(weeks.len(), 4);
+        assert_eq!(weeks[0].name, "2026/10/17");
+        assert_eq!(weeks[0].foreign_ratio, Some(72.95));
+        assert_eq!(weeks[0].big_investor_ratio, Some(88.12));
+    }
+
+    #[test]
+    fn reports_missing_fields() {
+        let pages = QuotePages {
+            profile: None,
+            revenue: Some(fixture("yahoo_revenue_legacy.html")),
+            holders: Some(fixture("yahoo_holders.html")),
+        };
+        let result = parse_pages("6223", ".TWO", &pages);
+        assert!(result.missing.contains(&"pe".to_string()));
+        assert!(result.missing.contains(&"report_period".to_string()));
+        assert!(result.missing.contains(&"revenue_recent_m3".to_string()));
+        assert!(result.missing.contains(&"eps_recent_q1".to_string()));
+        assert!(!result.missing.iter().any(|f| f.starts_with("recent_w")));
+        assert_eq!(
+            result.fundamentals.get("revenue_recent_m2_name"),
+            Some(&Value::from("2026/08"))
+        );
+        assert!(!result.fundamentals.contains_key("revenue_recent_m3_name"));
+        assert_eq!(
+            result.positions.get("recent_w4_foreign_ratio"),
+            Some(&Value::from(72.1))
+        );
+    }
+
+    #[test]
+    fn picks_suffix_by_market() {
+        assert_eq!(suffixes(Some("上櫃")), [".TWO", ".TW"]);
+        assert_eq!(suffixes(Some("上市")), [".TW", ".TWO"]);
+        assert_eq!(suffixes(None), [".TW", ".TWO"]);
+    }
+}
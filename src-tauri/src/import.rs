@@ -0,0 +1,819 @@
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::Path;
+
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use quick_xml::events::Event;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use tauri::State;
+
+use crate::adjustment;
+use crate::calendar::{self, TradingCalendar};
+use crate::data_quality;
+use crate::incremental;
+use crate::portfolio::{self, TradeInput, TradeKind};
+use crate::sqlite::connection::Db;
+use crate::types::{Bar, Timeframe};
+
+/// 預覽時回傳的資料列數
+const PREVIEW_ROWS: usize = 20;
+/// 回報的錯誤列數上限，其餘只計數
+const MAX_ISSUES: usize = 200;
+
+/// 各欄位可自動對應的標題 (不分大小寫)
+const BAR_ALIASES: &[(&str, &[&str])] = &[
+    (
+        "stock_id",
+        &[
+            "stock_id",
+            "symbol",
+            "code",
+            "ticker",
+            "代號",
+            "股票代號",
+            "證券代號",
+        ],
+    ),
+    (
+        "t",
+        &[
+            "t",
+            "ts",
+            "date",
+            "datetime",
+            "time",
+            "日期",
+            "時間",
+            "交易日",
+        ],
+    ),
+    ("o", &["o", "open", "開盤", "開盤價"]),
+    ("h", &["h", "high", "最高", "最高價"]),
+    ("l", &["l", "low", "最低", "最低價"]),
+    ("c", &["c", "close", "收盤", "收盤價"]),
+    ("v", &["v", "volume", "vol", "成交量", "成交股數"]),
+];
+
+const TRADE_ALIASES: &[(&str, &[&str])] = &[
+    (
+        "stock_id",
+        &["stock_id", "symbol", "code", "代號", "股票代號", "證券代號"],
+    ),
+    ("t", &["t", "date", "日期", "成交日期", "交易日期"]),
+    (
+        "kind",
+        &["kind", "side", "type", "買賣別", "交易類別", "類別"],
+    ),
+    (
+        "quantity",
+        &["quantity", "qty", "shares", "股數", "成交股數"],
+    ),
+    ("price", &["price", "成交價", "成交單價", "價格"]),
+    ("amount", &["amount", "金額", "股利金額"]),
+    ("fee", &["fee", "手續費"]),
+    ("tax", &["tax", "交易稅", "證交稅"]),
+    ("note", &["note", "備註"]),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportKind {
+    /// OHLCV K 線
+    Bars,
+    /// 券商對帳單交易紀錄
+    Trades,
+}
+
+impl ImportKind {
+    fn aliases(self) -> &'static [(&'static str, &'static [&'static str])] {
+        match self {
+            ImportKind::Bars => BAR_ALIASES,
+            ImportKind::Trades => TRADE_ALIASES,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ImportRequest {
+    pub path: String,
+    pub kind: ImportKind,
+    /// 欄位 -> 檔案標題，未指定的欄位依標題自動對應
+    #[serde(default)]
+    pub mapping: BTreeMap<String, String>,
+    /// 檔案沒有代號欄位時 (單一股票匯出) 使用
+    pub stock_id: Option<String>,
+    pub timeframe: Option<Timeframe>,
+    /// 成交量乘數，來源以張為單位時填 1000
+    pub volume_scale: Option<f64>,
+    pub portfolio_id: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportPreview {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    /// 自動對應結果 (欄位 -> 標題)
+    pub mapping: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportIssue {
+    /// 檔案中的列號 (標題為第 1 列)
+    pub line: usize,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportReport {
+    pub rows: usize,
+    pub imported: usize,
+    pub stocks: usize,
+    pub skipped: usize,
+    pub issues: Vec<ImportIssue>,
+}
+
+impl ImportReport {
+    fn skip(&mut self, line: usize, reason: impl Into<String>) {
+        self.skipped += 1;
+        if self.issues.len() < MAX_ISSUES {
+            self.issues.push(ImportIssue {
+                line,
+                reason: reason.into(),
+            });
+        }
+    }
+}
+
+/// 讀入的表格，第一列為標題
+#[derive(Debug, Clone, Default)]
+struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+fn read_csv(bytes: &[u8]) -> Result<Table, String> {
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_reader(bytes);
+    let headers = reader
+        .headers()
+        .map_err(|e| format!("[Import] CSV header error: {}", e))?
+        .iter()
+        .map(str::to_string)
+        .collect();
+    let rows = reader
+        .records()
+        .enumerate()
+        .map(|(line, record)| {
+            record
+                .map(|r| r.iter().map(str::to_string).collect())
+                .map_err(|e| format!("[Import] CSV line {}: {}", line + 2, e))
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(Table { headers, rows })
+}
+
+fn zip_entry<R: Read + std::io::Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    name: &str,
+) -> Result<Option<String>, String> {
+    let mut file = match archive.by_name(name) {
+        Ok(file) => file,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(format!("[Import] XLSX read {} error: {}", name, e)),
+    };
+    let mut text = String::new();
+    file.read_to_string(&mut text)
+        .map_err(|e| format!("[Import] XLSX read {} error: {}", name, e))?;
+    Ok(Some(text))
+}
+
+fn shared_strings(xml: &str) -> Result<Vec<String>, String> {
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut strings = Vec::new();
+    let mut current = String::new();
+    let mut in_text = false;
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) if e.local_name().as_ref() == b"si" => current.clear(),
+            Ok(Event::Start(e)) if e.local_name().as_ref() == b"t" => in_text = true,
+            Ok(Event::End(e)) if e.local_name().as_ref() == b"t" => in_text = false,
+            Ok(Event::End(e)) if e.local_name().as_ref() == b"si" => {
+                strings.push(std::mem::take(&mut current))
+            }
+            Ok(Event::Empty(e)) if e.local_name().as_ref() == b"si" => strings.push(String::new()),
+            Ok(Event::Text(e)) if in_text => {
+                current.push_str(&e.unescape().map_err(|e| e.to_string())?)
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(format!("[Import] XLSX shared strings error: {}", e)),
+            _ => {}
+        }
+    }
+    Ok(strings)
+}
+
+/// 儲存格參照 `AB12` 的欄位索引 (從 0 開始)
+fn column_index(reference: &str) -> Option<usize> {
+    let letters: Vec<u8> = reference
+        .bytes()
+        .take_while(u8::is_ascii_alphabetic)
+        .collect();
+    if letters.is_empty() {
+        return None;
+    }
+    Some(
+        letters.iter().fold(0, |acc, b| {
+            acc * 26 + (b.to_ascii_uppercase() - b'A' + 1) as usize
+        }) - 1,
+    )
+}
+
+/// 解析工作表 XML，空白儲存格補空字串
+fn sheet_rows(xml: &str, strings: &[String]) -> Result<Vec<Vec<String>>, String> {
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut rows = Vec::new();
+    let mut row: Vec<String> = Vec::new();
+    let mut cell_type = String::new();
+    let mut cell_index = 0;
+    let mut value = String::new();
+    let mut in_value = false;
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) => match e.local_name().as_ref() {
+                b"row" => {
+                    row.clear();
+                    cell_index = 0;
+                }
+                b"c" => {
+                    cell_type.clear();
+                    value.clear();
+                    for attr in e.attributes().flatten() {
+                        let text = attr.unescape_value().map_err(|e| e.to_string())?;
+                        match attr.key.local_name().as_ref() {
+                            b"t" => cell_type = text.into_owned(),
+                            b"r" => cell_index = column_index(&text).unwrap_or(row.len()),
+                            _ => {}
+                        }
+                    }
+                }
+                b"v" | b"t" => in_value = true,
+                _ => {}
+            },
+            Ok(Event::Text(e)) if in_value => {
+                value.push_str(&e.unescape().map_err(|e| e.to_string())?)
+            }
+            Ok(Event::End(e)) => match e.local_name().as_ref() {
+                b"v" | b"t" => in_value = false,
+                b"c" => {
+                    let text = match cell_type.as_str() {
+                        "s" => value
+                            .trim()
+                            .parse::<usize>()
+                            .ok()
+                            .and_then(|i| strings.get(i))
+                            .cloned()
+                            .unwrap_or_default(),
+                        _ => value.clone(),
+                    };
+                    if row.len() <= cell_index {
+                        row.resize(cell_index + 1, String::new());
+                    }
+                    row[cell_index] = text.trim().to_string();
+                    cell_index += 1;
+                }
+                b"row" => rows.push(std::mem::take(&mut row)),
+                _ => {}
+            },
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(format!("[Import] XLSX sheet error: {}", e)),
+            _ => {}
+        }
+    }
+    Ok(rows)
+}
+
+/// 讀取活頁簿的第一個工作表
+fn read_xlsx(path: &Path) -> Result<Table, String> {
+    let file = std::fs::File::open(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("[Import] XLSX open error: {}", e))?;
+    let strings = match zip_entry(&mut archive, "xl/sharedStrings.xml")? {
+        Some(xml) => shared_strings(&xml)?,
+        None => Vec::new(),
+    };
+    let sheet = archive
+        .file_names()
+        .filter_map(|name| {
+            let n = name
+                .strip_prefix("xl/worksheets/sheet")?
+                .strip_suffix(".xml")?;
+            Some((n.parse::<u32>().ok()?, name.to_string()))
+        })
+        .min()
+        .map(|(_, name)| name)
+        .ok_or("[Import] XLSX has no worksheet")?;
+    let xml = zip_entry(&mut archive, &sheet)?.unwrap_or_default();
+    let mut rows = sheet_rows(&xml, &strings)?
+        .into_iter()
+        .filter(|row| row.iter().any(|cell| !cell.is_empty()));
+    let headers = rows.next().unwrap_or_default();
+    Ok(Table {
+        headers,
+        rows: rows.collect(),
+    })
+}
+
+fn read_table(path: &Path) -> Result<Table, String> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+    match extension.as_str() {
+        "xlsx" | "xlsm" => read_xlsx(path),
+        "csv" | "txt" => {
+            let bytes = std::fs::read(path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            read_csv(&bytes)
+        }
+        _ => Err(format!("[Import] unsupported file type: {}", extension)),
+    }
+}
+
+/// 以使用者指定的對應為主，其餘欄位依別名比對標題
+fn resolve_mapping(
+    kind: ImportKind,
+    headers: &[String],
+    mapping: &BTreeMap<String, String>,
+) -> BTreeMap<String, usize> {
+    let find = |name: &str| {
+        headers
+            .iter()
+            .position(|h| h.trim().eq_ignore_ascii_case(name.trim()))
+    };
+    kind.aliases()
+        .iter()
+        .filter_map(|(field, aliases)| {
+            let index = match mapping.get(*field) {
+                Some(header) => find(header),
+                None => aliases.iter().find_map(|alias| find(alias)),
+            }?;
+            Some((field.to_string(), index))
+        })
+        .collect()
+}
+
+struct Row<'a> {
+    cells: &'a [String],
+    columns: &'a BTreeMap<String, usize>,
+}
+
+impl Row<'_> {
+    fn text(&self, field: &str) -> Option<&str> {
+        let cell = self.cells.get(*self.columns.get(field)?)?.trim();
+        (!cell.is_empty()).then_some(cell)
+    }
+
+    fn number(&self, field: &str) -> Option<f64> {
+        self.text(field)?
+            .replace(',', "")
+            .parse::<f64>()
+            .ok()
+            .filter(|n| n.is_finite())
+    }
+}
+
+/// Excel 日期序號 (1899-12-30 起算的天數，小數為時間)
+fn from_excel_serial(serial: f64) -> Option<(NaiveDate, u32)> {
+    if !(1.0..2958466.0).contains(&serial) {
+        return None;
+    }
+    let date = NaiveDate::from_ymd_opt(1899, 12, 30)? + Duration::days(serial.trunc() as i64);
+    let minutes = (serial.fract() * 1440.0).round() as u32;
+    Some((date, minutes / 60 * 100 + minutes % 60))
+}
+
+/// 年份欄位：三位數為民國年；兩位數 80 以上視為民國年，其餘為西元 20xx
+fn expand_year(year: &str) -> Option<i32> {
+    let value: i32 = year.parse().ok()?;
+    Some(match year.len() {
+        0..=2 if value >= 80 => value + 1911,
+        0..=2 => value + 2000,
+        3 => value + 1911,
+        _ => value,
+    })
+}
+
+/// 將各種日期格式轉為 YYYYMMDD (小時線為 YYYYMMDDHHmm，必須帶時間)。
+/// 支援 `2024-01-02`、`2024/1/2 10:00`、`24/01/02`、`20240102`、民國年 `113/01/02` 與 Excel 日期序號。
+fn normalize_time(text: &str, timeframe: Timeframe) -> Option<String> {
+    let groups: Vec<&str> = text
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|s| !s.is_empty())
+        .collect();
+    let (date, hhmm) = match groups[..] {
+        [digits] if digits.len() == 12 => (
+            calendar::parse_day(digits)?,
+            Some(digits[8..].parse::<u32>().ok()?),
+        ),
+        [digits] if digits.len() == 8 => (calendar::parse_day(digits)?, None),
+        [_] | [_, _] => {
+            let (date, hhmm) = from_excel_serial(text.trim().parse().ok()?)?;
+            // 序號沒有小數時只有日期
+            (date, text.contains('.').then_some(hhmm))
+        }
+        [year, month, day, ref rest @ ..] => {
+            let date = NaiveDate::from_ymd_opt(
+                expand_year(year)?,
+                month.parse().ok()?,
+                day.parse().ok()?,
+            )?;
+            let hhmm = match rest {
+                [hour, minute, ..] => {
+                    Some(hour.parse::<u32>().ok()? * 100 + minute.parse::<u32>().ok()?)
+                }
+                _ => None,
+            };
+            (date, hhmm)
+        }
+        _ => return None,
+    };
+    Some(match timeframe {
+        // 只有日期無法對應到任何一根小時線
+        Timeframe::Hourly => format!("{}{:04}", calendar::format_day(date), hhmm?),
+        _ => calendar::format_day(date),
+    })
+}
+
+/// 週末或本地日曆確定休市的日期不可有成交
+fn check_session(calendar: &TradingCalendar, t: &str) -> Result<(), String> {
+    let date = calendar::parse_day(t).ok_or_else(|| format!("invalid date {}", t))?;
+    if matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+        && calendar.is_session(date) != Some(true)
+    {
+        return Err(format!("{} is a weekend", t));
+    }
+    if calendar.is_session(date) == Some(false) {
+        return Err(format!("{} is not a trading day", t));
+    }
+    Ok(())
+}
+
+fn parse_bar(
+    row: &Row,
+    timeframe: Timeframe,
+    volume_scale: f64,
+    calendar: &TradingCalendar,
+) -> Result<Bar, String> {
+    let raw_t = row.text("t").ok_or("missing date")?;
+    let t = normalize_time(raw_t, timeframe).ok_or_else(|| format!("invalid date {}", raw_t))?;
+    // 週線日期為該週第一個交易日，不一定落在本地日曆內
+    if timeframe != Timeframe::Weekly {
+        check_session(calendar, &t)?;
+    }
+    let price = |field: &str| {
+        row.number(field)
+            .ok_or_else(|| format!("missing {}", field))
+    };
+    let bar = Bar {
+        t,
+        o: price("o")?,
+        h: price("h")?,
+        l: price("l")?,
+        c: price("c")?,
+        v: (row.number("v").unwrap_or(0.0) * volume_scale).round() as i64,
+    };
+    if !bar.is_valid() {
+        return Err(format!(
+            "invalid OHLC {} {} {} {}",
+            bar.o, bar.h, bar.l, bar.c
+        ));
+    }
+    Ok(bar)
+}
+
+fn parse_trade_kind(text: &str) -> Option<TradeKind> {
+    let lower = text.to_lowercase();
+    if lower.contains("股利")
+        || lower.contains("股息")
+        || lower.contains("配息")
+        || lower == "dividend"
+    {
+        Some(TradeKind::Dividend)
+    } else if lower.contains('買') || lower == "buy" || lower == "b" {
+        Some(TradeKind::Buy)
+    } else if lower.contains('賣') || lower == "sell" || lower == "s" {
+        Some(TradeKind::Sell)
+    } else if lower.contains("稅") || lower == "tax" {
+        Some(TradeKind::Tax)
+    } else if lower.contains("費") || lower == "fee" {
+        Some(TradeKind::Fee)
+    } else {
+        None
+    }
+}
+
+fn parse_trade(
+    row: &Row,
+    portfolio_id: i64,
+    stock_id: Option<&str>,
+    calendar: &TradingCalendar,
+) -> Result<TradeInput, String> {
+    let raw_t = row.text("t").ok_or("missing date")?;
+    let t =
+        normalize_time(raw_t, Timeframe::Daily).ok_or_else(|| format!("invalid date {}", raw_t))?;
+    let raw_kind = row.text("kind").ok_or("missing kind")?;
+    let kind = parse_trade_kind(raw_kind).ok_or_else(|| format!("unknown kind {}", raw_kind))?;
+    // 股利與費用可能在非交易日入帳，只檢查買賣
+    if matches!(kind, TradeKind::Buy | TradeKind::Sell) {
+        check_session(calendar, &t)?;
+    }
+    let stock_id = row
+        .text("stock_id")
+        .or(stock_id)
+        .map(|id| id.split_whitespace().next().unwrap_or(id).to_string());
+    if stock_id.is_none() && matches!(kind, TradeKind::Buy | TradeKind::Sell) {
+        return Err("missing stock_id".to_string());
+    }
+    Ok(TradeInput {
+        portfolio_id,
+        stock_id,
+        t,
+        kind,
+        quantity: row.number("quantity").unwrap_or(0.0).abs().round() as i64,
+        price: row.number("price").unwrap_or(0.0),
+        amount: row.number("amount").unwrap_or(0.0).abs(),
+        fee: row.number("fee"),
+        tax: row.number("tax"),
+        day_trade: raw_kind.contains("當沖"),
+        note: row.text("note").map(str::to_string),
+    })
+}
+
+async fn import_bars(
+    app: &tauri::AppHandle,
+    pool: &SqlitePool,
+    request: &ImportRequest,
+    table: &Table,
+    columns: &BTreeMap<String, usize>,
+) -> Result<ImportReport, String> {
+    let timeframe = request.timeframe.unwrap_or(Timeframe::Daily);
+    let volume_scale = request.volume_scale.unwrap_or(1.0);
+    let calendar = TradingCalendar::load(pool).await?;
+    let mut report = ImportReport {
+        rows: table.rows.len(),
+        ..Default::default()
+    };
+
+    let mut stocks: BTreeMap<String, BTreeMap<String, Bar>> = BTreeMap::new();
+    for (i, cells) in table.rows.iter().enumerate() {
+        let row = Row { cells, columns };
+        let Some(stock_id) = row.text("stock_id").or(request.stock_id.as_deref()) else {
+            report.skip(i + 2, "missing stock_id");
+            continue;
+        };
+        match parse_bar(&row, timeframe, volume_scale, &calendar) {
+            // 同一日期重複時以後出現的為準
+            Ok(bar) => {
+                stocks
+                    .entry(stock_id.to_string())
+                    .or_default()
+                    .insert(bar.t.clone(), bar);
+            }
+            Err(reason) => report.skip(i + 2, reason),
+        }
+    }
+
+    let mode = adjustment::price_mode(app);
+    for (stock_id, bars) in &stocks {
+        // 小時線沿用該股票既有的 ts 格式，避免同一時間寫入兩種格式
+        let dashed = data_quality::uses_dashed_ts(pool, timeframe, stock_id).await?;
+        let bars: Vec<Bar> = bars
+            .values()
+            .map(|bar| Bar {
+                t: data_quality::table_time(timeframe, &bar.t, dashed),
+                ..bar.clone()
+            })
+            .collect();
+        incremental::append(pool, timeframe, stock_id, &bars, mode).await?;
+        // 匯入的歷史 K 線可能讓先前不在範圍內的除權息日生效
+        if timeframe == Timeframe::Daily {
+            adjustment::refresh(pool, stock_id, mode).await?;
+        }
+        report.imported += bars.len();
+    }
+    report.stocks = stocks.len();
+    Ok(report)
+}
+
+async fn import_trades(
+    pool: &SqlitePool,
+    request: &ImportRequest,
+    table: &Table,
+    columns: &BTreeMap<String, usize>,
+) -> Result<ImportReport, String> {
+    let portfolio_id = request
+        .portfolio_id
+        .ok_or("[Import] portfolio_id is required for trades")?;
+    let calendar = TradingCalendar::load(pool).await?;
+    let mut report = ImportReport {
+        rows: table.rows.len(),
+        ..Default::default()
+    };
+
+    let mut trades = Vec::new();
+    for (i, cells) in table.rows.iter().enumerate() {
+        let row = Row { cells, columns };
+        match parse_trade(&row, portfolio_id, request.stock_id.as_deref(), &calendar) {
+            Ok(trade) => trades.push((i + 2, trade)),
+            Err(reason) => report.skip(i + 2, reason),
+        }
+    }
+    trades.sort_by(|(_, a), (_, b)| a.t.cmp(&b.t));
+    // 超賣等無法寫入的交易略過並回報行號
+    let mut imported = Vec::new();
+    for (line, trade) in trades {
+        match portfolio::insert_trade(pool, &trade).await {
+            Ok(_) => imported.push(trade),
+            Err(reason) => report.skip(line, reason),
+        }
+    }
+    let trades = imported;
+    report.imported = trades.len();
+    report.stocks = trades
+        .iter()
+        .filter_map(|t| t.stock_id.as_deref())
+        .collect::<std::collections::BTreeSet<_>>()
+        .len();
+    Ok(report)
+}
+
+/// 讀取檔案前幾列與自動對應結果，供前端設定欄位對應
+#[tauri::command]
+pub fn preview_import(path: String, kind: ImportKind) -> Result<ImportPreview, String> {
+    let table = read_table(Path::new(&path))?;
+    let mapping = resolve_mapping(kind, &table.headers, &BTreeMap::new())
+        .into_iter()
+        .map(|(field, index)| (field, table.headers[index].clone()))
+        .collect();
+    Ok(ImportPreview {
+        rows: table.rows.into_iter().take(PREVIEW_ROWS).collect(),
+        headers: table.headers,
+        mapping,
+    })
+}
+
+/// 匯入 CSV / XLSX 的 K 線或交易紀錄。
+/// K 線以批次寫入並增量重算技術指標，不合法或非交易日的資料列略過並列於報告中。
+#[tauri::command]
+pub async fn import_file(
+    app: tauri::AppHandle,
+    db: State<'_, Db>,
+    request: ImportRequest,
+) -> Result<ImportReport, String> {
+    let table = read_table(Path::new(&request.path))?;
+    let columns = resolve_mapping(request.kind, &table.headers, &request.mapping);
+    let required: &[&str] = match request.kind {
+        ImportKind::Bars => &["t", "o", "h", "l", "c"],
+        ImportKind::Trades => &["t", "kind"],
+    };
+    if let Some(field) = required.iter().find(|f| !columns.contains_key(**f)) {
+        return Err(format!("[Import] column for {} not found", field));
+    }
+
+    let report = match request.kind {
+        ImportKind::Bars => import_bars(&app, &db.0, &request, &table, &columns).await?,
+        ImportKind::Trades => import_trades(&db.0, &request, &table, &columns).await?,
+    };
+    log::info!(
+        "[Import] {}: {} of {} rows imported, {} skipped",
+        request.path,
+        report.imported,
+        report.rows,
+        report.skipped
+    );
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn normalizes_dates_and_times() {
+        let daily = |text: &str| normalize_time(text, Timeframe::Daily);
+        let hourly = |text: &str| normalize_time(text, Timeframe::Hourly);
+        assert_eq!(daily("2024-01-02").as_deref(), Some("20240102"));
+        assert_eq!(daily("2024/1/2").as_deref(), Some("20240102"));
+        assert_eq!(daily("20240102").as_deref(), Some("20240102"));
+        // 民國年
+        assert_eq!(daily("113/01/02").as_deref(), Some("20240102"));
+        assert_eq!(daily("99/12/31").as_deref(), Some("20101231"));
+        // 兩位數西元年
+        assert_eq!(daily("24/01/02").as_deref(), Some("20240102"));
+        // Excel 日期序號，45293 為 2024-01-02
+        assert_eq!(daily("45293").as_deref(), Some("20240102"));
+        assert_eq!(daily("2024/13/01"), None);
+        assert_eq!(daily("abc"), None);
+
+        assert_eq!(hourly("2024/1/2 10:00").as_deref(), Some("202401021000"));
+        assert_eq!(hourly("202401021300").as_deref(), Some("202401021300"));
+        // 45293 + 10.5 / 24
+        assert_eq!(hourly("45293.4375").as_deref(), Some("202401021030"));
+        // 小時線只有日期時無法判斷是哪一根
+        assert_eq!(hourly("2024-01-02"), None);
+        assert_eq!(hourly("20240102"), None);
+        assert_eq!(hourly("45293"), None);
+    }
+
+    #[test]
+    fn converts_cell_references() {
+        assert_eq!(column_index("A1"), Some(0));
+        assert_eq!(column_index("Z9"), Some(25));
+        assert_eq!(column_index("AA10"), Some(26));
+        assert_eq!(column_index("ab3"), Some(27));
+        assert_eq!(column_index("12"), None);
+    }
+
+    #[test]
+    fn reads_csv_with_bom() {
+        let table =
+            read_csv("\u{feff}日期, 收盤\n2024-01-02, 100\n2024-01-03,101\n".as_bytes()).unwrap();
+        assert_eq!(table.headers, ["日期", "收盤"]);
+        assert_eq!(table.rows, [["2024-01-02", "100"], ["2024-01-03", "101"]]);
+    }
+
+    const SHARED_STRINGS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<sst xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" count="4" uniqueCount="4">
+<si><t>日期</t></si>
+<si><r><t>收</t></r><r><t>盤</t></r></si>
+<si/>
+<si><t>A &amp; B</t></si>
+</sst>"#;
+
+    /// 第 2 列的儲存格沒有 `r` 屬性，第 3 列跳過 B 欄
+    const SHEET: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>
+<row r="1"><c r="A1" t="s"><v>0</v></c><c r="B1" t="s"><v>1</v></c><c r="C1" t="s"><v>3</v></c></row>
+<row r="2"><c><v>45293</v></c><c t="inlineStr"><is><t>100.5</t></is></c></row>
+<row r="3"><c r="A3" t="str"><v>113/01/03</v></c><c r="C3"><v>7</v></c></row>
+</sheetData></worksheet>"#;
+
+    #[test]
+    fn parses_shared_and_inline_strings() {
+        let strings = shared_strings(SHARED_STRINGS).unwrap();
+        assert_eq!(strings, ["日期", "收盤", "", "A & B"]);
+        let rows = sheet_rows(SHEET, &strings).unwrap();
+        assert_eq!(
+            rows,
+            [
+                vec!["日期", "收盤", "A & B"],
+                vec!["45293", "100.5"],
+                vec!["113/01/03", "", "7"],
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_first_worksheet_of_xlsx() {
+        let path = std::env::temp_dir().join(format!("schoice-import-{}.xlsx", std::process::id()));
+        {
+            let mut zip = zip::ZipWriter::new(std::fs::File::create(&path).unwrap());
+            let options = zip::write::SimpleFileOptions::default();
+            for (name, xml) in [
+                ("xl/sharedStrings.xml", SHARED_STRINGS),
+                ("xl/worksheets/sheet2.xml", "<worksheet/>"),
+                ("xl/worksheets/sheet1.xml", SHEET),
+            ] {
+                zip.start_file(name, options).unwrap();
+                zip.write_all(xml.as_bytes()).unwrap();
+            }
+            zip.finish().unwrap();
+        }
+        let table = read_table(&path);
+        std::fs::remove_file(&path).unwrap();
+        let table = table.unwrap();
+        assert_eq!(table.headers, ["日期", "收盤", "A & B"]);
+        assert_eq!(table.rows.len(), 2);
+
+        let columns = resolve_mapping(ImportKind::Bars, &table.headers, &BTreeMap::new());
+        let dates: Vec<_> = table
+            .rows
+            .iter()
+            .map(|cells| {
+                let row = Row {
+                    cells,
+                    columns: &columns,
+                };
+                normalize_time(row.text("t").unwrap(), Timeframe::Daily)
+            })
+            .collect();
+        assert_eq!(dates, [Some("20240102".into()), Some("20240103".into())]);
+    }
+}
@@ -0,0 +1,182 @@
+use sqlx::SqlitePool;
+use tauri::State;
+
+use crate::adjustment::{self, PriceMode};
+use crate::indicators::{IndicatorEngine, SkillsRow, STATE_VERSION};
+use crate::sqlite::connection::Db;
+use crate::sqlite::writer;
+use crate::types::{Bar, Timeframe};
+
+/// 每檔股票每個時框保留的狀態筆數。
+/// 同步時會覆寫最近幾根 K 線 (日線 3 根、週線 5 根)，需保留足夠的歷史狀態回溯到覆寫區間之前。
+const STATE_HISTORY: usize = 8;
+
+/// 狀態依推進時使用的價格分開保存，還原價格的日線狀態另存一組，切換模式或同步時不互相覆寫
+fn state_key(timeframe: Timeframe, adjusted: bool) -> &'static str {
+    match (timeframe, adjusted) {
+        (Timeframe::Daily, true) => "daily_adjusted",
+        _ => timeframe.as_str(),
+    }
+}
+
+async fn load_state(
+    pool: &SqlitePool,
+    key: &str,
+    stock_id: &str,
+    before: &str,
+) -> Result<Option<(String, String)>, String> {
+    sqlx::query_as(
+        "SELECT t, state FROM indicator_state
+         WHERE stock_id = $1 AND timeframe = $2 AND version = $3 AND t < $4
+         ORDER BY t DESC LIMIT 1",
+    )
+    .bind(stock_id)
+    .bind(key)
+    .bind(STATE_VERSION)
+    .bind(before)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| format!("[Incremental] load state error: {}", e))
+}
+
+async fn save_states(
+    pool: &SqlitePool,
+    key: &str,
+    stock_id: &str,
+    from: &str,
+    states: &[(String, String)],
+) -> Result<(), String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    // 自 from 起的 K 線已被改寫，之後的舊狀態全數失效
+    sqlx::query("DELETE FROM indicator_state WHERE stock_id = $1 AND timeframe = $2 AND t >= $3")
+        .bind(stock_id)
+        .bind(key)
+        .bind(from)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    for (t, state) in states {
+        sqlx::query(
+            "INSERT OR REPLACE INTO indicator_state (stock_id, timeframe, t, version, state) VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(stock_id)
+        .bind(key)
+        .bind(t)
+        .bind(STATE_VERSION)
+        .bind(state)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+    sqlx::query(
+        "DELETE FROM indicator_state WHERE stock_id = $1 AND timeframe = $2 AND t NOT IN (
+             SELECT t FROM indicator_state WHERE stock_id = $1 AND timeframe = $2 ORDER BY t DESC LIMIT $3
+         )",
+    )
+    .bind(stock_id)
+    .bind(key)
+    .bind(STATE_HISTORY as i64)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| e.to_string())?;
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+/// 清除狀態 (含還原價格的狀態)，`from` 為 None 時清除該股票該時框的全部狀態
+pub async fn invalidate(
+    pool: &SqlitePool,
+    timeframe: Timeframe,
+    stock_id: &str,
+    from: Option<&str>,
+) -> Result<(), String> {
+    sqlx::query(
+        "DELETE FROM indicator_state WHERE stock_id = $1 AND timeframe IN ($2, $3) AND t >= $4",
+    )
+    .bind(stock_id)
+    .bind(state_key(timeframe, false))
+    .bind(state_key(timeframe, true))
+    .bind(from.unwrap_or(""))
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 重算 `from` (含) 之後的技術指標。
+/// 有 `from` 之前的狀態時只需推進新增的 K 線，否則以完整歷史重算並建立狀態。
+/// 還原模式下日線以已保存的還原乘數換算價格；最後一次除權息之後的 K 線乘數為 1，新 K 線仍可增量更新。
+pub async fn update(
+    pool: &SqlitePool,
+    timeframe: Timeframe,
+    stock_id: &str,
+    from: &str,
+    mode: PriceMode,
+) -> Result<usize, String> {
+    let factors = match (timeframe, mode) {
+        (Timeframe::Daily, PriceMode::Adjusted) => adjustment::load_factors(pool, stock_id).await?,
+        _ => Vec::new(),
+    };
+    // 沒有公司行動的股票還原價格即原始價格，共用同一組狀態
+    let key = state_key(timeframe, !factors.is_empty());
+    let shares = writer::issued_shares(pool, stock_id).await?;
+    let (mut engine, after) = match load_state(pool, key, stock_id, from).await? {
+        Some((t, state)) => (IndicatorEngine::from_state(&state)?, t),
+        None => (IndicatorEngine::new(shares), String::new()),
+    };
+    engine.set_issued_shares(shares);
+
+    let bars: Vec<Bar> = writer::load_deals_after(pool, timeframe, stock_id, &after)
+        .await?
+        .into_iter()
+        .map(|bar| adjustment::scale(bar, &factors))
+        .collect();
+    let snapshot_from = bars.len().saturating_sub(STATE_HISTORY);
+    let mut skills: Vec<SkillsRow> = Vec::new();
+    let mut states = Vec::new();
+    for (i, bar) in bars.iter().enumerate() {
+        let row = engine.next(bar);
+        if bar.t.as_str() >= from {
+            skills.push(row);
+        }
+        if i >= snapshot_from {
+            states.push((bar.t.clone(), engine.to_state()?));
+        }
+    }
+
+    writer::save_skills(pool, timeframe, stock_id, &skills).await?;
+    save_states(pool, key, stock_id, from, &states).await?;
+    Ok(skills.len())
+}
+
+/// 寫入新的 K 線並以保存的狀態增量更新技術指標
+pub async fn append(
+    pool: &SqlitePool,
+    timeframe: Timeframe,
+    stock_id: &str,
+    bars: &[Bar],
+    mode: PriceMode,
+) -> Result<usize, String> {
+    let Some(from) = bars.iter().map(|bar| bar.t.as_str()).min() else {
+        return Ok(0);
+    };
+    writer::save_deals(pool, timeframe, stock_id, bars).await?;
+    update(pool, timeframe, stock_id, from, mode).await
+}
+
+#[tauri::command]
+pub async fn append_bars(
+    app: tauri::AppHandle,
+    db: State<'_, Db>,
+    stock_id: String,
+    timeframe: Timeframe,
+    bars: Vec<Bar>,
+) -> Result<usize, String> {
+    append(
+        &db.0,
+        timeframe,
+        &stock_id,
+        &bars,
+        adjustment::price_mode(&app),
+    )
+    .await
+}
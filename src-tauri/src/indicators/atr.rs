@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+
+use crate::types::Bar;
+
+/// 真實波幅均值，算法與 `cls_tools/supertrend.ts` 內的 RMA 相同：
+/// 前 period 根以累計平均起算，之後以 Wilder 平滑。
+/// 差異在於 TS 從第一根即輸出，這裡由呼叫端以 `is_ready` 判斷暖機完成前不輸出
+#[derive(Serialize, Deserialize)]
+pub struct Atr {
+    period: usize,
+    prev_close: Option<f64>,
+    count: usize,
+    value: f64,
+}
+
+impl Atr {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period,
+            prev_close: None,
+            count: 0,
+            value: 0.0,
+        }
+    }
+
+    /// 回傳當下 ATR，資料未滿 period 根時為起算中的平均值
+    pub fn next(&mut self, bar: &Bar) -> f64 {
+        let tr = true_range(bar, self.prev_close.replace(bar.c));
+        let n = self.count as f64;
+        self.value = if self.count < self.period {
+            (self.value * n + tr) / (n + 1.0)
+        } else {
+            let period = self.period as f64;
+            (self.value * (period - 1.0) + tr) / period
+        };
+        self.count += 1;
+        self.value
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.count >= self.period
+    }
+}
+
+pub fn true_range(bar: &Bar, prev_close: Option<f64>) -> f64 {
+    let range = bar.h - bar.l;
+    match prev_close {
+        Some(prev) => range.max((bar.h - prev).abs()).max((bar.l - prev).abs()),
+        None => range,
+    }
+}
@@ -0,0 +1,51 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+/// 布林通道 (20, 2)，使用母體標準差
+#[derive(Serialize, Deserialize)]
+pub struct Boll {
+    period: usize,
+    multiplier: f64,
+    window: VecDeque<f64>,
+}
+
+pub struct BollValue {
+    pub ub: Option<f64>,
+    pub ma: Option<f64>,
+    pub lb: Option<f64>,
+}
+
+impl Boll {
+    pub fn new(period: usize, multiplier: f64) -> Self {
+        Self {
+            period,
+            multiplier,
+            window: VecDeque::with_capacity(period + 1),
+        }
+    }
+
+    pub fn next(&mut self, close: f64) -> BollValue {
+        self.window.push_back(close);
+        if self.window.len() > self.period {
+            self.window.pop_front();
+        }
+        if self.window.len() < self.period {
+            return BollValue {
+                ub: None,
+                ma: None,
+                lb: None,
+            };
+        }
+
+        let n = self.period as f64;
+        let mean = self.window.iter().sum::<f64>() / n;
+        let variance = self.window.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
+        let width = variance.sqrt() * self.multiplier;
+        BollValue {
+            ub: Some(mean + width),
+            ma: Some(mean),
+            lb: Some(mean - width),
+        }
+    }
+}
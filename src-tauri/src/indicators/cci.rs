@@ -0,0 +1,41 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::Bar;
+
+/// 順勢指標，與 `cls_tools/cci.ts` 相同：(TP - SMA(TP)) / (0.015 * 平均偏差)
+#[derive(Serialize, Deserialize)]
+pub struct Cci {
+    period: usize,
+    tps: VecDeque<f64>,
+}
+
+impl Cci {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period,
+            tps: VecDeque::with_capacity(period + 1),
+        }
+    }
+
+    pub fn next(&mut self, bar: &Bar) -> Option<f64> {
+        let tp = (bar.h + bar.l + bar.c) / 3.0;
+        self.tps.push_back(tp);
+        if self.tps.len() > self.period {
+            self.tps.pop_front();
+        }
+        if self.tps.len() < self.period {
+            return None;
+        }
+
+        let period = self.period as f64;
+        let sma = self.tps.iter().sum::<f64>() / period;
+        let mean_dev = self.tps.iter().map(|v| (v - sma).abs()).sum::<f64>() / period;
+        Some(if mean_dev == 0.0 {
+            0.0
+        } else {
+            (tp - sma) / (0.015 * mean_dev)
+        })
+    }
+}
@@ -0,0 +1,58 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use super::ema::Ema;
+use crate::types::Bar;
+
+/// 蔡金資金流 CMF(21) 與其 EMA5
+#[derive(Serialize, Deserialize)]
+pub struct Cmf {
+    period: usize,
+    window: VecDeque<(f64, f64)>,
+    ema: Ema,
+}
+
+pub struct CmfValue {
+    pub cmf: Option<f64>,
+    pub ema: Option<f64>,
+}
+
+impl Cmf {
+    pub fn new(period: usize, ema_period: usize) -> Self {
+        Self {
+            period,
+            window: VecDeque::with_capacity(period + 1),
+            ema: Ema::new(ema_period),
+        }
+    }
+
+    pub fn next(&mut self, bar: &Bar) -> CmfValue {
+        let volume = bar.v as f64;
+        let multiplier = if bar.h > bar.l {
+            ((bar.c - bar.l) - (bar.h - bar.c)) / (bar.h - bar.l)
+        } else {
+            0.0
+        };
+        self.window.push_back((multiplier * volume, volume));
+        if self.window.len() > self.period {
+            self.window.pop_front();
+        }
+        if self.window.len() < self.period {
+            return CmfValue {
+                cmf: None,
+                ema: None,
+            };
+        }
+
+        let (flow, volume) = self
+            .window
+            .iter()
+            .fold((0.0, 0.0), |(f, v), (mf, mv)| (f + mf, v + mv));
+        let cmf = if volume == 0.0 { 0.0 } else { flow / volume };
+        CmfValue {
+            cmf: Some(cmf),
+            ema: self.ema.next(cmf),
+        }
+    }
+}
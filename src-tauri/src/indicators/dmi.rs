@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+
+use crate::types::Bar;
+
+/// DMI / ADX，與前端 `calculateDMI` 相同的 Wilder 平滑
+#[derive(Serialize, Deserialize)]
+pub struct Dmi {
+    period: usize,
+    prev: Option<(f64, f64, f64)>,
+    count: usize,
+    tr_smooth: f64,
+    plus_dm_smooth: f64,
+    minus_dm_smooth: f64,
+    dx_count: usize,
+    dx_sum: f64,
+    adx: Option<f64>,
+}
+
+pub struct DmiValue {
+    pub di_plus: Option<f64>,
+    pub di_minus: Option<f64>,
+    pub adx: Option<f64>,
+}
+
+impl Dmi {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period,
+            prev: None,
+            count: 0,
+            tr_smooth: 0.0,
+            plus_dm_smooth: 0.0,
+            minus_dm_smooth: 0.0,
+            dx_count: 0,
+            dx_sum: 0.0,
+            adx: None,
+        }
+    }
+
+    pub fn next(&mut self, bar: &Bar) -> DmiValue {
+        let empty = DmiValue {
+            di_plus: None,
+            di_minus: None,
+            adx: None,
+        };
+        let Some((prev_h, prev_l, prev_c)) = self.prev.replace((bar.h, bar.l, bar.c)) else {
+            return empty;
+        };
+
+        let tr = (bar.h - bar.l)
+            .max((bar.h - prev_c).abs())
+            .max((bar.l - prev_c).abs());
+        let up_move = bar.h - prev_h;
+        let down_move = prev_l - bar.l;
+        let plus_dm = if up_move > down_move && up_move > 0.0 {
+            up_move
+        } else {
+            0.0
+        };
+        let minus_dm = if down_move > up_move && down_move > 0.0 {
+            down_move
+        } else {
+            0.0
+        };
+
+        let period = self.period as f64;
+        self.count += 1;
+        if self.count <= self.period {
+            self.tr_smooth += tr;
+            self.plus_dm_smooth += plus_dm;
+            self.minus_dm_smooth += minus_dm;
+            if self.count < self.period {
+                return empty;
+            }
+        } else {
+            self.tr_smooth = self.tr_smooth - self.tr_smooth / period + tr;
+            self.plus_dm_smooth = self.plus_dm_smooth - self.plus_dm_smooth / period + plus_dm;
+            self.minus_dm_smooth = self.minus_dm_smooth - self.minus_dm_smooth / period + minus_dm;
+        }
+
+        if self.tr_smooth == 0.0 {
+            return empty;
+        }
+        let di_plus = self.plus_dm_smooth / self.tr_smooth * 100.0;
+        let di_minus = self.minus_dm_smooth / self.tr_smooth * 100.0;
+        let dx = if di_plus + di_minus == 0.0 {
+            0.0
+        } else {
+            (di_plus - di_minus).abs() / (di_plus + di_minus) * 100.0
+        };
+
+        self.adx = match self.adx {
+            Some(prev) => Some((prev * (period - 1.0) + dx) / period),
+            None => {
+                self.dx_sum += dx;
+                self.dx_count += 1;
+                (self.dx_count == self.period).then(|| self.dx_sum / period)
+            }
+        };
+
+        DmiValue {
+            di_plus: Some(di_plus),
+            di_minus: Some(di_minus),
+            adx: self.adx,
+        }
+    }
+}
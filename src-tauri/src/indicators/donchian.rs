@@ -0,0 +1,53 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::Bar;
+
+/// 唐奇安通道，與 `cls_tools/donchian.ts` 相同
+#[derive(Serialize, Deserialize)]
+pub struct Donchian {
+    period: usize,
+    highs: VecDeque<f64>,
+    lows: VecDeque<f64>,
+}
+
+pub struct DonchianValue {
+    pub upper: Option<f64>,
+    pub middle: Option<f64>,
+    pub lower: Option<f64>,
+}
+
+impl Donchian {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period,
+            highs: VecDeque::with_capacity(period + 1),
+            lows: VecDeque::with_capacity(period + 1),
+        }
+    }
+
+    pub fn next(&mut self, bar: &Bar) -> DonchianValue {
+        self.highs.push_back(bar.h);
+        self.lows.push_back(bar.l);
+        if self.highs.len() > self.period {
+            self.highs.pop_front();
+            self.lows.pop_front();
+        }
+        if self.highs.len() < self.period {
+            return DonchianValue {
+                upper: None,
+                middle: None,
+                lower: None,
+            };
+        }
+
+        let upper = self.highs.iter().copied().fold(f64::MIN, f64::max);
+        let lower = self.lows.iter().copied().fold(f64::MAX, f64::min);
+        DonchianValue {
+            upper: Some(upper),
+            middle: Some((upper + lower) / 2.0),
+            lower: Some(lower),
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+/// 指數移動平均，前 period 筆以 SMA 作為種子
+#[derive(Serialize, Deserialize)]
+pub struct Ema {
+    period: usize,
+    alpha: f64,
+    seed_sum: f64,
+    count: usize,
+    value: Option<f64>,
+}
+
+impl Ema {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period,
+            alpha: 2.0 / (period as f64 + 1.0),
+            seed_sum: 0.0,
+            count: 0,
+            value: None,
+        }
+    }
+
+    pub fn next(&mut self, value: f64) -> Option<f64> {
+        self.value = match self.value {
+            Some(prev) => Some(prev + self.alpha * (value - prev)),
+            None => {
+                self.seed_sum += value;
+                self.count += 1;
+                (self.count == self.period).then(|| self.seed_sum / self.period as f64)
+            }
+        };
+        self.value
+    }
+}
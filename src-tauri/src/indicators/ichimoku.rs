@@ -0,0 +1,76 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::Bar;
+
+/// 一目均衡表 (9, 26, 52)，延遲線直接記錄當日收盤，位移交由圖表處理
+#[derive(Serialize, Deserialize)]
+pub struct Ichimoku {
+    highs: VecDeque<f64>,
+    lows: VecDeque<f64>,
+}
+
+pub struct IchimokuValue {
+    pub tenkan: Option<f64>,
+    pub kijun: Option<f64>,
+    pub senkou_a: Option<f64>,
+    pub senkou_b: Option<f64>,
+    pub chikou: Option<f64>,
+}
+
+const TENKAN: usize = 9;
+const KIJUN: usize = 26;
+const SENKOU_B: usize = 52;
+
+impl Ichimoku {
+    pub fn new() -> Self {
+        Self {
+            highs: VecDeque::with_capacity(SENKOU_B + 1),
+            lows: VecDeque::with_capacity(SENKOU_B + 1),
+        }
+    }
+
+    fn mid(&self, period: usize) -> Option<f64> {
+        if self.highs.len() < period {
+            return None;
+        }
+        let skip = self.highs.len() - period;
+        let high = self
+            .highs
+            .iter()
+            .skip(skip)
+            .copied()
+            .fold(f64::MIN, f64::max);
+        let low = self
+            .lows
+            .iter()
+            .skip(skip)
+            .copied()
+            .fold(f64::MAX, f64::min);
+        Some((high + low) / 2.0)
+    }
+
+    pub fn next(&mut self, bar: &Bar) -> IchimokuValue {
+        self.highs.push_back(bar.h);
+        self.lows.push_back(bar.l);
+        if self.highs.len() > SENKOU_B {
+            self.highs.pop_front();
+            self.lows.pop_front();
+        }
+
+        let tenkan = self.mid(TENKAN);
+        let kijun = self.mid(KIJUN);
+        let senkou_a = match (tenkan, kijun) {
+            (Some(t), Some(k)) => Some((t + k) / 2.0),
+            _ => None,
+        };
+        IchimokuValue {
+            tenkan,
+            kijun,
+            senkou_a,
+            senkou_b: self.mid(SENKOU_B),
+            chikou: Some(bar.c),
+        }
+    }
+}
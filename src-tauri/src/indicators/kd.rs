@@ -0,0 +1,64 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::Bar;
+
+/// KD 隨機指標 (台灣慣用 1/3 平滑，K、D 初始值 50)
+#[derive(Serialize, Deserialize)]
+pub struct Kd {
+    period: usize,
+    highs: VecDeque<f64>,
+    lows: VecDeque<f64>,
+    k: f64,
+    d: f64,
+}
+
+pub struct KdValue {
+    pub k: Option<f64>,
+    pub d: Option<f64>,
+    pub j: Option<f64>,
+}
+
+impl Kd {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period,
+            highs: VecDeque::with_capacity(period + 1),
+            lows: VecDeque::with_capacity(period + 1),
+            k: 50.0,
+            d: 50.0,
+        }
+    }
+
+    pub fn next(&mut self, bar: &Bar) -> KdValue {
+        self.highs.push_back(bar.h);
+        self.lows.push_back(bar.l);
+        if self.highs.len() > self.period {
+            self.highs.pop_front();
+            self.lows.pop_front();
+        }
+        if self.highs.len() < self.period {
+            return KdValue {
+                k: None,
+                d: None,
+                j: None,
+            };
+        }
+
+        let high = self.highs.iter().copied().fold(f64::MIN, f64::max);
+        let low = self.lows.iter().copied().fold(f64::MAX, f64::min);
+        let rsv = if high > low {
+            (bar.c - low) / (high - low) * 100.0
+        } else {
+            50.0
+        };
+        self.k = self.k * 2.0 / 3.0 + rsv / 3.0;
+        self.d = self.d * 2.0 / 3.0 + self.k / 3.0;
+        KdValue {
+            k: Some(self.k),
+            d: Some(self.d),
+            j: Some(3.0 * self.k - 2.0 * self.d),
+        }
+    }
+}
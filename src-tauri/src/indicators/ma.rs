@@ -0,0 +1,48 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+/// 簡單移動平均 (SMA)，同時提供扣抵值
+#[derive(Serialize, Deserialize)]
+pub struct Ma {
+    period: usize,
+    window: VecDeque<f64>,
+    sum: f64,
+}
+
+pub struct MaValue {
+    pub ma: Option<f64>,
+    /// 下一根 K 線進來時會被移出視窗的數值
+    pub ded: Option<f64>,
+}
+
+impl Ma {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period,
+            window: VecDeque::with_capacity(period + 1),
+            sum: 0.0,
+        }
+    }
+
+    pub fn next(&mut self, value: f64) -> MaValue {
+        self.window.push_back(value);
+        self.sum += value;
+        if self.window.len() > self.period {
+            if let Some(out) = self.window.pop_front() {
+                self.sum -= out;
+            }
+        }
+
+        if self.window.len() < self.period {
+            return MaValue {
+                ma: None,
+                ded: None,
+            };
+        }
+        MaValue {
+            ma: Some(self.sum / self.period as f64),
+            ded: self.window.front().copied(),
+        }
+    }
+}
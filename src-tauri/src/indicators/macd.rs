@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+use super::ema::Ema;
+
+/// MACD(12, 26, 9)：dif = EMA12 - EMA26，macd = dif 的 EMA9，osc = dif - macd
+#[derive(Serialize, Deserialize)]
+pub struct Macd {
+    fast: Ema,
+    slow: Ema,
+    signal: Ema,
+}
+
+pub struct MacdValue {
+    pub dif: Option<f64>,
+    pub macd: Option<f64>,
+    pub osc: Option<f64>,
+}
+
+impl Macd {
+    pub fn new() -> Self {
+        Self {
+            fast: Ema::new(12),
+            slow: Ema::new(26),
+            signal: Ema::new(9),
+        }
+    }
+
+    pub fn next(&mut self, close: f64) -> MacdValue {
+        let fast = self.fast.next(close);
+        let slow = self.slow.next(close);
+        let dif = match (fast, slow) {
+            (Some(f), Some(s)) => Some(f - s),
+            _ => None,
+        };
+        let macd = dif.and_then(|v| self.signal.next(v));
+        let osc = match (dif, macd) {
+            (Some(d), Some(m)) => Some(d - m),
+            _ => None,
+        };
+        MacdValue { dif, macd, osc }
+    }
+}
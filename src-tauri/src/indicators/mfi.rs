@@ -0,0 +1,51 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::Bar;
+
+/// 資金流量指標，與前端 `calculateMFI` 相同
+#[derive(Serialize, Deserialize)]
+pub struct Mfi {
+    period: usize,
+    prev_tp: Option<f64>,
+    /// 正值為正向資金流，負值為負向資金流
+    flows: VecDeque<f64>,
+}
+
+impl Mfi {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period,
+            prev_tp: None,
+            flows: VecDeque::with_capacity(period + 1),
+        }
+    }
+
+    pub fn next(&mut self, bar: &Bar) -> Option<f64> {
+        let tp = (bar.h + bar.l + bar.c) / 3.0;
+        let prev = self.prev_tp.replace(tp)?;
+        let raw = tp * bar.v as f64;
+        let flow = if tp > prev {
+            raw
+        } else if tp < prev {
+            -raw
+        } else {
+            0.0
+        };
+        self.flows.push_back(flow);
+        if self.flows.len() > self.period {
+            self.flows.pop_front();
+        }
+        if self.flows.len() < self.period {
+            return None;
+        }
+
+        let positive: f64 = self.flows.iter().filter(|f| **f > 0.0).sum();
+        let negative: f64 = -self.flows.iter().filter(|f| **f < 0.0).sum::<f64>();
+        if negative == 0.0 {
+            return Some(100.0);
+        }
+        Some(100.0 - 100.0 / (1.0 + positive / negative))
+    }
+}
@@ -0,0 +1,699 @@
+mod atr;
+mod boll;
+mod cci;
+mod cmf;
+mod dmi;
+mod donchian;
+mod ema;
+mod ichimoku;
+mod kd;
+mod ma;
+mod macd;
+mod mfi;
+mod mss;
+mod obv;
+pub mod rsi;
+mod supertrend;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::Bar;
+
+/// `*_skills` 資料表的欄位順序，與前端 `SyncDatabaseHelper.saveSkills` 一致
+pub const SKILLS_COLUMNS: &[&str] = &[
+    "ma5",
+    "ma5_ded",
+    "ma10",
+    "ma10_ded",
+    "ma20",
+    "ma20_ded",
+    "ma30",
+    "ma30_ded",
+    "ma50",
+    "ma50_ded",
+    "ma60",
+    "ma60_ded",
+    "ma120",
+    "ma120_ded",
+    "ma240",
+    "ma240_ded",
+    "ema5",
+    "ema10",
+    "ema20",
+    "ema60",
+    "ema120",
+    "ema200",
+    "macd",
+    "dif",
+    "osc",
+    "k",
+    "d",
+    "j",
+    "rsi5",
+    "rsi10",
+    "bollUb",
+    "bollMa",
+    "bollLb",
+    "obv",
+    "obv_ma5",
+    "obv_ma10",
+    "obv_ma20",
+    "obv_ma60",
+    "obv_ema5",
+    "obv_ema10",
+    "obv_ema20",
+    "obv_ema60",
+    "mfi",
+    "tenkan",
+    "kijun",
+    "senkouA",
+    "senkouB",
+    "chikou",
+    "di_plus",
+    "di_minus",
+    "adx",
+    "cmf",
+    "cmf_ema5",
+    "turnover_rate",
+    "atr",
+    "cci",
+    "donchian_ub",
+    "donchian_ma",
+    "donchian_lb",
+    "supertrend",
+    "supertrend_dir",
+    "mss",
+];
+
+/// 單根 K 線的技術指標，資料不足時為 None (寫入 NULL)
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SkillsRow {
+    pub t: String,
+    pub ma5: Option<f64>,
+    pub ma5_ded: Option<f64>,
+    pub ma10: Option<f64>,
+    pub ma10_ded: Option<f64>,
+    pub ma20: Option<f64>,
+    pub ma20_ded: Option<f64>,
+    pub ma30: Option<f64>,
+    pub ma30_ded: Option<f64>,
+    pub ma50: Option<f64>,
+    pub ma50_ded: Option<f64>,
+    pub ma60: Option<f64>,
+    pub ma60_ded: Option<f64>,
+    pub ma120: Option<f64>,
+    pub ma120_ded: Option<f64>,
+    pub ma240: Option<f64>,
+    pub ma240_ded: Option<f64>,
+    pub ema5: Option<f64>,
+    pub ema10: Option<f64>,
+    pub ema20: Option<f64>,
+    pub ema60: Option<f64>,
+    pub ema120: Option<f64>,
+    pub ema200: Option<f64>,
+    pub macd: Option<f64>,
+    pub dif: Option<f64>,
+    pub osc: Option<f64>,
+    pub k: Option<f64>,
+    pub d: Option<f64>,
+    pub j: Option<f64>,
+    pub rsi5: Option<f64>,
+    pub rsi10: Option<f64>,
+    pub boll_ub: Option<f64>,
+    pub boll_ma: Option<f64>,
+    pub boll_lb: Option<f64>,
+    pub obv: Option<f64>,
+    pub obv_ma5: Option<f64>,
+    pub obv_ma10: Option<f64>,
+    pub obv_ma20: Option<f64>,
+    pub obv_ma60: Option<f64>,
+    pub obv_ema5: Option<f64>,
+    pub obv_ema10: Option<f64>,
+    pub obv_ema20: Option<f64>,
+    pub obv_ema60: Option<f64>,
+    pub mfi: Option<f64>,
+    pub tenkan: Option<f64>,
+    pub kijun: Option<f64>,
+    pub senkou_a: Option<f64>,
+    pub senkou_b: Option<f64>,
+    pub chikou: Option<f64>,
+    pub di_plus: Option<f64>,
+    pub di_minus: Option<f64>,
+    pub adx: Option<f64>,
+    pub cmf: Option<f64>,
+    pub cmf_ema5: Option<f64>,
+    pub turnover_rate: Option<f64>,
+    pub atr: Option<f64>,
+    pub cci: Option<f64>,
+    pub donchian_ub: Option<f64>,
+    pub donchian_ma: Option<f64>,
+    pub donchian_lb: Option<f64>,
+    pub supertrend: Option<f64>,
+    /// 1 為多頭，-1 為空頭
+    pub supertrend_dir: Option<f64>,
+    pub mss: Option<f64>,
+}
+
+impl SkillsRow {
+    /// 依 `SKILLS_COLUMNS` 順序輸出欄位值
+    pub fn values(&self) -> Vec<Option<f64>> {
+        vec![
+            self.ma5,
+            self.ma5_ded,
+            self.ma10,
+            self.ma10_ded,
+            self.ma20,
+            self.ma20_ded,
+            self.ma30,
+            self.ma30_ded,
+            self.ma50,
+            self.ma50_ded,
+            self.ma60,
+            self.ma60_ded,
+            self.ma120,
+            self.ma120_ded,
+            self.ma240,
+            self.ma240_ded,
+            self.ema5,
+            self.ema10,
+            self.ema20,
+            self.ema60,
+            self.ema120,
+            self.ema200,
+            self.macd,
+            self.dif,
+            self.osc,
+            self.k,
+            self.d,
+            self.j,
+            self.rsi5,
+            self.rsi10,
+            self.boll_ub,
+            self.boll_ma,
+            self.boll_lb,
+            self.obv,
+            self.obv_ma5,
+            self.obv_ma10,
+            self.obv_ma20,
+            self.obv_ma60,
+            self.obv_ema5,
+            self.obv_ema10,
+            self.obv_ema20,
+            self.obv_ema60,
+            self.mfi,
+            self.tenkan,
+            self.kijun,
+            self.senkou_a,
+            self.senkou_b,
+            self.chikou,
+            self.di_plus,
+            self.di_minus,
+            self.adx,
+            self.cmf,
+            self.cmf_ema5,
+            self.turnover_rate,
+            self.atr,
+            self.cci,
+            self.donchian_ub,
+            self.donchian_ma,
+            self.donchian_lb,
+            self.supertrend,
+            self.supertrend_dir,
+            self.mss,
+        ]
+    }
+}
+
+/// 指標狀態格式版本，調整任何指標的狀態欄位或算法時需遞增，使舊狀態失效
+pub const STATE_VERSION: i64 = 3;
+
+/// 逐根推進的指標引擎，對應前端 `processor` 中 init/next 的流程。
+/// 整個引擎可序列化，保存後即可從最後一根 K 線接續計算。
+#[derive(Serialize, Deserialize)]
+pub struct IndicatorEngine {
+    issued_shares: Option<i64>,
+    ma5: ma::Ma,
+    ma10: ma::Ma,
+    ma20: ma::Ma,
+    ma30: ma::Ma,
+    ma50: ma::Ma,
+    ma60: ma::Ma,
+    ma120: ma::Ma,
+    ma240: ma::Ma,
+    ema5: ema::Ema,
+    ema10: ema::Ema,
+    ema20: ema::Ema,
+    ema60: ema::Ema,
+    ema120: ema::Ema,
+    ema200: ema::Ema,
+    macd: macd::Macd,
+    kd: kd::Kd,
+    rsi5: rsi::Rsi,
+    rsi10: rsi::Rsi,
+    boll: boll::Boll,
+    obv: obv::Obv,
+    obv5: obv::ObvAverage,
+    obv10: obv::ObvAverage,
+    obv20: obv::ObvAverage,
+    obv60: obv::ObvAverage,
+    mfi: mfi::Mfi,
+    ichimoku: ichimoku::Ichimoku,
+    dmi: dmi::Dmi,
+    cmf: cmf::Cmf,
+    atr: atr::Atr,
+    cci: cci::Cci,
+    donchian: donchian::Donchian,
+    supertrend: supertrend::Supertrend,
+    mss: mss::Mss,
+}
+
+impl IndicatorEngine {
+    pub fn new(issued_shares: Option<i64>) -> Self {
+        Self {
+            issued_shares: issued_shares.filter(|s| *s > 0),
+            ma5: ma::Ma::new(5),
+            ma10: ma::Ma::new(10),
+            ma20: ma::Ma::new(20),
+            ma30: ma::Ma::new(30),
+            ma50: ma::Ma::new(50),
+            ma60: ma::Ma::new(60),
+            ma120: ma::Ma::new(120),
+            ma240: ma::Ma::new(240),
+            ema5: ema::Ema::new(5),
+            ema10: ema::Ema::new(10),
+            ema20: ema::Ema::new(20),
+            ema60: ema::Ema::new(60),
+            ema120: ema::Ema::new(120),
+            ema200: ema::Ema::new(200),
+            macd: macd::Macd::new(),
+            kd: kd::Kd::new(9),
+            rsi5: rsi::Rsi::new(5),
+            rsi10: rsi::Rsi::new(10),
+            boll: boll::Boll::new(20, 2.0),
+            obv: obv::Obv::new(),
+            obv5: obv::ObvAverage::new(5),
+            obv10: obv::ObvAverage::new(10),
+            obv20: obv::ObvAverage::new(20),
+            obv60: obv::ObvAverage::new(60),
+            mfi: mfi::Mfi::new(14),
+            ichimoku: ichimoku::Ichimoku::new(),
+            dmi: dmi::Dmi::new(14),
+            cmf: cmf::Cmf::new(21, 5),
+            atr: atr::Atr::new(14),
+            cci: cci::Cci::new(14),
+            donchian: donchian::Donchian::new(20),
+            supertrend: supertrend::Supertrend::new(10, 3.0),
+            mss: mss::Mss::new(20, 1.5),
+        }
+    }
+
+    /// 股本可能於同步時更新，接續計算前以最新值覆寫
+    pub fn set_issued_shares(&mut self, issued_shares: Option<i64>) {
+        self.issued_shares = issued_shares.filter(|s| *s > 0);
+    }
+
+    pub fn to_state(&self) -> Result<String, String> {
+        serde_json::to_string(self).map_err(|e| format!("[Indicators] save state error: {}", e))
+    }
+
+    pub fn from_state(state: &str) -> Result<Self, String> {
+        serde_json::from_str(state).map_err(|e| format!("[Indicators] load state error: {}", e))
+    }
+
+    pub fn next(&mut self, bar: &Bar) -> SkillsRow {
+        let ma5 = self.ma5.next(bar.c);
+        let ma10 = self.ma10.next(bar.c);
+        let ma20 = self.ma20.next(bar.c);
+        let ma30 = self.ma30.next(bar.c);
+        let ma50 = self.ma50.next(bar.c);
+        let ma60 = self.ma60.next(bar.c);
+        let ma120 = self.ma120.next(bar.c);
+        let ma240 = self.ma240.next(bar.c);
+        let ema5 = self.ema5.next(bar.c);
+        let ema10 = self.ema10.next(bar.c);
+        let ema60 = self.ema60.next(bar.c);
+        let macd = self.macd.next(bar.c);
+        let kd = self.kd.next(bar);
+        let boll = self.boll.next(bar.c);
+        let obv = self.obv.next(bar);
+        let obv5 = self.obv5.next(obv);
+        let obv10 = self.obv10.next(obv);
+        let obv20 = self.obv20.next(obv);
+        let obv60 = self.obv60.next(obv);
+        let ichimoku = self.ichimoku.next(bar);
+        let dmi = self.dmi.next(bar);
+        let cmf = self.cmf.next(bar);
+        let atr = self.atr.next(bar);
+        let donchian = self.donchian.next(bar);
+        let supertrend = self.supertrend.next(bar);
+        let mss = self.mss.next(
+            bar,
+            mss::MssInput {
+                ema5,
+                ema10,
+                ema60,
+                boll_ub: boll.ub,
+                boll_lb: boll.lb,
+                adx: dmi.adx,
+            },
+        );
+
+        SkillsRow {
+            t: bar.t.clone(),
+            ma5: ma5.ma,
+            ma5_ded: ma5.ded,
+            ma10: ma10.ma,
+            ma10_ded: ma10.ded,
+            ma20: ma20.ma,
+            ma20_ded: ma20.ded,
+            ma30: ma30.ma,
+            ma30_ded: ma30.ded,
+            ma50: ma50.ma,
+            ma50_ded: ma50.ded,
+            ma60: ma60.ma,
+            ma60_ded: ma60.ded,
+            ma120: ma120.ma,
+            ma120_ded: ma120.ded,
+            ma240: ma240.ma,
+            ma240_ded: ma240.ded,
+            ema5,
+            ema10,
+            ema20: self.ema20.next(bar.c),
+            ema60,
+            ema120: self.ema120.next(bar.c),
+            ema200: self.ema200.next(bar.c),
+            macd: macd.macd,
+            // 與前端 `state.macd.dif[last] || 0` 相同，暖機期間為 0 而非 NULL
+            dif: Some(macd.dif.unwrap_or(0.0)),
+            osc: macd.osc,
+            k: kd.k,
+            d: kd.d,
+            j: kd.j,
+            rsi5: self.rsi5.next(bar.c),
+            rsi10: self.rsi10.next(bar.c),
+            boll_ub: boll.ub,
+            boll_ma: boll.ma,
+            boll_lb: boll.lb,
+            obv: Some(obv),
+            obv_ma5: obv5.ma,
+            obv_ma10: obv10.ma,
+            obv_ma20: obv20.ma,
+            obv_ma60: obv60.ma,
+            obv_ema5: obv5.ema,
+            obv_ema10: obv10.ema,
+            obv_ema20: obv20.ema,
+            obv_ema60: obv60.ema,
+            mfi: self.mfi.next(bar),
+            tenkan: ichimoku.tenkan,
+            kijun: ichimoku.kijun,
+            senkou_a: ichimoku.senkou_a,
+            senkou_b: ichimoku.senkou_b,
+            chikou: ichimoku.chikou,
+            di_plus: dmi.di_plus,
+            di_minus: dmi.di_minus,
+            adx: dmi.adx,
+            cmf: cmf.cmf,
+            cmf_ema5: cmf.ema,
+            // 沒有股本時與前端相同寫入 0
+            turnover_rate: Some(self.issued_shares.map_or(0.0, |shares| {
+                (bar.v as f64 * 1000.0) / shares as f64 * 100.0
+            })),
+            atr: self.atr.is_ready().then_some(atr),
+            cci: self.cci.next(bar),
+            donchian_ub: donchian.upper,
+            donchian_ma: donchian.middle,
+            donchian_lb: donchian.lower,
+            supertrend: supertrend.value,
+            supertrend_dir: supertrend.direction,
+            mss,
+        }
+    }
+}
+
+/// 由最舊到最新的 K 線計算完整指標序列，作為增量更新的對照
+#[cfg(test)]
+pub fn compute(bars: &[Bar], issued_shares: Option<i64>) -> Vec<SkillsRow> {
+    let mut engine = IndicatorEngine::new(issued_shares);
+    bars.iter().map(|bar| engine.next(bar)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 以固定種子產生的隨機漫步 K 線
+    fn sample_bars(count: usize) -> Vec<Bar> {
+        let mut seed: u64 = 20241007;
+        let mut random = move || {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) as f64 / (1u64 << 31) as f64
+        };
+        let mut close = 100.0;
+        (0..count)
+            .map(|i| {
+                let open = close;
+                close = (open * (1.0 + (random() - 0.5) * 0.08)).max(1.0);
+                Bar {
+                    t: format!("{:08}", 20200101 + i),
+                    o: open,
+                    h: open.max(close) * (1.0 + random() * 0.02),
+                    l: open.min(close) * (1.0 - random() * 0.02),
+                    c: close,
+                    v: (random() * 50000.0) as i64,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn incremental_matches_full_recompute() {
+        let bars = sample_bars(400);
+        let full = compute(&bars, Some(25_000_000));
+
+        // 每次只推進一根，並經過序列化還原，模擬逐日同步
+        let mut state = IndicatorEngine::new(Some(25_000_000)).to_state().unwrap();
+        let mut incremental = Vec::with_capacity(bars.len());
+        for bar in &bars {
+            let mut engine = IndicatorEngine::from_state(&state).unwrap();
+            incremental.push(engine.next(bar));
+            state = engine.to_state().unwrap();
+        }
+        assert_eq!(full, incremental);
+    }
+
+    #[test]
+    fn resume_from_middle_matches_full_recompute() {
+        let bars = sample_bars(300);
+        let full = compute(&bars, None);
+
+        let (head, tail) = bars.split_at(250);
+        let mut engine = IndicatorEngine::new(None);
+        head.iter().for_each(|bar| {
+            engine.next(bar);
+        });
+        let mut resumed = IndicatorEngine::from_state(&engine.to_state().unwrap()).unwrap();
+        let rows: Vec<_> = tail.iter().map(|bar| resumed.next(bar)).collect();
+        assert_eq!(&full[250..], rows.as_slice());
+        assert!(rows.last().unwrap().ma240.is_some());
+    }
+
+    #[test]
+    fn keeps_ts_zero_fallbacks() {
+        let rows = compute(&sample_bars(30), None);
+        // 沒有股本時週轉率為 0，MACD 暖機期間 dif 為 0
+        assert!(rows.iter().all(|row| row.turnover_rate == Some(0.0)));
+        assert_eq!(rows[0].dif, Some(0.0));
+        assert!(rows[0].macd.is_none());
+        let rows = compute(&sample_bars(30), Some(25_000_000));
+        assert!(rows[29].dif.is_some_and(|dif| dif != 0.0));
+        assert!(rows[0].turnover_rate.is_some_and(|rate| rate > 0.0));
+    }
+
+    /// 由 `tests/fixtures/skills_parity.mjs` 以前端 anysis 計算器產生
+    const PARITY_FIXTURE: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/skills_parity.json"
+    );
+    /// 最長的暖機期 (MA240)，之後所有指標都應與前端一致
+    const WARM_UP: usize = 240;
+
+    #[derive(Deserialize)]
+    struct ParityBar {
+        t: String,
+        o: f64,
+        h: f64,
+        l: f64,
+        c: f64,
+        v: i64,
+    }
+
+    #[derive(Deserialize)]
+    struct Parity {
+        issued_shares: i64,
+        bars: Vec<ParityBar>,
+        rows: Vec<std::collections::BTreeMap<String, Option<f64>>>,
+    }
+
+    #[test]
+    #[ignore = "run `node src-tauri/tests/fixtures/skills_parity.mjs` with frontend dependencies installed first"]
+    fn engine_matches_ts_fixture() {
+        let text = std::fs::read_to_string(PARITY_FIXTURE).expect("skills_parity.json");
+        let parity: Parity = serde_json::from_str(&text).unwrap();
+        let bars: Vec<Bar> = parity
+            .bars
+            .into_iter()
+            .map(|b| Bar {
+                t: b.t,
+                o: b.o,
+                h: b.h,
+                l: b.l,
+                c: b.c,
+                v: b.v,
+            })
+            .collect();
+        let rows = compute(&bars, Some(parity.issued_shares));
+        // 前端沒有的新欄位 (ATR 之後) 另以 cls_tools 對照
+        let legacy = SKILLS_COLUMNS
+            .iter()
+            .position(|column| *column == "atr")
+            .unwrap();
+        let mut mismatches = Vec::new();
+        for (i, (row, expected)) in rows.iter().zip(&parity.rows).enumerate().skip(WARM_UP) {
+            for (column, actual) in SKILLS_COLUMNS[..legacy].iter().zip(row.values()) {
+                let expected = expected.get(*column).copied().flatten();
+                let same = match (actual, expected) {
+                    (Some(a), Some(e)) => (a - e).abs() <= 1e-6 * e.abs().max(1.0),
+                    (a, e) => a == e,
+                };
+                if !same {
+                    mismatches.push(format!("{} #{}: {:?} != {:?}", column, i, actual, expected));
+                }
+            }
+        }
+        assert!(
+            mismatches.is_empty(),
+            "{}",
+            mismatches[..mismatches.len().min(20)].join("\n")
+        );
+    }
+
+    /// 由 `tests/fixtures/indicators_parity.mjs` 以 `src/cls_tools` 產生；
+    /// supertrend 為 TS 預設 (14, 2.5)，mss 附上當根輸入的其他指標
+    const CLS_TOOLS_FIXTURE: &str = include_str!("../../tests/fixtures/indicators_parity.json");
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct ClsMssInput {
+        ema5: f64,
+        ema10: f64,
+        ema60: f64,
+        boll_ub: Option<f64>,
+        boll_lb: Option<f64>,
+        adx: f64,
+    }
+
+    #[derive(Deserialize)]
+    struct ClsRow {
+        atr: f64,
+        supertrend: Option<f64>,
+        direction: f64,
+        cci: Option<f64>,
+        dc_upper: Option<f64>,
+        dc_middle: Option<f64>,
+        dc_lower: Option<f64>,
+        mss_input: ClsMssInput,
+        mss: f64,
+    }
+
+    #[derive(Deserialize)]
+    struct ClsTools {
+        bars: Vec<Bar>,
+        rows: Vec<ClsRow>,
+    }
+
+    fn cls_tools() -> ClsTools {
+        serde_json::from_str(CLS_TOOLS_FIXTURE).unwrap()
+    }
+
+    fn approx(actual: Option<f64>, expected: Option<f64>) -> bool {
+        match (actual, expected) {
+            (Some(a), Some(e)) => (a - e).abs() <= 1e-9 * e.abs().max(1.0),
+            (a, e) => a == e,
+        }
+    }
+
+    #[test]
+    fn atr_matches_cls_tools() {
+        let fixture = cls_tools();
+        let mut atr = atr::Atr::new(14);
+        for (i, (bar, row)) in fixture.bars.iter().zip(&fixture.rows).enumerate() {
+            let value = atr.next(bar);
+            assert!(approx(Some(value), Some(row.atr)), "#{}", i);
+            assert_eq!(atr.is_ready(), i >= 13, "#{}", i);
+        }
+    }
+
+    #[test]
+    fn supertrend_matches_cls_tools() {
+        let fixture = cls_tools();
+        let mut supertrend = supertrend::Supertrend::new(14, 2.5);
+        for (i, (bar, row)) in fixture.bars.iter().zip(&fixture.rows).enumerate() {
+            let value = supertrend.next(bar);
+            if i < 13 {
+                // ATR 暖機中不輸出
+                assert!(value.value.is_none() && value.direction.is_none(), "#{}", i);
+                continue;
+            }
+            assert!(approx(value.value, row.supertrend), "#{}", i);
+            // TS 以 -1 表示多頭
+            assert_eq!(value.direction, Some(-row.direction), "#{}", i);
+        }
+    }
+
+    #[test]
+    fn cci_and_donchian_match_cls_tools() {
+        let fixture = cls_tools();
+        let mut cci = cci::Cci::new(14);
+        let mut donchian = donchian::Donchian::new(20);
+        for (i, (bar, row)) in fixture.bars.iter().zip(&fixture.rows).enumerate() {
+            assert!(approx(cci.next(bar), row.cci), "cci #{}", i);
+            let channel = donchian.next(bar);
+            assert!(approx(channel.upper, row.dc_upper), "upper #{}", i);
+            assert!(approx(channel.middle, row.dc_middle), "middle #{}", i);
+            assert!(approx(channel.lower, row.dc_lower), "lower #{}", i);
+        }
+    }
+
+    #[test]
+    fn mss_matches_cls_tools() {
+        let fixture = cls_tools();
+        let mut mss = mss::Mss::new(20, 1.5);
+        for (i, (bar, row)) in fixture.bars.iter().zip(&fixture.rows).enumerate() {
+            let input = &row.mss_input;
+            let score = mss.next(
+                bar,
+                mss::MssInput {
+                    ema5: Some(input.ema5),
+                    ema10: Some(input.ema10),
+                    ema60: Some(input.ema60),
+                    boll_ub: input.boll_ub,
+                    boll_lb: input.boll_lb,
+                    adx: Some(input.adx),
+                },
+            );
+            assert_eq!(score, Some(row.mss), "#{}", i);
+        }
+        // EMA 尚無值時不評分
+        let input = mss::MssInput {
+            ema5: None,
+            ema10: None,
+            ema60: None,
+            boll_ub: None,
+            boll_lb: None,
+            adx: None,
+        };
+        assert_eq!(mss.next(&fixture.bars[0], input), None);
+    }
+}
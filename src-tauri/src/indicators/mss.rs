@@ -0,0 +1,93 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use super::atr::true_range;
+use crate::types::Bar;
+
+/// EMA60 斜率的回看根數
+const SLOPE_LOOKBACK: usize = 5;
+
+/// 市場狀態分數 (MSS)，評分同 `cls_tools/mss.ts`：
+/// 擠壓 1 分、寬幅震盪 2.5 分、均線發散趨勢 4 分，ADX > 25 且上升再加 1 分。
+/// 與 TS 不同的是 EMA5/10/60 任一尚無值時回傳 None，且不計入 EMA60 斜率的歷史
+#[derive(Serialize, Deserialize)]
+pub struct Mss {
+    atr_period: usize,
+    kc_multiplier: f64,
+    prev_close: Option<f64>,
+    atr: Option<f64>,
+    prev_adx: f64,
+    ema60_history: VecDeque<f64>,
+}
+
+/// 計算 MSS 所需的其他指標
+pub struct MssInput {
+    pub ema5: Option<f64>,
+    pub ema10: Option<f64>,
+    pub ema60: Option<f64>,
+    pub boll_ub: Option<f64>,
+    pub boll_lb: Option<f64>,
+    pub adx: Option<f64>,
+}
+
+impl Mss {
+    pub fn new(atr_period: usize, kc_multiplier: f64) -> Self {
+        Self {
+            atr_period,
+            kc_multiplier,
+            prev_close: None,
+            atr: None,
+            prev_adx: 0.0,
+            ema60_history: VecDeque::with_capacity(SLOPE_LOOKBACK + 2),
+        }
+    }
+
+    pub fn next(&mut self, bar: &Bar, input: MssInput) -> Option<f64> {
+        let tr = true_range(bar, self.prev_close.replace(bar.c));
+        let period = self.atr_period as f64;
+        let atr = match self.atr {
+            Some(prev) => (prev * (period - 1.0) + tr) / period,
+            None => tr,
+        };
+        self.atr = Some(atr);
+
+        let adx = input.adx.unwrap_or(0.0);
+        let prev_adx = std::mem::replace(&mut self.prev_adx, adx);
+
+        let (Some(ema5), Some(ema10), Some(ema60)) = (input.ema5, input.ema10, input.ema60) else {
+            return None;
+        };
+        self.ema60_history.push_back(ema60);
+        if self.ema60_history.len() > SLOPE_LOOKBACK + 1 {
+            self.ema60_history.pop_front();
+        }
+        let slope = match self.ema60_history.front() {
+            Some(first) if self.ema60_history.len() > SLOPE_LOOKBACK => {
+                (ema60 - first) / SLOPE_LOOKBACK as f64
+            }
+            _ => 0.0,
+        };
+
+        let kc_width = atr * self.kc_multiplier * 2.0;
+        let bb_width = match (input.boll_ub, input.boll_lb) {
+            (Some(ub), Some(lb)) => ub - lb,
+            _ => 0.0,
+        };
+
+        let mut score = if bb_width < kc_width {
+            1.0
+        } else {
+            let aligned = (ema5 > ema10 && ema10 > ema60) || (ema5 < ema10 && ema10 < ema60);
+            if aligned && slope.abs() > ema60 * 0.0005 {
+                4.0
+            } else {
+                2.5
+            }
+        };
+        if adx > 25.0 && adx > prev_adx {
+            score += 1.0;
+        }
+        Some(score)
+    }
+}
@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+
+use super::{ema::Ema, ma::Ma};
+use crate::types::Bar;
+
+/// 能量潮，首根 K 線為 0
+#[derive(Serialize, Deserialize)]
+pub struct Obv {
+    prev_close: Option<f64>,
+    obv: f64,
+}
+
+impl Obv {
+    pub fn new() -> Self {
+        Self {
+            prev_close: None,
+            obv: 0.0,
+        }
+    }
+
+    pub fn next(&mut self, bar: &Bar) -> f64 {
+        if let Some(prev) = self.prev_close {
+            if bar.c > prev {
+                self.obv += bar.v as f64;
+            } else if bar.c < prev {
+                self.obv -= bar.v as f64;
+            }
+        }
+        self.prev_close = Some(bar.c);
+        self.obv
+    }
+}
+
+/// OBV 的均線與指數均線，對應前端 `ObvEma`
+#[derive(Serialize, Deserialize)]
+pub struct ObvAverage {
+    ma: Ma,
+    ema: Ema,
+}
+
+pub struct ObvAverageValue {
+    pub ma: Option<f64>,
+    pub ema: Option<f64>,
+}
+
+impl ObvAverage {
+    pub fn new(period: usize) -> Self {
+        Self {
+            ma: Ma::new(period),
+            ema: Ema::new(period),
+        }
+    }
+
+    pub fn next(&mut self, obv: f64) -> ObvAverageValue {
+        ObvAverageValue {
+            ma: self.ma.next(obv).ma,
+            ema: self.ema.next(obv),
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+
+/// RSI，與前端 `calculateRSI` 相同：先取前 period 筆平均，之後以 Wilder 平滑
+#[derive(Serialize, Deserialize)]
+pub struct Rsi {
+    period: usize,
+    prev_close: Option<f64>,
+    count: usize,
+    avg_gain: f64,
+    avg_loss: f64,
+}
+
+impl Rsi {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period,
+            prev_close: None,
+            count: 0,
+            avg_gain: 0.0,
+            avg_loss: 0.0,
+        }
+    }
+
+    pub fn next(&mut self, close: f64) -> Option<f64> {
+        let prev = self.prev_close.replace(close)?;
+        let diff = close - prev;
+        let gain = diff.max(0.0);
+        let loss = (-diff).max(0.0);
+        let period = self.period as f64;
+
+        self.count += 1;
+        if self.count <= self.period {
+            self.avg_gain += gain / period;
+            self.avg_loss += loss / period;
+            if self.count < self.period {
+                return None;
+            }
+        } else {
+            self.avg_gain = (self.avg_gain * (period - 1.0) + gain) / period;
+            self.avg_loss = (self.avg_loss * (period - 1.0) + loss) / period;
+        }
+
+        if self.avg_loss == 0.0 {
+            return Some(100.0);
+        }
+        Some(100.0 - 100.0 / (1.0 + self.avg_gain / self.avg_loss))
+    }
+}
@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+
+use super::atr::Atr;
+use crate::types::Bar;
+
+/// 超級趨勢，軌道與翻轉規則同 `cls_tools/supertrend.ts`，但有三點不同：
+/// - 引擎使用 (10, 3.0)，TS 預設為 (14, 2.5)
+/// - 方向 1 為多頭、-1 為空頭，TS 正好相反
+/// - ATR 未滿 period 根前 value/direction 皆為 None，TS 從第一根即輸出
+#[derive(Serialize, Deserialize)]
+pub struct Supertrend {
+    multiplier: f64,
+    atr: Atr,
+    prev_close: Option<f64>,
+    final_upper: f64,
+    final_lower: f64,
+    value: Option<f64>,
+    /// 1 為多頭，-1 為空頭
+    direction: f64,
+}
+
+pub struct SupertrendValue {
+    pub value: Option<f64>,
+    pub direction: Option<f64>,
+}
+
+impl Supertrend {
+    pub fn new(period: usize, multiplier: f64) -> Self {
+        Self {
+            multiplier,
+            atr: Atr::new(period),
+            prev_close: None,
+            final_upper: 0.0,
+            final_lower: 0.0,
+            value: None,
+            direction: -1.0,
+        }
+    }
+
+    pub fn next(&mut self, bar: &Bar) -> SupertrendValue {
+        let atr = self.atr.next(bar);
+        let src = (bar.h + bar.l) / 2.0;
+        let basic_upper = src + self.multiplier * atr;
+        let basic_lower = src - self.multiplier * atr;
+
+        let (final_upper, final_lower) = match self.prev_close {
+            Some(prev_close) => (
+                if basic_upper < self.final_upper || prev_close > self.final_upper {
+                    basic_upper
+                } else {
+                    self.final_upper
+                },
+                if basic_lower > self.final_lower || prev_close < self.final_lower {
+                    basic_lower
+                } else {
+                    self.final_lower
+                },
+            ),
+            None => (basic_upper, basic_lower),
+        };
+
+        if self.prev_close.is_some() {
+            // 前一根沿著上軌代表空頭，收盤突破當前上軌才翻多；反之跌破下軌才翻空
+            self.direction = if self.value.is_none() || self.value == Some(self.final_upper) {
+                if bar.c > final_upper {
+                    1.0
+                } else {
+                    -1.0
+                }
+            } else if bar.c < final_lower {
+                -1.0
+            } else {
+                1.0
+            };
+        }
+
+        let value = if self.direction > 0.0 {
+            final_lower
+        } else {
+            final_upper
+        };
+        self.value = (value > 0.0).then_some(value);
+        self.final_upper = final_upper;
+        self.final_lower = final_lower;
+        self.prev_close = Some(bar.c);
+
+        if !self.atr.is_ready() {
+            return SupertrendValue {
+                value: None,
+                direction: None,
+            };
+        }
+        SupertrendValue {
+            value: self.value,
+            direction: Some(self.direction),
+        }
+    }
+}
@@ -0,0 +1,543 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::Duration;
+use serde::Serialize;
+use sqlx::{QueryBuilder, Sqlite, SqlitePool};
+use tauri::State;
+
+use crate::calendar;
+use crate::listing;
+use crate::sqlite::connection::Db;
+use crate::sqlite::writer;
+
+/// 全部同步中股票組成的大盤指數，作為相對強弱的比較基準
+pub const MARKET_GROUP: &str = "全市場";
+/// 可在選股條件中使用的個股產業排名欄位
+pub const RANK_COLUMNS: &[&str] = &[
+    "return_20",
+    "group_percentile",
+    "group_relative_strength",
+    "group_rs_percentile",
+];
+/// 相對強弱與個股報酬的比較根數
+const RS_BARS: usize = 20;
+/// 每次同步重算的最近交易日數
+const RECENT_DAYS: i64 = 5;
+/// 指數起始值
+const BASE_INDEX: f64 = 100.0;
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct IndustryIndex {
+    pub industry_group: String,
+    pub t: String,
+    pub members: i64,
+    /// 等權與市值加權 (前一日收盤價 × 發行股數) 的當日報酬 (%)
+    pub equal_return: f64,
+    pub cap_return: f64,
+    pub equal_index: f64,
+    pub cap_index: f64,
+    /// 市值加權指數 20 日漲幅相對大盤的比值 (× 100)，100 以上為強於大盤
+    pub relative_strength: Option<f64>,
+    /// 當日各產業相對強弱的百分位 (0-100)
+    pub rs_percentile: Option<f64>,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct GroupReturn {
+    t: String,
+    industry_group: String,
+    members: i64,
+    equal_return: f64,
+    cap_return: f64,
+}
+
+/// 以前一日的指數值串接報酬，`history` 為各產業依日期排序的既有指數
+fn chain(
+    returns: Vec<GroupReturn>,
+    mut history: HashMap<String, Vec<IndustryIndex>>,
+) -> Vec<IndustryIndex> {
+    let mut days: BTreeMap<String, Vec<IndustryIndex>> = BTreeMap::new();
+    for row in returns {
+        let series = history.entry(row.industry_group.clone()).or_default();
+        let (equal_base, cap_base) = series.last().map_or((BASE_INDEX, BASE_INDEX), |prev| {
+            (prev.equal_index, prev.cap_index)
+        });
+        let index = IndustryIndex {
+            industry_group: row.industry_group,
+            t: row.t,
+            members: row.members,
+            equal_return: row.equal_return,
+            cap_return: row.cap_return,
+            equal_index: equal_base * (1.0 + row.equal_return / 100.0),
+            cap_index: cap_base * (1.0 + row.cap_return / 100.0),
+            relative_strength: None,
+            rs_percentile: None,
+        };
+        series.push(index.clone());
+        days.entry(index.t.clone()).or_default().push(index);
+    }
+
+    // 往前 RS_BARS 個交易日以大盤 (每個交易日都有) 的日期為準，
+    // 產業當天沒有成交時沿用之前最後的指數，避免以產業自身的根數回推到更早的日期
+    let sessions: Vec<&str> = history
+        .get(MARKET_GROUP)
+        .map(|series| series.iter().map(|row| row.t.as_str()).collect())
+        .unwrap_or_default();
+    let lagged_day = |t: &str| -> Option<&str> {
+        let i = sessions.binary_search(&t).ok()?;
+        sessions.get(i.checked_sub(RS_BARS)?).copied()
+    };
+    let index_at = |group: &str, t: &str| -> Option<f64> {
+        let series = history.get(group)?;
+        let n = series.partition_point(|row| row.t.as_str() <= t);
+        series.get(n.checked_sub(1)?).map(|row| row.cap_index)
+    };
+    let change =
+        |group: &str, t: &str, prev: &str| Some(index_at(group, t)? / index_at(group, prev)?);
+    let mut result = Vec::new();
+    for (t, mut rows) in days {
+        let prev = lagged_day(&t);
+        let market = prev.and_then(|prev| change(MARKET_GROUP, &t, prev));
+        for row in rows.iter_mut() {
+            row.relative_strength = market
+                .and_then(|market| Some(change(&row.industry_group, &t, prev?)? / market * 100.0));
+        }
+        let strengths: Vec<f64> = rows
+            .iter()
+            .filter(|row| row.industry_group != MARKET_GROUP)
+            .filter_map(|row| row.relative_strength)
+            .collect();
+        for row in rows.iter_mut() {
+            if row.industry_group == MARKET_GROUP {
+                continue;
+            }
+            row.rs_percentile = row.relative_strength.map(|rs| percent_rank(&strengths, rs));
+        }
+        result.extend(rows);
+    }
+    result
+}
+
+/// 與 SQL `PERCENT_RANK()` 相同：比 `value` 小的個數 / (n - 1)，以 0-100 表示
+fn percent_rank(values: &[f64], value: f64) -> f64 {
+    if values.len() < 2 {
+        return 0.0;
+    }
+    let below = values.iter().filter(|v| **v < value).count();
+    below as f64 / (values.len() - 1) as f64 * 100.0
+}
+
+/// 產業別取當日的分類，與 `universe::as_of` 相同
+const GROUP_AS_OF: &str = "COALESCE(h.industry_group, s.industry_group)";
+const HISTORY_JOIN: &str = "LEFT JOIN stock_history h ON h.stock_id = d.stock_id
+              AND h.valid_from <= d.t AND (h.valid_to IS NULL OR h.valid_to > d.t)";
+
+async fn group_returns(pool: &SqlitePool, from: &str) -> Result<Vec<GroupReturn>, String> {
+    let lookback = calendar::parse_day(from)
+        .map(|day| calendar::format_day(day - Duration::days(14)))
+        .unwrap_or_default();
+    let sql = format!(
+        "WITH d AS (
+            SELECT dd.stock_id, dd.t, dd.c,
+                LAG(dd.c) OVER (PARTITION BY dd.stock_id ORDER BY dd.t) AS prev_c
+            FROM daily_deal dd
+            JOIN stock s ON s.stock_id = dd.stock_id AND s.{filter}
+            WHERE dd.t >= $1
+        ), r AS (
+            SELECT d.t, {group} AS industry_group,
+                (d.c / d.prev_c - 1) * 100 AS ret,
+                d.prev_c * s.issued_shares AS weight
+            FROM d
+            JOIN stock s ON s.stock_id = d.stock_id
+            {history}
+            WHERE d.t >= $2 AND d.prev_c > 0
+        )
+        SELECT t, industry_group, COUNT(*) AS members, AVG(ret) AS equal_return,
+            COALESCE(SUM(ret * weight) / SUM(weight), AVG(ret)) AS cap_return
+        FROM r
+        WHERE industry_group IS NOT NULL AND industry_group != ''
+        GROUP BY t, industry_group
+        UNION ALL
+        SELECT t, '{market}', COUNT(*), AVG(ret),
+            COALESCE(SUM(ret * weight) / SUM(weight), AVG(ret))
+        FROM r
+        GROUP BY t
+        ORDER BY t",
+        filter = listing::SYNCED_TYPES_FILTER,
+        group = GROUP_AS_OF,
+        history = HISTORY_JOIN,
+        market = MARKET_GROUP
+    );
+    sqlx::query_as(&sql)
+        .bind(lookback)
+        .bind(from)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("[Industry] load returns error: {}", e))
+}
+
+/// `from` 之前最後 RS_BARS + 1 個交易日的指數，供串接與計算相對強弱；
+/// 另帶上各產業在這段期間之前的最後一筆，供期間內沒有成交的產業沿用指數
+async fn load_history(
+    pool: &SqlitePool,
+    from: &str,
+) -> Result<HashMap<String, Vec<IndustryIndex>>, String> {
+    let rows: Vec<IndustryIndex> = sqlx::query_as(
+        "WITH w AS (
+             SELECT MIN(t) AS start FROM (
+                 SELECT DISTINCT t FROM industry_index WHERE t < $1 ORDER BY t DESC LIMIT $2
+             )
+         )
+         SELECT x.* FROM industry_index x, w
+         WHERE x.t < $1 AND (
+             x.t >= w.start
+             OR x.t = (
+                 SELECT MAX(y.t) FROM industry_index y
+                 WHERE y.industry_group = x.industry_group AND y.t < w.start
+             )
+         )
+         ORDER BY x.t",
+    )
+    .bind(from)
+    .bind(RS_BARS as i64 + 1)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("[Industry] load history error: {}", e))?;
+    let mut history: HashMap<String, Vec<IndustryIndex>> = HashMap::new();
+    for row in rows {
+        history
+            .entry(row.industry_group.clone())
+            .or_default()
+            .push(row);
+    }
+    Ok(history)
+}
+
+async fn save_indices(pool: &SqlitePool, rows: &[IndustryIndex]) -> Result<(), String> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("[Industry] begin error: {}", e))?;
+    for chunk in rows.chunks(1000) {
+        let mut builder: QueryBuilder<Sqlite> = QueryBuilder::new(
+            "INSERT OR REPLACE INTO industry_index (industry_group, t, members, equal_return,
+             cap_return, equal_index, cap_index, relative_strength, rs_percentile) ",
+        );
+        builder.push_values(chunk, |mut row, index| {
+            row.push_bind(&index.industry_group)
+                .push_bind(&index.t)
+                .push_bind(index.members)
+                .push_bind(index.equal_return)
+                .push_bind(index.cap_return)
+                .push_bind(index.equal_index)
+                .push_bind(index.cap_index)
+                .push_bind(index.relative_strength)
+                .push_bind(index.rs_percentile);
+        });
+        builder
+            .build()
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("[Industry] save index error: {}", e))?;
+    }
+    writer::bump_versions(&mut *tx, &["industry_index"]).await?;
+    tx.commit()
+        .await
+        .map_err(|e| format!("[Industry] commit error: {}", e))
+}
+
+/// 個股 20 日報酬在所屬產業中的百分位，並附上產業的相對強弱
+async fn save_ranks(pool: &SqlitePool, from: &str) -> Result<u64, String> {
+    let lookback = calendar::parse_day(from)
+        .map(|day| calendar::format_day(day - Duration::days(RS_BARS as i64 * 3)))
+        .unwrap_or_default();
+    let sql = format!(
+        "WITH d AS (
+            SELECT dd.stock_id, dd.t,
+                (dd.c / LAG(dd.c, {bars}) OVER (PARTITION BY dd.stock_id ORDER BY dd.t) - 1) * 100
+                    AS return_20
+            FROM daily_deal dd
+            JOIN stock s ON s.stock_id = dd.stock_id AND s.{filter}
+            WHERE dd.t >= $1
+        ), g AS (
+            SELECT d.stock_id, d.t, d.return_20, {group} AS industry_group
+            FROM d
+            JOIN stock s ON s.stock_id = d.stock_id
+            {history}
+            WHERE d.t >= $2 AND d.return_20 IS NOT NULL
+        )
+        INSERT OR REPLACE INTO industry_rank (stock_id, t, industry_group, return_20,
+            group_percentile, group_relative_strength, group_rs_percentile)
+        SELECT g.stock_id, g.t, g.industry_group, g.return_20,
+            PERCENT_RANK() OVER (PARTITION BY g.t, g.industry_group ORDER BY g.return_20) * 100,
+            x.relative_strength, x.rs_percentile
+        FROM g
+        LEFT JOIN industry_index x ON x.industry_group = g.industry_group AND x.t = g.t
+        WHERE g.industry_group IS NOT NULL AND g.industry_group != ''",
+        bars = RS_BARS,
+        filter = listing::SYNCED_TYPES_FILTER,
+        group = GROUP_AS_OF,
+        history = HISTORY_JOIN
+    );
+    let rows = sqlx::query(&sql)
+        .bind(lookback)
+        .bind(from)
+        .execute(pool)
+        .await
+        .map(|result| result.rows_affected())
+        .map_err(|e| format!("[Industry] save ranks error: {}", e))?;
+    writer::bump_versions(pool, &["industry_rank"]).await?;
+    Ok(rows)
+}
+
+/// 重算 `from` (YYYYMMDD，含) 之後的產業指數與個股排名，空字串為全部歷史
+pub async fn compute(pool: &SqlitePool, from: &str) -> Result<usize, String> {
+    let returns = group_returns(pool, from).await?;
+    let history = load_history(pool, from).await?;
+    let indices = chain(returns, history);
+    save_indices(pool, &indices).await?;
+    save_ranks(pool, from).await?;
+    Ok(indices.len())
+}
+
+/// 同步完成後重算最近幾個交易日，並補齊上次計算之後缺少的日期
+pub async fn on_sync_success(pool: &SqlitePool) -> Result<(), String> {
+    let latest: Option<String> = sqlx::query_scalar("SELECT MAX(t) FROM industry_index")
+        .fetch_one(pool)
+        .await
+        .map_err(|e| format!("[Industry] load latest error: {}", e))?;
+    let recent: Option<String> = sqlx::query_scalar(
+        "SELECT MIN(t) FROM (SELECT DISTINCT t FROM daily_deal ORDER BY t DESC LIMIT $1)",
+    )
+    .bind(RECENT_DAYS)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| format!("[Industry] load dates error: {}", e))?;
+    let from = match (latest, recent) {
+        (Some(latest), Some(recent)) => latest.min(recent),
+        _ => String::new(),
+    };
+    let rows = compute(pool, &from).await?;
+    log::info!("[Industry] computed {} group rows from {}", rows, from);
+    Ok(())
+}
+
+/// 某交易日 (YYYYMMDD，未指定時為最新) 各產業的強弱，依相對強弱由強到弱排序
+#[tauri::command]
+pub async fn get_industry_strength(
+    db: State<'_, Db>,
+    date: Option<String>,
+) -> Result<Vec<IndustryIndex>, String> {
+    sqlx::query_as(
+        "SELECT * FROM industry_index
+         WHERE t = COALESCE($1, (SELECT MAX(t) FROM industry_index))
+         ORDER BY relative_strength DESC NULLS LAST, industry_group",
+    )
+    .bind(date)
+    .fetch_all(&db.0)
+    .await
+    .map_err(|e| format!("[Industry] query error: {}", e))
+}
+
+/// 單一產業 (或 `全市場`) 的指數走勢，供類股輪動圖使用
+#[tauri::command]
+pub async fn get_industry_index(
+    db: State<'_, Db>,
+    industry_group: String,
+    from: Option<String>,
+    to: Option<String>,
+) -> Result<Vec<IndustryIndex>, String> {
+    sqlx::query_as(
+        "SELECT * FROM industry_index
+         WHERE industry_group = $1 AND ($2 IS NULL OR t >= $2) AND ($3 IS NULL OR t <= $3)
+         ORDER BY t",
+    )
+    .bind(industry_group)
+    .bind(from)
+    .bind(to)
+    .fetch_all(&db.0)
+    .await
+    .map_err(|e| format!("[Industry] query error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(i: usize) -> String {
+        format!("{:08}", 20240101 + i)
+    }
+
+    /// (產業, 第幾個交易日, 等權報酬, 市值加權報酬)
+    fn returns(rows: &[(&str, usize, f64, f64)]) -> Vec<GroupReturn> {
+        let mut rows: Vec<GroupReturn> = rows
+            .iter()
+            .map(|(group, i, equal, cap)| GroupReturn {
+                t: day(*i),
+                industry_group: group.to_string(),
+                members: 1,
+                equal_return: *equal,
+                cap_return: *cap,
+            })
+            .collect();
+        rows.sort_by(|a, b| a.t.cmp(&b.t));
+        rows
+    }
+
+    /// 大盤每日持平，`groups` 的產業每日以固定市值加權報酬上漲
+    fn sessions(
+        days: usize,
+        groups: &[(&'static str, f64)],
+    ) -> Vec<(&'static str, usize, f64, f64)> {
+        let mut rows = Vec::new();
+        for i in 0..days {
+            rows.push((MARKET_GROUP, i, 0.0, 0.0));
+            for (group, cap) in groups {
+                rows.push((*group, i, 0.0, *cap));
+            }
+        }
+        rows
+    }
+
+    fn find<'a>(rows: &'a [IndustryIndex], group: &str, i: usize) -> &'a IndustryIndex {
+        let t = day(i);
+        rows.iter()
+            .find(|row| row.industry_group == group && row.t == t)
+            .unwrap()
+    }
+
+    fn close(actual: Option<f64>, expected: Option<f64>) -> bool {
+        match (actual, expected) {
+            (Some(a), Some(e)) => (a - e).abs() < 1e-9,
+            (a, e) => a == e,
+        }
+    }
+
+    type Expected = (
+        &'static str,
+        usize,
+        Option<(f64, f64)>,
+        Option<f64>,
+        Option<f64>,
+    );
+
+    struct Case {
+        name: &'static str,
+        returns: Vec<(&'static str, usize, f64, f64)>,
+        history: Vec<(&'static str, usize, f64)>,
+        /// (產業, 第幾個交易日, 等權指數, 市值加權指數, 相對強弱, 百分位)；指數為 None 時不檢查
+        expected: Vec<Expected>,
+    }
+
+    #[test]
+    fn chains_indices_and_relative_strength() {
+        let cases = vec![
+            Case {
+                name: "等權與市值加權分別串接",
+                returns: vec![("水泥", 0, 10.0, 5.0), ("水泥", 1, -10.0, 0.0)],
+                history: vec![],
+                expected: vec![
+                    ("水泥", 0, Some((110.0, 105.0)), None, None),
+                    ("水泥", 1, Some((99.0, 105.0)), None, None),
+                ],
+            },
+            Case {
+                name: "接續既有指數",
+                returns: vec![("水泥", 1, 10.0, 10.0)],
+                history: vec![("水泥", 0, 200.0)],
+                expected: vec![("水泥", 1, Some((220.0, 220.0)), None, None)],
+            },
+            Case {
+                name: "滿 20 個交易日才有相對強弱，並依強弱給百分位",
+                returns: sessions(21, &[("半導體", 1.0), ("航運", -1.0), ("水泥", 0.0)]),
+                history: vec![],
+                expected: vec![
+                    ("半導體", 19, None, None, None),
+                    (
+                        "半導體",
+                        20,
+                        None,
+                        Some(1.01f64.powi(20) * 100.0),
+                        Some(100.0),
+                    ),
+                    ("航運", 20, None, Some(0.99f64.powi(20) * 100.0), Some(0.0)),
+                    ("水泥", 20, None, Some(100.0), Some(50.0)),
+                    (MARKET_GROUP, 20, None, Some(100.0), None),
+                ],
+            },
+            Case {
+                name: "產業中間沒有成交時以大盤交易日回推並沿用指數",
+                returns: {
+                    let mut rows = sessions(21, &[]);
+                    rows.extend([("生技", 0, 0.0, 10.0), ("生技", 20, 0.0, 10.0)]);
+                    rows
+                },
+                history: vec![],
+                expected: vec![("生技", 20, Some((100.0, 121.0)), Some(110.0), Some(0.0))],
+            },
+        ];
+
+        for case in cases {
+            let mut history: HashMap<String, Vec<IndustryIndex>> = HashMap::new();
+            for (group, i, index) in &case.history {
+                history
+                    .entry(group.to_string())
+                    .or_default()
+                    .push(IndustryIndex {
+                        industry_group: group.to_string(),
+                        t: day(*i),
+                        members: 1,
+                        equal_return: 0.0,
+                        cap_return: 0.0,
+                        equal_index: *index,
+                        cap_index: *index,
+                        relative_strength: None,
+                        rs_percentile: None,
+                    });
+            }
+            let rows = chain(returns(&case.returns), history);
+            for (group, i, indices, rs, percentile) in case.expected {
+                let row = find(&rows, group, i);
+                if let Some((equal, cap)) = indices {
+                    assert!(
+                        close(Some(row.equal_index), Some(equal)),
+                        "{}: equal {}",
+                        case.name,
+                        row.equal_index
+                    );
+                    assert!(
+                        close(Some(row.cap_index), Some(cap)),
+                        "{}: cap {}",
+                        case.name,
+                        row.cap_index
+                    );
+                }
+                assert!(
+                    close(row.relative_strength, rs),
+                    "{}: rs {:?}",
+                    case.name,
+                    row.relative_strength
+                );
+                assert!(
+                    close(row.rs_percentile, percentile),
+                    "{}: percentile {:?}",
+                    case.name,
+                    row.rs_percentile
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn percent_rank_matches_sql() {
+        for (values, value, expected) in [
+            (vec![], 1.0, 0.0),
+            (vec![5.0], 5.0, 0.0),
+            (vec![1.0, 2.0, 3.0], 1.0, 0.0),
+            (vec![1.0, 2.0, 3.0], 3.0, 100.0),
+            (vec![1.0, 2.0, 2.0, 3.0], 2.0, 100.0 / 3.0),
+        ] {
+            assert!((percent_rank(&values, value) - expected).abs() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,274 @@
+use chrono::{Duration, NaiveDate};
+use serde::Serialize;
+use sqlx::{QueryBuilder, Sqlite, SqlitePool};
+use tauri::State;
+
+use crate::adjustment::PriceMode;
+use crate::calendar;
+use crate::incremental;
+use crate::settings;
+use crate::sqlite::connection::Db;
+use crate::types::{Bar, Timeframe};
+use crate::watchlist;
+use crate::yahoo;
+
+/// store.json 中 1 分鐘 K 線的保留天數
+pub const RETENTION_KEY: &str = "minuteRetentionDays";
+pub const DEFAULT_RETENTION_DAYS: i64 = 30;
+
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct MinuteReport {
+    pub stocks: usize,
+    pub minutes: usize,
+    pub failed: usize,
+    /// 超過保留天數而刪除的筆數
+    pub pruned: u64,
+}
+
+pub fn retention_days(app: &tauri::AppHandle) -> i64 {
+    settings::get(app, RETENTION_KEY).unwrap_or(DEFAULT_RETENTION_DAYS)
+}
+
+/// 以整點分組合併為小時 K 線，與 Yahoo 60m 相同以開始時間為 ts (13:30 收盤競價併入 1300)
+pub fn hourly_bars(minutes: &[Bar]) -> Vec<Bar> {
+    let mut sorted: Vec<&Bar> = minutes.iter().filter(|bar| bar.t.len() >= 10).collect();
+    sorted.sort_by(|a, b| a.t.cmp(&b.t));
+
+    let mut hours: Vec<Bar> = Vec::new();
+    for bar in sorted {
+        let ts = format!("{}00", &bar.t[..10]);
+        match hours.last_mut() {
+            Some(hour) if hour.t == ts => {
+                hour.h = hour.h.max(bar.h);
+                hour.l = hour.l.min(bar.l);
+                hour.c = bar.c;
+                hour.v += bar.v;
+            }
+            _ => hours.push(Bar {
+                t: ts,
+                ..bar.clone()
+            }),
+        }
+    }
+    hours
+}
+
+async fn save_minutes(pool: &SqlitePool, stock_id: &str, bars: &[Bar]) -> Result<(), String> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("[Intraday] begin error: {}", e))?;
+    for chunk in bars.chunks(1000) {
+        let mut builder: QueryBuilder<Sqlite> =
+            QueryBuilder::new("INSERT OR REPLACE INTO minute_deal (stock_id, ts, c, o, h, l, v) ");
+        builder.push_values(chunk, |mut row, bar| {
+            row.push_bind(stock_id)
+                .push_bind(&bar.t)
+                .push_bind(bar.c)
+                .push_bind(bar.o)
+                .push_bind(bar.h)
+                .push_bind(bar.l)
+                .push_bind(bar.v);
+        });
+        builder
+            .build()
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("[Intraday] save minutes error: {}", e))?;
+    }
+    tx.commit()
+        .await
+        .map_err(|e| format!("[Intraday] commit error: {}", e))
+}
+
+/// 某日 (YYYYMMDD) 的 1 分鐘 K 線，依時間排序
+pub async fn load_minutes(
+    pool: &SqlitePool,
+    stock_id: &str,
+    day: &str,
+) -> Result<Vec<Bar>, String> {
+    let rows: Vec<(String, f64, f64, f64, f64, i64)> = sqlx::query_as(
+        "SELECT ts, CAST(o AS REAL), CAST(h AS REAL), CAST(l AS REAL), CAST(c AS REAL), CAST(v AS INTEGER)
+         FROM minute_deal
+         WHERE stock_id = $1 AND ts >= $2 AND ts < $3
+         ORDER BY ts",
+    )
+    .bind(stock_id)
+    .bind(format!("{}0000", day))
+    .bind(format!("{}2400", day))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("[Intraday] load minutes error: {}", e))?;
+    Ok(rows
+        .into_iter()
+        .map(|(t, o, h, l, c, v)| Bar { t, o, h, l, c, v })
+        .collect())
+}
+
+/// 以本地保存的 1 分鐘 K 線重建某日的小時 K 線並更新小時指標
+pub async fn rebuild_hourly(pool: &SqlitePool, stock_id: &str, day: &str) -> Result<usize, String> {
+    let hours = hourly_bars(&load_minutes(pool, stock_id, day).await?);
+    // 小時線不做還原
+    incremental::append(pool, Timeframe::Hourly, stock_id, &hours, PriceMode::Raw).await?;
+    Ok(hours.len())
+}
+
+/// 刪除超過保留天數的 1 分鐘 K 線
+pub async fn prune(
+    pool: &SqlitePool,
+    today: NaiveDate,
+    retention_days: i64,
+) -> Result<u64, String> {
+    let cutoff = calendar::format_day(today - Duration::days(retention_days.max(1)));
+    sqlx::query("DELETE FROM minute_deal WHERE ts < $1")
+        .bind(format!("{}0000", cutoff))
+        .execute(pool)
+        .await
+        .map(|result| result.rows_affected())
+        .map_err(|e| format!("[Intraday] prune error: {}", e))
+}
+
+/// 下載當日分時資料，寫入 `minute_deal` 後重建涉及日期的小時 K 線
+pub async fn sync_stock(pool: &SqlitePool, stock_id: &str) -> Result<usize, String> {
+    let bars: Vec<Bar> = yahoo::fetch_minutes(stock_id)
+        .await?
+        .into_iter()
+        .filter(Bar::is_valid)
+        .collect();
+    save_minutes(pool, stock_id, &bars).await?;
+    let mut days: Vec<&str> = bars.iter().map(|bar| &bar.t[..8]).collect();
+    days.dedup();
+    for day in days {
+        rebuild_hourly(pool, stock_id, day).await?;
+    }
+    Ok(bars.len())
+}
+
+pub async fn sync(
+    pool: &SqlitePool,
+    stock_ids: &[String],
+    retention_days: i64,
+) -> Result<MinuteReport, String> {
+    let mut report = MinuteReport {
+        stocks: stock_ids.len(),
+        ..Default::default()
+    };
+    for stock_id in stock_ids {
+        match sync_stock(pool, stock_id).await {
+            Ok(count) => report.minutes += count,
+            Err(e) => {
+                log::warn!("[Intraday] {} failed: {}", stock_id, e);
+                report.failed += 1;
+            }
+        }
+    }
+    report.pruned = prune(pool, chrono::Local::now().date_naive(), retention_days).await?;
+    Ok(report)
+}
+
+/// 同步完成後只下載自選股的分時資料，避免對全市場送出額外請求
+pub async fn on_sync_success(app: &tauri::AppHandle, pool: &SqlitePool) -> Result<(), String> {
+    let stock_ids = watchlist::all_stock_ids(pool).await?;
+    let report = sync(pool, &stock_ids, retention_days(app)).await?;
+    log::info!(
+        "[Intraday] {} stocks, {} minutes, {} failed, {} pruned",
+        report.stocks,
+        report.minutes,
+        report.failed,
+        report.pruned
+    );
+    Ok(())
+}
+
+/// `stock_ids` 未指定時為全部自選股
+#[tauri::command]
+pub async fn sync_minutes(
+    app: tauri::AppHandle,
+    db: State<'_, Db>,
+    stock_ids: Option<Vec<String>>,
+) -> Result<MinuteReport, String> {
+    let stock_ids = match stock_ids {
+        Some(ids) => ids,
+        None => watchlist::all_stock_ids(&db.0).await?,
+    };
+    sync(&db.0, &stock_ids, retention_days(&app)).await
+}
+
+/// `date` 為 YYYYMMDD
+#[tauri::command]
+pub async fn get_minute_bars(
+    db: State<'_, Db>,
+    stock_id: String,
+    date: String,
+) -> Result<Vec<Bar>, String> {
+    load_minutes(&db.0, &stock_id, &date).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sqlite::connection;
+
+    fn fixture(name: &str) -> String {
+        let path = format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name);
+        std::fs::read_to_string(path).unwrap()
+    }
+
+    #[test]
+    fn aggregates_minutes_into_hours() {
+        let minutes = yahoo::parse_tick(&fixture("yahoo_tick.json")).unwrap();
+        let mut shuffled = minutes.clone();
+        shuffled.reverse();
+        let hours = hourly_bars(&shuffled);
+        let summary: Vec<(&str, f64, f64, f64, f64, i64)> = hours
+            .iter()
+            .map(|bar| (bar.t.as_str(), bar.o, bar.h, bar.l, bar.c, bar.v))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("202410070900", 1000.0, 1005.0, 995.0, 995.0, 160),
+                ("202410071000", 998.0, 998.0, 998.0, 998.0, 25),
+                ("202410071200", 1010.0, 1010.0, 1010.0, 1010.0, 5),
+                // 13:30 收盤競價併入 1300
+                ("202410071300", 1012.0, 1015.0, 1008.0, 1015.0, 312),
+            ]
+        );
+    }
+
+    #[test]
+    fn prunes_minutes_older_than_retention() {
+        tauri::async_runtime::block_on(async {
+            let pool = connection::memory().await;
+            connection::migrate(&pool, ..).await;
+            let bars: Vec<Bar> = ["202409060900", "202409070900", "202410071330"]
+                .iter()
+                .map(|t| Bar {
+                    t: t.to_string(),
+                    o: 1.0,
+                    h: 1.0,
+                    l: 1.0,
+                    c: 1.0,
+                    v: 1,
+                })
+                .collect();
+            save_minutes(&pool, "2330", &bars).await.unwrap();
+
+            let today = calendar::parse_day("20241007").unwrap();
+            // 保留 30 天：2024-09-07 當天 (含) 以後的資料保留
+            assert_eq!(prune(&pool, today, 30).await.unwrap(), 1);
+            let left: Vec<String> = sqlx::query_scalar("SELECT ts FROM minute_deal ORDER BY ts")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+            assert_eq!(left, vec!["202409070900", "202410071330"]);
+
+            // 保留天數至少 1 天，不會刪到當日資料
+            assert_eq!(prune(&pool, today, 0).await.unwrap(), 1);
+            assert_eq!(
+                load_minutes(&pool, "2330", "20241007").await.unwrap().len(),
+                1
+            );
+        });
+    }
+}
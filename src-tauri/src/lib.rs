@@ -1,4 +1,33 @@
+mod adjustment;
+mod alerts;
+mod alignment;
+mod api;
+mod backtest;
+mod breadth;
+mod calendar;
+mod chips;
+mod cli;
+mod columnar;
+mod data_quality;
+mod export;
+mod fundamentals;
+mod import;
+mod incremental;
+mod indicators;
+mod industry;
+mod intraday;
+mod listing;
+mod portfolio;
+mod post_sync;
+mod screen;
+mod screen_cache;
+mod settings;
+mod signals;
 mod sqlite;
+mod types;
+mod universe;
+mod watchlist;
+mod yahoo;
 use std::fs;
 use tauri::Manager;
 use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
@@ -18,10 +47,7 @@ async fn update(app: tauri::AppHandle) -> tauri_plugin_updater::Result<()> {
         .build()
         .map_err(|e| {
             println!("Failed to build store: {}", e);
-            tauri_plugin_updater::Error::Io(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                e.to_string(),
-            ))
+            tauri_plugin_updater::Error::Io(std::io::Error::other(e.to_string()))
         })?;
 
     let auto_update = store.get("autoUpdate").unwrap_or(Some(false).into());
@@ -35,7 +61,7 @@ async fn update(app: tauri::AppHandle) -> tauri_plugin_updater::Result<()> {
 
         let ans = app
             .dialog()
-            .message(&format!("Update available: v{}", version))
+            .message(format!("Update available: v{}", version))
             .kind(MessageDialogKind::Info)
             .buttons(MessageDialogButtons::OkCancel)
             .blocking_show();
@@ -67,7 +93,7 @@ async fn update(app: tauri::AppHandle) -> tauri_plugin_updater::Result<()> {
             {
                 println!("Download Update failed message: {}", e);
                 app.dialog()
-                    .message(&format!("Download Update failed: {}", e))
+                    .message(format!("Download Update failed: {}", e))
                     .kind(MessageDialogKind::Warning)
                     .buttons(MessageDialogButtons::Ok)
                     .blocking_show();
@@ -84,10 +110,7 @@ async fn update(app: tauri::AppHandle) -> tauri_plugin_updater::Result<()> {
                 if restart_ans {
                     let exe_path = std::env::current_exe()?;
                     let app_dir = app.path().app_data_dir().map_err(|arg0: tauri::Error| {
-                        tauri_plugin_updater::Error::Io(std::io::Error::new(
-                            std::io::ErrorKind::Other,
-                            arg0.to_string(),
-                        ))
+                        tauri_plugin_updater::Error::Io(std::io::Error::other(arg0.to_string()))
                     })?;
 
                     println!(
@@ -121,6 +144,11 @@ fn get_db_size(app_handle: tauri::AppHandle) -> Result<(u64, String), String> {
     Ok((size, db_path.to_string_lossy().to_string()))
 }
 
+/// 帶子命令啟動時以 CLI 模式執行，回傳結束碼；否則回傳 None 並由 `run` 開啟視窗
+pub fn run_cli() -> Option<i32> {
+    cli::run(std::env::args().skip(1).collect())
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     let result = tauri::Builder::default()
@@ -146,13 +174,68 @@ pub fn run() {
                 .build(),
         )
         .setup(|app| {
+            let db_path = sqlite::connection::db_path(app.handle())?;
+            let pool = tauri::async_runtime::block_on(sqlite::connection::open(&db_path))?;
+            app.manage(sqlite::connection::Db(pool));
+            post_sync::register(app.handle());
+            api::start(app.handle());
+
             let handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
                 update(handle).await.unwrap();
             });
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![greet, get_db_size])
+        .invoke_handler(tauri::generate_handler![
+            greet,
+            get_db_size,
+            data_quality::validate_data,
+            adjustment::import_adjustments_csv,
+            adjustment::fetch_twse_adjustments,
+            adjustment::get_adjusted_deals,
+            adjustment::get_price_mode,
+            adjustment::set_price_mode,
+            incremental::append_bars,
+            alerts::get_alert_history,
+            watchlist::list_watchlists,
+            watchlist::create_watchlist,
+            watchlist::rename_watchlist,
+            watchlist::delete_watchlist,
+            watchlist::reorder_watchlists,
+            watchlist::get_watchlist_items,
+            watchlist::upsert_watchlist_item,
+            watchlist::remove_watchlist_item,
+            watchlist::reorder_watchlist_items,
+            watchlist::set_watchlist_tags,
+            watchlist::reconcile_watchlist,
+            portfolio::list_portfolios,
+            portfolio::save_portfolio,
+            portfolio::delete_portfolio,
+            portfolio::list_trades,
+            portfolio::add_trade,
+            portfolio::delete_trade,
+            portfolio::get_portfolio_summary,
+            portfolio::get_nav_history,
+            import::preview_import,
+            import::import_file,
+            export::export_results,
+            columnar::export_history,
+            listing::sync_listings,
+            universe::get_universe,
+            universe::get_stock_history,
+            screen::save_prompt_template,
+            api::get_api_info,
+            api::reset_api_token,
+            fundamentals::scrape_fundamentals,
+            chips::sync_chips,
+            intraday::sync_minutes,
+            intraday::get_minute_bars,
+            breadth::get_market_breadth,
+            industry::get_industry_strength,
+            industry::get_industry_index,
+            alignment::align_timeframes,
+            screen_cache::run_screen
+        ])
         .run(tauri::generate_context!());
 
     if let Err(e) = result {
@@ -0,0 +1,311 @@
+use scraper::{ElementRef, Html, Selector};
+use serde::{Deserialize, Serialize};
+use sqlx::{QueryBuilder, Sqlite, SqlitePool};
+use tauri::State;
+use tauri_plugin_http::reqwest;
+
+use crate::sqlite::connection::Db;
+use crate::sqlite::writer;
+use crate::universe;
+
+/// 對應前端 `QueryStockType`：2 為上市、4 為上櫃
+const MARKETS: &[(&str, u8)] = &[("上市", 2), ("上櫃", 4)];
+/// 與前端 `MENU_SECURITY_TYPES` 相同，只有這些類別會同步 K 線
+pub const SYNCED_TYPES_FILTER: &str = "security_type IN ('stock', 'tdr')";
+/// 代號與名稱之間以全形空白分隔
+const NAME_SEPARATOR: char = '\u{3000}';
+
+fn listing_url(mode: u8) -> String {
+    format!(
+        "https://isin.twse.com.tw/isin/C_public.jsp?strMode={}",
+        mode
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SecurityType {
+    Stock,
+    Etf,
+    Etn,
+    Warrant,
+    /// 臺灣存託憑證
+    Tdr,
+    /// 特別股
+    Preferred,
+    /// 不動產投資信託等受益證券
+    Reit,
+    Other,
+}
+
+impl SecurityType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SecurityType::Stock => "stock",
+            SecurityType::Etf => "etf",
+            SecurityType::Etn => "etn",
+            SecurityType::Warrant => "warrant",
+            SecurityType::Tdr => "tdr",
+            SecurityType::Preferred => "preferred",
+            SecurityType::Reit => "reit",
+            SecurityType::Other => "other",
+        }
+    }
+
+    /// 依表格中的分類標題列判斷，例如「股票」、「上市認購(售)權證」、「ETF」
+    fn from_section(title: &str) -> Option<Self> {
+        let title = title.trim();
+        Some(if title.contains("權證") {
+            SecurityType::Warrant
+        } else if title.contains("ETN") {
+            SecurityType::Etn
+        } else if title.contains("ETF") {
+            SecurityType::Etf
+        } else if title.contains("存託憑證") || title.contains("TDR") {
+            SecurityType::Tdr
+        } else if title.contains("特別股") {
+            SecurityType::Preferred
+        } else if title.contains("受益證券") || title.contains("不動產投資信託") {
+            SecurityType::Reit
+        } else if title.contains("股票") || title.contains("創新板") {
+            SecurityType::Stock
+        } else {
+            return None;
+        })
+    }
+
+    /// 分類標題無法辨識時，改以 ISO 10962 CFI 代碼判斷
+    fn from_cfi(cfi: &str) -> Self {
+        match cfi.get(0..2) {
+            Some("ES") => SecurityType::Stock,
+            Some("EP") => SecurityType::Preferred,
+            Some("ED") => SecurityType::Tdr,
+            Some("CE") => SecurityType::Etf,
+            Some("RW") => SecurityType::Warrant,
+            _ => SecurityType::Other,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Listing {
+    pub stock_id: String,
+    pub stock_name: String,
+    /// 上市 / 上櫃
+    pub market_type: String,
+    pub industry_group: String,
+    pub security_type: SecurityType,
+    pub isin: String,
+    /// YYYYMMDD
+    pub listed_at: Option<String>,
+}
+
+fn cell_text(cell: &ElementRef) -> String {
+    cell.text().collect::<String>().trim().to_string()
+}
+
+/// 解析 C_public.jsp 的 HTML：每個分類以一列 `colspan` 標題開始，其後為該分類的證券
+pub fn parse_listings(html: &str) -> Vec<Listing> {
+    let document = Html::parse_document(html);
+    let (Ok(row_selector), Ok(cell_selector)) = (Selector::parse("tr"), Selector::parse("td"))
+    else {
+        return Vec::new();
+    };
+
+    let mut section = None;
+    let mut listings = Vec::new();
+    for row in document.select(&row_selector) {
+        let cells: Vec<String> = row.select(&cell_selector).map(|c| cell_text(&c)).collect();
+        if cells.len() == 1 {
+            section = SecurityType::from_section(&cells[0]);
+            continue;
+        }
+        if cells.len() < 6 {
+            continue;
+        }
+        // 表頭列 (有價證券代號及名稱) 沒有全形空白，自然略過
+        let Some((stock_id, stock_name)) = cells[0].split_once(NAME_SEPARATOR) else {
+            continue;
+        };
+        let stock_id = stock_id.trim();
+        if stock_id.is_empty() || !stock_id.chars().all(|c| c.is_ascii_alphanumeric()) {
+            continue;
+        }
+        listings.push(Listing {
+            stock_id: stock_id.to_string(),
+            stock_name: stock_name.trim().to_string(),
+            market_type: cells[3].clone(),
+            industry_group: cells[4].clone(),
+            security_type: section.unwrap_or_else(|| SecurityType::from_cfi(&cells[5])),
+            isin: cells[1].clone(),
+            listed_at: Some(cells[2].replace('/', "")).filter(|t| t.len() == 8),
+        });
+    }
+    listings
+}
+
+/// 網頁為 Big5 (MS950) 編碼
+pub fn decode_big5(bytes: &[u8]) -> String {
+    let (text, _, had_errors) = encoding_rs::BIG5.decode(bytes);
+    if had_errors {
+        log::warn!("[Listing] invalid Big5 sequences replaced");
+    }
+    text.into_owned()
+}
+
+async fn fetch_market(market: &str, mode: u8) -> Result<Vec<Listing>, String> {
+    let response = reqwest::get(listing_url(mode))
+        .await
+        .map_err(|e| format!("[Listing] {} request error: {}", market, e))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "[Listing] {} fetch failed: {}",
+            market,
+            response.status()
+        ));
+    }
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| format!("[Listing] {} read error: {}", market, e))?;
+    let listings = parse_listings(&decode_big5(&bytes));
+    // 網站維護時會回傳空白頁，不可當成全部下市
+    if listings.is_empty() {
+        return Err(format!("[Listing] {} returned no listings", market));
+    }
+    Ok(listings)
+}
+
+/// 下載上市與上櫃清單
+pub async fn fetch_listings() -> Result<Vec<Listing>, String> {
+    let mut listings = Vec::new();
+    for (market, mode) in MARKETS {
+        listings.extend(fetch_market(market, *mode).await?);
+    }
+    Ok(listings)
+}
+
+/// 寫入 `stock`，保留同步時補上的 `issued_shares`
+pub async fn save_listings(pool: &SqlitePool, listings: &[Listing]) -> Result<(), String> {
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("[Listing] begin error: {}", e))?;
+    // SQLite 單一語句最多 32766 個參數
+    for chunk in listings.chunks(1000) {
+        let mut builder: QueryBuilder<Sqlite> = QueryBuilder::new(
+            "INSERT INTO stock (stock_id, stock_name, industry_group, market_type, security_type) ",
+        );
+        builder.push_values(chunk, |mut row, listing| {
+            row.push_bind(&listing.stock_id)
+                .push_bind(&listing.stock_name)
+                .push_bind(&listing.industry_group)
+                .push_bind(&listing.market_type)
+                .push_bind(listing.security_type.as_str());
+        });
+        builder.push(
+            " ON CONFLICT(stock_id) DO UPDATE SET
+                stock_name = excluded.stock_name,
+                industry_group = excluded.industry_group,
+                market_type = excluded.market_type,
+                security_type = excluded.security_type",
+        );
+        builder
+            .build()
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("[Listing] save error: {}", e))?;
+    }
+    writer::bump_versions(&mut *tx, &["stock"]).await?;
+    tx.commit()
+        .await
+        .map_err(|e| format!("[Listing] commit error: {}", e))
+}
+
+/// 更新股票清單與 `stock_history`，回傳全部上市櫃證券，由前端決定放入選單的類別
+#[tauri::command]
+pub async fn sync_listings(db: State<'_, Db>) -> Result<Vec<Listing>, String> {
+    let listings = fetch_listings().await?;
+    save_listings(&db.0, &listings).await?;
+    let changes =
+        universe::record_listings(&db.0, &listings, chrono::Local::now().date_naive()).await?;
+    log::info!(
+        "[Listing] saved {} listings, {} listed, {} changed, {} delisted",
+        listings.len(),
+        changes.listed,
+        changes.changed,
+        changes.delisted
+    );
+    Ok(listings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> Vec<Listing> {
+        let path = format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name);
+        let bytes = std::fs::read(&path).unwrap();
+        parse_listings(&decode_big5(&bytes))
+    }
+
+    fn find<'a>(listings: &'a [Listing], stock_id: &str) -> &'a Listing {
+        listings.iter().find(|l| l.stock_id == stock_id).unwrap()
+    }
+
+    #[test]
+    fn parses_twse_sections() {
+        let listings = fixture("isin_twse.html");
+        assert_eq!(listings.len(), 9);
+
+        let tsmc = find(&listings, "2330");
+        assert_eq!(tsmc.stock_name, "台積電");
+        assert_eq!(tsmc.market_type, "上市");
+        assert_eq!(tsmc.industry_group, "半導體業");
+        assert_eq!(tsmc.security_type, SecurityType::Stock);
+        assert_eq!(tsmc.isin, "TW0002330008");
+        assert_eq!(tsmc.listed_at.as_deref(), Some("19940905"));
+
+        assert_eq!(find(&listings, "0050").security_type, SecurityType::Etf);
+        assert_eq!(
+            find(&listings, "030001").security_type,
+            SecurityType::Warrant
+        );
+        assert_eq!(find(&listings, "9103").security_type, SecurityType::Tdr);
+        assert_eq!(
+            find(&listings, "2881A").security_type,
+            SecurityType::Preferred
+        );
+        assert_eq!(find(&listings, "01001T").security_type, SecurityType::Reit);
+        assert_eq!(find(&listings, "020000").security_type, SecurityType::Etn);
+    }
+
+    #[test]
+    fn parses_tpex_and_falls_back_to_cfi() {
+        let listings = fixture("isin_tpex.html");
+        assert_eq!(listings.len(), 4);
+
+        let stock = find(&listings, "6488");
+        assert_eq!(stock.stock_name, "環球晶");
+        assert_eq!(stock.market_type, "上櫃");
+        assert_eq!(stock.security_type, SecurityType::Stock);
+        assert_eq!(find(&listings, "006201").security_type, SecurityType::Etf);
+        assert_eq!(
+            find(&listings, "70000P").security_type,
+            SecurityType::Warrant
+        );
+        // 未知的分類標題以 CFI 代碼判斷
+        assert_eq!(
+            find(&listings, "5468A").security_type,
+            SecurityType::Preferred
+        );
+    }
+
+    #[test]
+    fn skips_header_and_blank_pages() {
+        assert!(parse_listings("<html><body></body></html>").is_empty());
+        let header = "<table><tr><td>有價證券代號及名稱</td><td>ISIN</td><td>上市日</td>\
+                      <td>市場別</td><td>產業別</td><td>CFICode</td><td>備註</td></tr></table>";
+        assert!(parse_listings(header).is_empty());
+    }
+}
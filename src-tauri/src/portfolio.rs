@@ -0,0 +1,684 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use tauri::State;
+
+use crate::sqlite::connection::Db;
+
+/// 券商手續費率 0.1425%，實收依折扣計算，未滿最低手續費以最低計
+const BROKER_FEE_RATE: f64 = 0.001425;
+/// 證券交易稅：股票 0.3%、ETF / ETN 0.1%、現股當沖 0.15%，債券 ETF 免徵，僅賣出時課徵
+const STOCK_TAX_RATE: f64 = 0.003;
+const ETF_TAX_RATE: f64 = 0.001;
+const DAY_TRADE_TAX_RATE: f64 = 0.0015;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(rename_all = "lowercase")]
+pub enum TradeKind {
+    Buy,
+    Sell,
+    /// 現金股利 (amount) 或股票股利 (quantity，成本為 0)
+    Dividend,
+    /// 其他費用，例如匯費
+    Fee,
+    /// 其他稅負，例如二代健保補充保費
+    Tax,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct Portfolio {
+    pub id: i64,
+    pub name: String,
+    /// 初始資金
+    pub cash: f64,
+    /// 手續費折扣，例如 0.6 為六折
+    pub fee_discount: f64,
+    pub min_fee: f64,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct Trade {
+    pub id: i64,
+    pub portfolio_id: i64,
+    pub stock_id: Option<String>,
+    pub t: String,
+    pub kind: TradeKind,
+    pub quantity: i64,
+    pub price: f64,
+    pub amount: f64,
+    pub fee: f64,
+    pub tax: f64,
+    pub day_trade: bool,
+    pub note: Option<String>,
+}
+
+/// 新增交易，fee / tax 未帶入時依台股規則計算
+#[derive(Debug, Clone, Deserialize)]
+pub struct TradeInput {
+    pub portfolio_id: i64,
+    pub stock_id: Option<String>,
+    pub t: String,
+    pub kind: TradeKind,
+    #[serde(default)]
+    pub quantity: i64,
+    #[serde(default)]
+    pub price: f64,
+    #[serde(default)]
+    pub amount: f64,
+    pub fee: Option<f64>,
+    pub tax: Option<f64>,
+    #[serde(default)]
+    pub day_trade: bool,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Position {
+    pub stock_id: String,
+    pub stock_name: Option<String>,
+    pub quantity: i64,
+    /// 含買進手續費的加權平均成本
+    pub avg_cost: f64,
+    pub cost_basis: f64,
+    pub last_close: Option<f64>,
+    pub market_value: f64,
+    pub unrealized_pnl: f64,
+    pub realized_pnl: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PortfolioSummary {
+    pub cash: f64,
+    pub market_value: f64,
+    pub nav: f64,
+    pub realized_pnl: f64,
+    pub unrealized_pnl: f64,
+    pub fees: f64,
+    pub taxes: f64,
+    pub positions: Vec<Position>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NavPoint {
+    pub t: String,
+    pub cash: f64,
+    pub market_value: f64,
+    pub nav: f64,
+}
+
+pub fn broker_fee(value: f64, discount: f64, min_fee: f64) -> f64 {
+    if value <= 0.0 {
+        return 0.0;
+    }
+    (value * BROKER_FEE_RATE * discount).floor().max(min_fee)
+}
+
+/// `security_type` 為 `stock.security_type`，債券 ETF 以代號結尾 B 判斷
+pub fn transaction_tax(security_type: &str, stock_id: &str, value: f64, day_trade: bool) -> f64 {
+    if security_type == "etf" && stock_id.ends_with('B') {
+        return 0.0;
+    }
+    let rate = if matches!(security_type, "etf" | "etn") {
+        ETF_TAX_RATE
+    } else if day_trade {
+        DAY_TRADE_TAX_RATE
+    } else {
+        STOCK_TAX_RATE
+    };
+    (value * rate).floor()
+}
+
+#[derive(Debug, Clone, Default)]
+struct Holding {
+    quantity: i64,
+    cost: f64,
+    realized: f64,
+}
+
+/// 依時間順序重播交易，以加權平均成本法計算持股與已實現損益
+#[derive(Debug, Clone, Default)]
+struct Ledger {
+    cash: f64,
+    fees: f64,
+    taxes: f64,
+    /// 未指定股票的股利與費用
+    other_realized: f64,
+    holdings: BTreeMap<String, Holding>,
+}
+
+impl Ledger {
+    fn new(cash: f64) -> Self {
+        Self {
+            cash,
+            ..Default::default()
+        }
+    }
+
+    fn apply(&mut self, trade: &Trade) {
+        let value = trade.quantity as f64 * trade.price;
+        self.fees += trade.fee;
+        self.taxes += trade.tax;
+        let holding = trade
+            .stock_id
+            .as_ref()
+            .map(|stock_id| self.holdings.entry(stock_id.clone()).or_default());
+
+        match trade.kind {
+            TradeKind::Buy => {
+                self.cash -= value + trade.fee + trade.tax;
+                if let Some(holding) = holding {
+                    holding.quantity += trade.quantity;
+                    holding.cost += value + trade.fee + trade.tax;
+                }
+            }
+            TradeKind::Sell => {
+                let proceeds = value - trade.fee - trade.tax;
+                self.cash += proceeds;
+                if let Some(holding) = holding {
+                    let sold = trade.quantity.min(holding.quantity);
+                    if sold <= 0 {
+                        return;
+                    }
+                    let cost = holding.cost * sold as f64 / holding.quantity as f64;
+                    holding.quantity -= sold;
+                    holding.cost -= cost;
+                    // 舊資料可能超賣，只計入有成本對應的部分
+                    holding.realized += proceeds * sold as f64 / trade.quantity as f64 - cost;
+                }
+            }
+            TradeKind::Dividend => {
+                let net = trade.amount - trade.fee - trade.tax;
+                self.cash += net;
+                match holding {
+                    Some(holding) => {
+                        holding.quantity += trade.quantity;
+                        holding.realized += net;
+                    }
+                    None => self.other_realized += net,
+                }
+            }
+            TradeKind::Fee | TradeKind::Tax => {
+                let spent = trade.amount + trade.fee + trade.tax;
+                if trade.kind == TradeKind::Fee {
+                    self.fees += trade.amount;
+                } else {
+                    self.taxes += trade.amount;
+                }
+                self.cash -= spent;
+                match holding {
+                    Some(holding) => holding.realized -= spent,
+                    None => self.other_realized -= spent,
+                }
+            }
+        }
+    }
+
+    fn market_value(&self, closes: &BTreeMap<String, f64>) -> f64 {
+        self.holdings
+            .iter()
+            .map(|(stock_id, holding)| {
+                if holding.quantity == 0 {
+                    return 0.0;
+                }
+                // 尚無收盤價時以成本估值
+                closes
+                    .get(stock_id)
+                    .map_or(holding.cost, |c| holding.quantity as f64 * c)
+            })
+            .sum()
+    }
+}
+
+async fn load_portfolio(pool: &SqlitePool, id: i64) -> Result<Portfolio, String> {
+    sqlx::query_as("SELECT id, name, cash, fee_discount, min_fee FROM portfolio WHERE id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("[Portfolio] load portfolio error: {}", e))?
+        .ok_or_else(|| format!("[Portfolio] portfolio {} not found", id))
+}
+
+async fn load_trades(pool: &SqlitePool, portfolio_id: i64) -> Result<Vec<Trade>, String> {
+    sqlx::query_as(
+        "SELECT id, portfolio_id, stock_id, t, kind, quantity, price, amount, fee, tax, day_trade, note
+         FROM trade WHERE portfolio_id = $1 ORDER BY t, id",
+    )
+    .bind(portfolio_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("[Portfolio] load trades error: {}", e))
+}
+
+async fn replay(pool: &SqlitePool, portfolio_id: i64) -> Result<Ledger, String> {
+    let portfolio = load_portfolio(pool, portfolio_id).await?;
+    let mut ledger = Ledger::new(portfolio.cash);
+    for trade in load_trades(pool, portfolio_id).await? {
+        ledger.apply(&trade);
+    }
+    Ok(ledger)
+}
+
+pub async fn summary(pool: &SqlitePool, portfolio_id: i64) -> Result<PortfolioSummary, String> {
+    let ledger = replay(pool, portfolio_id).await?;
+
+    let mut positions = Vec::new();
+    for (stock_id, holding) in &ledger.holdings {
+        let (stock_name, last_close): (Option<String>, Option<f64>) = sqlx::query_as(
+            "SELECT (SELECT stock_name FROM stock WHERE stock_id = $1),
+                    (SELECT c FROM daily_deal WHERE stock_id = $1 ORDER BY t DESC LIMIT 1)",
+        )
+        .bind(stock_id)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let market_value = match (holding.quantity, last_close) {
+            (0, _) => 0.0,
+            (quantity, Some(close)) => quantity as f64 * close,
+            (_, None) => holding.cost,
+        };
+        positions.push(Position {
+            stock_id: stock_id.clone(),
+            stock_name,
+            quantity: holding.quantity,
+            avg_cost: if holding.quantity > 0 {
+                holding.cost / holding.quantity as f64
+            } else {
+                0.0
+            },
+            cost_basis: holding.cost,
+            last_close,
+            market_value,
+            unrealized_pnl: market_value - holding.cost,
+            realized_pnl: holding.realized,
+        });
+    }
+
+    let market_value: f64 = positions.iter().map(|p| p.market_value).sum();
+    let unrealized_pnl = positions.iter().map(|p| p.unrealized_pnl).sum();
+    let realized_pnl =
+        positions.iter().map(|p| p.realized_pnl).sum::<f64>() + ledger.other_realized;
+    Ok(PortfolioSummary {
+        cash: ledger.cash,
+        market_value,
+        nav: ledger.cash + market_value,
+        realized_pnl,
+        unrealized_pnl,
+        fees: ledger.fees,
+        taxes: ledger.taxes,
+        positions,
+    })
+}
+
+/// 自第一筆交易起每個交易日的淨值，收盤價取當日或之前最近一筆
+pub async fn nav_history(pool: &SqlitePool, portfolio_id: i64) -> Result<Vec<NavPoint>, String> {
+    let portfolio = load_portfolio(pool, portfolio_id).await?;
+    let trades = load_trades(pool, portfolio_id).await?;
+    let Some(first) = trades.first().map(|trade| trade.t.clone()) else {
+        return Ok(Vec::new());
+    };
+
+    let closes: Vec<(String, String, f64)> = sqlx::query_as(
+        "SELECT d.t, d.stock_id, d.c FROM daily_deal d
+         WHERE d.t >= $1 AND d.stock_id IN (SELECT DISTINCT stock_id FROM trade WHERE portfolio_id = $2)
+         ORDER BY d.t",
+    )
+    .bind(&first)
+    .bind(portfolio_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("[Portfolio] load closes error: {}", e))?;
+    let mut by_day: BTreeMap<String, Vec<(String, f64)>> = BTreeMap::new();
+    for (t, stock_id, c) in closes {
+        by_day.entry(t).or_default().push((stock_id, c));
+    }
+    let days: Vec<String> =
+        sqlx::query_scalar("SELECT DISTINCT t FROM daily_deal WHERE t >= $1 ORDER BY t")
+            .bind(&first)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+
+    let mut ledger = Ledger::new(portfolio.cash);
+    let mut last_close: BTreeMap<String, f64> = BTreeMap::new();
+    let mut pending = trades.iter().peekable();
+    let mut history = Vec::with_capacity(days.len());
+    for day in days {
+        while let Some(trade) = pending.next_if(|trade| trade.t <= day) {
+            ledger.apply(trade);
+        }
+        for (stock_id, c) in by_day.remove(&day).unwrap_or_default() {
+            last_close.insert(stock_id, c);
+        }
+        let market_value = ledger.market_value(&last_close);
+        history.push(NavPoint {
+            t: day,
+            cash: ledger.cash,
+            market_value,
+            nav: ledger.cash + market_value,
+        });
+    }
+    Ok(history)
+}
+
+#[tauri::command]
+pub async fn list_portfolios(db: State<'_, Db>) -> Result<Vec<Portfolio>, String> {
+    sqlx::query_as("SELECT id, name, cash, fee_discount, min_fee FROM portfolio ORDER BY id")
+        .fetch_all(&db.0)
+        .await
+        .map_err(|e| format!("[Portfolio] load portfolios error: {}", e))
+}
+
+#[tauri::command]
+pub async fn save_portfolio(
+    db: State<'_, Db>,
+    id: Option<i64>,
+    name: String,
+    cash: f64,
+    fee_discount: Option<f64>,
+    min_fee: Option<f64>,
+) -> Result<i64, String> {
+    let result = sqlx::query(
+        "INSERT INTO portfolio (id, name, cash, fee_discount, min_fee, created_at)
+         VALUES ($1, $2, $3, $4, $5, datetime('now', 'localtime'))
+         ON CONFLICT (id) DO UPDATE SET
+             name = excluded.name,
+             cash = excluded.cash,
+             fee_discount = excluded.fee_discount,
+             min_fee = excluded.min_fee",
+    )
+    .bind(id)
+    .bind(name.trim())
+    .bind(cash)
+    .bind(fee_discount.unwrap_or(1.0))
+    .bind(min_fee.unwrap_or(20.0))
+    .execute(&db.0)
+    .await
+    .map_err(|e| format!("[Portfolio] save portfolio error: {}", e))?;
+    Ok(id.unwrap_or(result.last_insert_rowid()))
+}
+
+#[tauri::command]
+pub async fn delete_portfolio(db: State<'_, Db>, id: i64) -> Result<(), String> {
+    let mut tx = db.0.begin().await.map_err(|e| e.to_string())?;
+    for sql in [
+        "DELETE FROM trade WHERE portfolio_id = $1",
+        "DELETE FROM portfolio WHERE id = $1",
+    ] {
+        sqlx::query(sql)
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("[Portfolio] delete error: {}", e))?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_trades(db: State<'_, Db>, portfolio_id: i64) -> Result<Vec<Trade>, String> {
+    load_trades(&db.0, portfolio_id).await
+}
+
+/// 交易日 (含) 以前的持股數量
+async fn held_quantity(
+    pool: &SqlitePool,
+    portfolio_id: i64,
+    stock_id: &str,
+    t: &str,
+) -> Result<i64, String> {
+    sqlx::query_scalar(
+        "SELECT COALESCE(SUM(CASE kind WHEN 'sell' THEN -quantity WHEN 'buy' THEN quantity WHEN 'dividend' THEN quantity ELSE 0 END), 0)
+         FROM trade WHERE portfolio_id = $1 AND stock_id = $2 AND t <= $3",
+    )
+    .bind(portfolio_id)
+    .bind(stock_id)
+    .bind(t)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| format!("[Portfolio] load holding error: {}", e))
+}
+
+/// 寫入一筆交易，未帶入的手續費與交易稅依帳戶設定計算，賣出數量不可超過當時持股
+pub async fn insert_trade(pool: &SqlitePool, trade: &TradeInput) -> Result<i64, String> {
+    let portfolio = load_portfolio(pool, trade.portfolio_id).await?;
+    let value = trade.quantity as f64 * trade.price;
+    let stock_id = trade.stock_id.as_deref().unwrap_or_default();
+    if trade.kind == TradeKind::Sell {
+        let held = held_quantity(pool, trade.portfolio_id, stock_id, &trade.t).await?;
+        if trade.quantity > held {
+            return Err(format!(
+                "[Portfolio] sell {} {} exceeds holding {} on {}",
+                stock_id, trade.quantity, held, trade.t
+            ));
+        }
+    }
+    let (fee, tax) = match trade.kind {
+        TradeKind::Buy => (
+            trade
+                .fee
+                .unwrap_or_else(|| broker_fee(value, portfolio.fee_discount, portfolio.min_fee)),
+            trade.tax.unwrap_or(0.0),
+        ),
+        TradeKind::Sell => (
+            trade
+                .fee
+                .unwrap_or_else(|| broker_fee(value, portfolio.fee_discount, portfolio.min_fee)),
+            match trade.tax {
+                Some(tax) => tax,
+                None => {
+                    let security_type: Option<String> =
+                        sqlx::query_scalar("SELECT security_type FROM stock WHERE stock_id = $1")
+                            .bind(stock_id)
+                            .fetch_optional(pool)
+                            .await
+                            .map_err(|e| format!("[Portfolio] load security type error: {}", e))?;
+                    transaction_tax(
+                        security_type.as_deref().unwrap_or("stock"),
+                        stock_id,
+                        value,
+                        trade.day_trade,
+                    )
+                }
+            },
+        ),
+        _ => (trade.fee.unwrap_or(0.0), trade.tax.unwrap_or(0.0)),
+    };
+
+    let result = sqlx::query(
+        "INSERT INTO trade (portfolio_id, stock_id, t, kind, quantity, price, amount, fee, tax, day_trade, note, created_at)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, datetime('now', 'localtime'))",
+    )
+    .bind(trade.portfolio_id)
+    .bind(&trade.stock_id)
+    .bind(&trade.t)
+    .bind(trade.kind)
+    .bind(trade.quantity)
+    .bind(trade.price)
+    .bind(trade.amount)
+    .bind(fee)
+    .bind(tax)
+    .bind(trade.day_trade)
+    .bind(&trade.note)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("[Portfolio] save trade error: {}", e))?;
+    Ok(result.last_insert_rowid())
+}
+
+#[tauri::command]
+pub async fn add_trade(db: State<'_, Db>, trade: TradeInput) -> Result<i64, String> {
+    insert_trade(&db.0, &trade).await
+}
+
+#[tauri::command]
+pub async fn delete_trade(db: State<'_, Db>, id: i64) -> Result<(), String> {
+    sqlx::query("DELETE FROM trade WHERE id = $1")
+        .bind(id)
+        .execute(&db.0)
+        .await
+        .map_err(|e| format!("[Portfolio] delete trade error: {}", e))?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_portfolio_summary(
+    db: State<'_, Db>,
+    portfolio_id: i64,
+) -> Result<PortfolioSummary, String> {
+    summary(&db.0, portfolio_id).await
+}
+
+#[tauri::command]
+pub async fn get_nav_history(
+    db: State<'_, Db>,
+    portfolio_id: i64,
+) -> Result<Vec<NavPoint>, String> {
+    nav_history(&db.0, portfolio_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sqlite::connection;
+
+    fn trade(kind: TradeKind, quantity: i64, price: f64, fee: f64, tax: f64) -> Trade {
+        Trade {
+            id: 0,
+            portfolio_id: 1,
+            stock_id: Some("2330".to_string()),
+            t: "20241007".to_string(),
+            kind,
+            quantity,
+            price,
+            amount: 0.0,
+            fee,
+            tax,
+            day_trade: false,
+            note: None,
+        }
+    }
+
+    fn input(kind: TradeKind, stock_id: &str, quantity: i64, price: f64) -> TradeInput {
+        TradeInput {
+            portfolio_id: 1,
+            stock_id: Some(stock_id.to_string()),
+            t: "20241007".to_string(),
+            kind,
+            quantity,
+            price,
+            amount: 0.0,
+            fee: None,
+            tax: None,
+            day_trade: false,
+            note: None,
+        }
+    }
+
+    #[test]
+    fn broker_fee_applies_discount_floor_and_minimum() {
+        // 100,000 * 0.1425% = 142.5，無條件捨去
+        assert_eq!(broker_fee(100_000.0, 1.0, 20.0), 142.0);
+        // 六折 85.5 捨去為 85
+        assert_eq!(broker_fee(100_000.0, 0.6, 20.0), 85.0);
+        // 10,000 * 0.1425% = 14.25 未滿最低手續費 20
+        assert_eq!(broker_fee(10_000.0, 1.0, 20.0), 20.0);
+        assert_eq!(broker_fee(0.0, 1.0, 20.0), 0.0);
+    }
+
+    #[test]
+    fn transaction_tax_by_security_type() {
+        assert_eq!(transaction_tax("stock", "2330", 100_000.0, false), 300.0);
+        assert_eq!(transaction_tax("stock", "2330", 100_000.0, true), 150.0);
+        assert_eq!(transaction_tax("etf", "0050", 100_000.0, false), 100.0);
+        assert_eq!(transaction_tax("etf", "0050", 100_000.0, true), 100.0);
+        assert_eq!(transaction_tax("etn", "020000", 100_000.0, false), 100.0);
+        // 債券 ETF 免徵
+        assert_eq!(transaction_tax("etf", "00679B", 100_000.0, false), 0.0);
+        // 代號 00 開頭但非 ETF 仍依股票稅率
+        assert_eq!(transaction_tax("stock", "0015", 1_000.0, false), 3.0);
+        assert_eq!(transaction_tax("stock", "2330", 999.0, false), 2.0);
+    }
+
+    #[test]
+    fn realized_pnl_uses_weighted_average_cost() {
+        let mut ledger = Ledger::new(1_000_000.0);
+        ledger.apply(&trade(TradeKind::Buy, 1000, 100.0, 142.0, 0.0));
+        ledger.apply(&trade(TradeKind::Buy, 1000, 110.0, 156.0, 0.0));
+        ledger.apply(&trade(TradeKind::Sell, 1000, 120.0, 171.0, 360.0));
+        let holding = &ledger.holdings["2330"];
+        assert_eq!(holding.quantity, 1000);
+        assert_eq!(holding.cost, 105_149.0);
+        assert_eq!(holding.realized, 120_000.0 - 171.0 - 360.0 - 105_149.0);
+        assert_eq!(ledger.cash, 1_000_000.0 - 210_298.0 + 119_469.0);
+    }
+
+    #[test]
+    fn oversold_ledger_only_realizes_held_quantity() {
+        let mut ledger = Ledger::new(0.0);
+        ledger.apply(&trade(TradeKind::Buy, 1000, 100.0, 0.0, 0.0));
+        ledger.apply(&trade(TradeKind::Sell, 2000, 110.0, 0.0, 0.0));
+        let holding = &ledger.holdings["2330"];
+        assert_eq!(holding.quantity, 0);
+        assert_eq!(holding.realized, 10_000.0);
+        // 無持股時再賣出不影響已實現損益
+        ledger.apply(&trade(TradeKind::Sell, 1000, 110.0, 0.0, 0.0));
+        assert_eq!(ledger.holdings["2330"].realized, 10_000.0);
+    }
+
+    #[test]
+    fn insert_trade_computes_costs_and_rejects_oversell() {
+        tauri::async_runtime::block_on(async {
+            let pool = connection::memory().await;
+            connection::migrate(&pool, ..).await;
+            sqlx::query("UPDATE portfolio SET fee_discount = 0.6, min_fee = 20 WHERE id = 1")
+                .execute(&pool)
+                .await
+                .unwrap();
+            sqlx::query(
+                "INSERT INTO stock (stock_id, stock_name, security_type)
+                 VALUES ('2330', '台積電', 'stock'), ('0050', '元大台灣50', 'etf'), ('00679B', '元大美債20年', 'etf')",
+            )
+            .execute(&pool)
+            .await
+            .unwrap();
+
+            for stock_id in ["2330", "0050", "00679B"] {
+                insert_trade(&pool, &input(TradeKind::Buy, stock_id, 1000, 100.0))
+                    .await
+                    .unwrap();
+            }
+            let error = insert_trade(&pool, &input(TradeKind::Sell, "2330", 1001, 100.0))
+                .await
+                .unwrap_err();
+            assert!(error.contains("exceeds holding 1000"), "{}", error);
+            for stock_id in ["2330", "0050", "00679B"] {
+                insert_trade(&pool, &input(TradeKind::Sell, stock_id, 1000, 100.0))
+                    .await
+                    .unwrap();
+            }
+
+            let costs: Vec<(String, String, f64, f64)> =
+                sqlx::query_as("SELECT stock_id, kind, fee, tax FROM trade ORDER BY id")
+                    .fetch_all(&pool)
+                    .await
+                    .unwrap();
+            assert_eq!(
+                costs,
+                [
+                    ("2330".to_string(), "buy".to_string(), 85.0, 0.0),
+                    ("0050".to_string(), "buy".to_string(), 85.0, 0.0),
+                    ("00679B".to_string(), "buy".to_string(), 85.0, 0.0),
+                    ("2330".to_string(), "sell".to_string(), 85.0, 300.0),
+                    ("0050".to_string(), "sell".to_string(), 85.0, 100.0),
+                    ("00679B".to_string(), "sell".to_string(), 85.0, 0.0),
+                ]
+            );
+
+            let summary = summary(&pool, 1).await.unwrap();
+            assert_eq!(summary.fees, 85.0 * 6.0);
+            assert_eq!(summary.taxes, 400.0);
+        });
+    }
+}
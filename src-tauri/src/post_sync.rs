@@ -0,0 +1,52 @@
+use tauri::{Listener, Manager};
+
+use crate::adjustment;
+use crate::alerts;
+use crate::breadth;
+use crate::chips;
+use crate::industry;
+use crate::intraday;
+use crate::signals;
+use crate::sqlite::connection::Db;
+
+/// 前端 `SyncEngine.broadcast("status", ...)` 送出的事件
+const SYNC_STATUS_EVENT: &str = "sync:status_change";
+
+/// 同步完成後依序執行的 Rust 端工作
+async fn run(app: tauri::AppHandle) {
+    let pool = app.state::<Db>().0.clone();
+    if let Err(e) = adjustment::on_sync_success(&app, &pool).await {
+        log::error!("[PostSync] adjustment failed: {}", e);
+    }
+    if let Err(e) = chips::on_sync_success(&pool, &chips::ChipSources::load(&app)).await {
+        log::error!("[PostSync] chips ingestion failed: {}", e);
+    }
+    if let Err(e) = intraday::on_sync_success(&app, &pool).await {
+        log::error!("[PostSync] minute bars failed: {}", e);
+    }
+    if let Err(e) = breadth::on_sync_success(&pool).await {
+        log::error!("[PostSync] market breadth failed: {}", e);
+    }
+    if let Err(e) = industry::on_sync_success(&pool).await {
+        log::error!("[PostSync] industry strength failed: {}", e);
+    }
+    if let Err(e) = signals::on_sync_success(&pool).await {
+        log::error!("[PostSync] signal scan failed: {}", e);
+    }
+    // 警示條件可能使用訊號次數，需在訊號掃描之後評估
+    if let Err(e) = alerts::evaluate(&app, &pool).await {
+        log::error!("[PostSync] alert evaluation failed: {}", e);
+    }
+}
+
+pub fn register(app: &tauri::AppHandle) {
+    let handle = app.clone();
+    app.listen_any(SYNC_STATUS_EVENT, move |event| {
+        let Ok(status) = serde_json::from_str::<String>(event.payload()) else {
+            return;
+        };
+        if status == "success" {
+            tauri::async_runtime::spawn(run(handle.clone()));
+        }
+    });
+}
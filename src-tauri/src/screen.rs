@@ -0,0 +1,677 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use tauri_plugin_dialog::DialogExt;
+
+use crate::alignment::{self, Alignment};
+use crate::breadth;
+use crate::calendar;
+use crate::chips;
+use crate::indicators::SKILLS_COLUMNS;
+use crate::industry;
+use crate::listing;
+use crate::universe;
+
+/// 與 `useFindStocksByPrompt` 取用的日期數量一致
+const DAILY_DATES: i64 = 30;
+const WEEKLY_DATES: i64 = 4;
+const HOURLY_DATES: i64 = 24;
+
+/// 可用於基本面條件的欄位與所在資料表別名
+const FINANCIAL_METRIC_COLUMNS: &[&str] = &[
+    "pe",
+    "pb",
+    "dividend_yield",
+    "gross_profit_margin",
+    "operating_margin",
+    "pre_tax_profit_margin",
+    "roa",
+    "roe",
+    "book_value_per_share",
+];
+/// 別名、資料表與 JOIN 條件；每日籌碼、產業排名與市場寬度只取選股當日的資料
+const FUNDAMENTAL_TABLES: &[(&str, &str, &str)] = &[
+    ("f", "financial_metric", "f.stock_id = s.stock_id"),
+    ("r", "recent_fundamental", "r.stock_id = s.stock_id"),
+    ("p", "investor_positions", "p.stock_id = s.stock_id"),
+    (
+        "i",
+        "daily_institutional",
+        "i.stock_id = s.stock_id AND i.t = '{{day:0}}'",
+    ),
+    (
+        "m",
+        "daily_margin",
+        "m.stock_id = s.stock_id AND m.t = '{{day:0}}'",
+    ),
+    (
+        "g",
+        "industry_rank",
+        "g.stock_id = s.stock_id AND g.t = '{{day:0}}'",
+    ),
+    // 大盤濾網：同一交易日所有股票共用一列
+    ("b", "market_breadth", "b.t = '{{day:0}}'"),
+];
+/// 排名條件可使用的當日 K 線欄位
+const DEAL_COLUMNS: &[&str] = &["c", "o", "h", "l", "v"];
+/// 排名結果中的名次欄位，1 為第一名
+const RANK_COLUMN: &str = "screen_rank";
+/// 兩字元運算子須排在單字元之前
+const OPERATORS: &[&str] = &["<=", ">=", "!=", "<", ">", "="];
+
+/// 前端編譯好的選股條件，日期以 `{{day:N}}` / `{{week:N}}` / `{{hour:N}}` 佔位
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptTemplate {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub sql: String,
+    /// 基本面與籌碼條件，例如 `pe < 15`、`revenue_recent_m1_yoy > 20`、`foreign_net > 0`、`above_ma20_ratio > 50`
+    #[serde(default)]
+    pub fundamentals: Vec<String>,
+    /// 排名條件，於選股當日對全市場計算，例如 `return_20 desc top 20`、`percentile(turnover_rate) > 90`
+    #[serde(default)]
+    pub rankings: Vec<String>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Ranking<'a> {
+    /// `欄位 asc|desc [top N]`
+    Order {
+        metric: &'a str,
+        desc: bool,
+        top: Option<usize>,
+    },
+    /// `percentile(欄位) 運算子 0-100`，百分位越高數值越大
+    Percentile {
+        metric: &'a str,
+        operator: &'static str,
+        value: f64,
+    },
+}
+
+/// 欄位所在資料表的別名，不在白名單內回傳 None
+fn fundamental_alias(column: &str) -> Option<&'static str> {
+    if FINANCIAL_METRIC_COLUMNS.contains(&column) {
+        return Some("f");
+    }
+    if chips::INSTITUTIONAL_COLUMNS.contains(&column) {
+        return Some("i");
+    }
+    if chips::MARGIN_COLUMNS.contains(&column) {
+        return Some("m");
+    }
+    if industry::RANK_COLUMNS.contains(&column) {
+        return Some("g");
+    }
+    if breadth::BREADTH_COLUMNS.contains(&column) {
+        return Some("b");
+    }
+    let period = |rest: &str| matches!(rest, "1" | "2" | "3" | "4");
+    if let Some(rest) = column.strip_prefix("revenue_recent_m") {
+        let (n, field) = rest.split_once('_').unwrap_or((rest, ""));
+        return (period(n) && matches!(field, "" | "mom" | "yoy" | "yoy_acc")).then_some("r");
+    }
+    if let Some(rest) = column
+        .strip_prefix("eps_recent_q")
+        .or_else(|| column.strip_prefix("eps_recent_y"))
+    {
+        return period(rest).then_some("r");
+    }
+    if let Some(rest) = column.strip_prefix("recent_w") {
+        let (n, field) = rest.split_once('_')?;
+        return (period(n) && matches!(field, "foreign_ratio" | "big_investor_ratio"))
+            .then_some("p");
+    }
+    None
+}
+
+/// 將欄位或數值轉為 SQL 運算元，欄位帶上資料表別名
+fn fundamental_operand(token: &str) -> Result<(String, Option<&'static str>), String> {
+    let token = token.trim();
+    if let Some(value) = token.parse::<f64>().ok().filter(|v| v.is_finite()) {
+        return Ok((value.to_string(), None));
+    }
+    let alias = fundamental_alias(token)
+        .ok_or_else(|| format!("[Screen] unknown fundamental column: {}", token))?;
+    Ok((format!("{}.{}", alias, token), Some(alias)))
+}
+
+/// 解析 `欄位 運算子 欄位或數值`，回傳 SQL 條件與用到的資料表別名
+fn fundamental_condition(expr: &str) -> Result<(String, Vec<&'static str>), String> {
+    let (index, operator) = OPERATORS
+        .iter()
+        .filter_map(|op| expr.find(op).map(|i| (i, *op)))
+        .min_by_key(|(i, op)| (*i, std::cmp::Reverse(op.len())))
+        .ok_or_else(|| format!("[Screen] invalid fundamental condition: {}", expr))?;
+    let (left, left_alias) = fundamental_operand(&expr[..index])?;
+    let (right, right_alias) = fundamental_operand(&expr[index + operator.len()..])?;
+    if left_alias.is_none() {
+        return Err(format!(
+            "[Screen] fundamental condition must start with a column: {}",
+            expr
+        ));
+    }
+    Ok((
+        format!("{} {} {}", left, operator, right),
+        left_alias.into_iter().chain(right_alias).collect(),
+    ))
+}
+
+/// 資料表別名對應的 LEFT JOIN 子句
+fn fundamental_join(alias: &str) -> Option<String> {
+    FUNDAMENTAL_TABLES
+        .iter()
+        .find(|(a, _, _)| *a == alias)
+        .map(|(alias, table, on)| format!(" LEFT JOIN {} {} ON {}", table, alias, on))
+}
+
+fn deal_join(alias: &str, day: usize) -> String {
+    format!(
+        " LEFT JOIN daily_deal {a} ON {a}.stock_id = s.stock_id AND {a}.t = '{{{{day:{d}}}}}'",
+        a = alias,
+        d = day
+    )
+}
+
+/// 排名欄位的 SQL 運算式與所需的 JOIN；`return_N` 為 N 個交易日前至選股當日的報酬 (%)
+fn rank_metric(metric: &str) -> Result<(String, Vec<String>), String> {
+    if let Some(n) = metric
+        .strip_prefix("return_")
+        .and_then(|n| n.parse::<usize>().ok())
+        .filter(|n| (1..DAILY_DATES as usize).contains(n))
+    {
+        let alias = format!("d{}", n);
+        return Ok((
+            format!("(d.c / {}.c - 1) * 100", alias),
+            vec![deal_join("d", 0), deal_join(&alias, n)],
+        ));
+    }
+    if DEAL_COLUMNS.contains(&metric) {
+        return Ok((format!("d.{}", metric), vec![deal_join("d", 0)]));
+    }
+    if SKILLS_COLUMNS.contains(&metric) {
+        return Ok((
+            format!("k.{}", metric),
+            vec![
+                " LEFT JOIN daily_skills k ON k.stock_id = s.stock_id AND k.t = '{{day:0}}'"
+                    .to_string(),
+            ],
+        ));
+    }
+    fundamental_alias(metric)
+        .and_then(|alias| {
+            Some((
+                format!("{}.{}", alias, metric),
+                vec![fundamental_join(alias)?],
+            ))
+        })
+        .ok_or_else(|| format!("[Screen] unknown ranking column: {}", metric))
+}
+
+fn ranking_clause(text: &str) -> Result<Ranking<'_>, String> {
+    let invalid = || format!("[Screen] invalid ranking: {}", text);
+    let text = text.trim();
+    if let Some(rest) = text.strip_prefix("percentile(") {
+        let (metric, condition) = rest.split_once(')').ok_or_else(invalid)?;
+        let condition = condition.trim();
+        let operator = OPERATORS
+            .iter()
+            .find(|op| condition.starts_with(**op))
+            .ok_or_else(invalid)?;
+        let value = condition[operator.len()..]
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|v| (0.0..=100.0).contains(v))
+            .ok_or_else(invalid)?;
+        return Ok(Ranking::Percentile {
+            metric: metric.trim(),
+            operator,
+            value,
+        });
+    }
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    let (metric, direction, top) = match tokens.as_slice() {
+        [metric, direction] => (*metric, *direction, None),
+        [metric, direction, keyword, n] if keyword.eq_ignore_ascii_case("top") => {
+            let n = n.parse::<usize>().ok().filter(|n| *n > 0);
+            (*metric, *direction, Some(n.ok_or_else(invalid)?))
+        }
+        _ => return Err(invalid()),
+    };
+    let desc = match direction.to_ascii_lowercase().as_str() {
+        "desc" => true,
+        "asc" => false,
+        _ => return Err(invalid()),
+    };
+    Ok(Ranking::Order { metric, desc, top })
+}
+
+impl PromptTemplate {
+    /// 有排序條件時，查詢結果帶有名次欄位，需依名次取用
+    pub fn is_ranked(&self) -> bool {
+        self.rankings
+            .iter()
+            .any(|text| matches!(ranking_clause(text), Ok(Ranking::Order { .. })))
+    }
+
+    /// 將基本面條件接在技術面 SQL 之後，再套用排名條件，組成單一查詢樣板；沒有資料的欄位 (NULL) 視為不符合
+    pub fn compile(&self) -> Result<String, String> {
+        let filter = self.filter_sql()?;
+        if self.rankings.is_empty() {
+            return filter.ok_or_else(|| format!("[Screen] {} has no conditions", self.name));
+        }
+        self.rank_sql(filter)
+    }
+
+    fn filter_sql(&self) -> Result<Option<String>, String> {
+        let technical = self.sql.trim();
+        if self.fundamentals.is_empty() {
+            return Ok((!technical.is_empty()).then(|| technical.to_string()));
+        }
+
+        let mut conditions = Vec::new();
+        let mut aliases = Vec::new();
+        for expr in &self.fundamentals {
+            let (condition, used) = fundamental_condition(expr)?;
+            conditions.push(condition);
+            aliases.extend(used);
+        }
+        let joins: String = FUNDAMENTAL_TABLES
+            .iter()
+            .filter(|(alias, _, _)| aliases.contains(alias))
+            .filter_map(|(alias, _, _)| fundamental_join(alias))
+            .collect();
+        let fundamental = format!(
+            "SELECT s.stock_id FROM stock s{} WHERE {}",
+            joins,
+            conditions.join(" AND ")
+        );
+        if technical.is_empty() {
+            return Ok(Some(fundamental));
+        }
+        Ok(Some(format!(
+            "SELECT stock_id FROM ({}) WHERE stock_id IN ({})",
+            technical, fundamental
+        )))
+    }
+
+    /// 百分位以選股當日掛牌且有值的股票計算，再與其他條件取交集；排序與前 N 名則在符合條件的股票中取。
+    /// 掛牌條件須在 LIMIT 與 PERCENT_RANK 之前套用，已下市的股票才不會佔用名次
+    fn rank_sql(&self, filter: Option<String>) -> Result<String, String> {
+        let universe = format!(
+            "s.{} AND {}",
+            listing::SYNCED_TYPES_FILTER,
+            universe::active_at("s.stock_id", "{{day:0}}")
+        );
+        let mut joins: Vec<String> = Vec::new();
+        let mut conditions = vec![universe.clone()];
+        if let Some(filter) = filter {
+            conditions.push(format!("s.stock_id IN (SELECT stock_id FROM ({}))", filter));
+        }
+        let mut orders = Vec::new();
+        let mut limit = None;
+        for (i, text) in self.rankings.iter().enumerate() {
+            match ranking_clause(text)? {
+                Ranking::Order { metric, desc, top } => {
+                    let (expr, used) = rank_metric(metric)?;
+                    for join in used {
+                        if !joins.contains(&join) {
+                            joins.push(join);
+                        }
+                    }
+                    conditions.push(format!("{} IS NOT NULL", expr));
+                    orders.push(format!("{} {}", expr, if desc { "DESC" } else { "ASC" }));
+                    if let Some(n) = top {
+                        if limit.replace(n).is_some() {
+                            return Err(format!("[Screen] {} has more than one top-N", self.name));
+                        }
+                    }
+                }
+                Ranking::Percentile {
+                    metric,
+                    operator,
+                    value,
+                } => {
+                    let (expr, used) = rank_metric(metric)?;
+                    let alias = format!("pc{}", i);
+                    joins.push(format!(
+                        " LEFT JOIN (SELECT s.stock_id, PERCENT_RANK() OVER (ORDER BY {expr}) * 100 AS pct
+                         FROM stock s{used} WHERE {universe} AND {expr} IS NOT NULL) {alias}
+                         ON {alias}.stock_id = s.stock_id",
+                        expr = expr,
+                        used = used.concat(),
+                        universe = universe,
+                        alias = alias
+                    ));
+                    conditions.push(format!("{}.pct {} {}", alias, operator, value));
+                }
+            }
+        }
+        let rank = if orders.is_empty() {
+            "NULL".to_string()
+        } else {
+            format!(
+                "ROW_NUMBER() OVER (ORDER BY {}, s.stock_id)",
+                orders.join(", ")
+            )
+        };
+        let mut sql = format!(
+            "SELECT s.stock_id, {} AS {} FROM stock s{} WHERE {}",
+            rank,
+            RANK_COLUMN,
+            joins.concat(),
+            conditions.join(" AND ")
+        );
+        if let Some(n) = limit {
+            sql.push_str(&format!(" ORDER BY {} LIMIT {}", RANK_COLUMN, n));
+        }
+        Ok(sql)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct ScreenMatch {
+    pub stock_id: String,
+    pub stock_name: Option<String>,
+    pub industry_group: Option<String>,
+    pub market_type: Option<String>,
+    /// 有排序條件時的名次
+    pub rank: Option<i64>,
+}
+
+async fn dates_before(
+    pool: &SqlitePool,
+    sql: &str,
+    bound: String,
+    limit: i64,
+) -> Result<Vec<String>, String> {
+    sqlx::query_scalar(sql)
+        .bind(bound)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("[Screen] load dates error: {}", e))
+}
+
+/// 以某交易日 (含) 之前的日/週/小時日期填入佔位符，對應前端選定該日的選股結果。
+/// 各時框的最新日期由 `alignment` 決定，回看歷史時不會用到尚未收盤的週 K 或小時 K
+pub struct DateContext {
+    daily: Vec<String>,
+    weekly: Vec<String>,
+    hourly: Vec<String>,
+}
+
+impl DateContext {
+    /// `date` 為該日收盤後，None 時為現在；之前沒有任何日 K 時回傳 None
+    pub async fn load(pool: &SqlitePool, date: Option<NaiveDate>) -> Result<Option<Self>, String> {
+        let at = date.map_or_else(alignment::now, alignment::session_close);
+        let aligned = Alignment::load(pool, at).await?;
+        let Some(today) = aligned.daily.latest(aligned.live) else {
+            return Ok(None);
+        };
+        let daily = dates_before(
+            pool,
+            "SELECT DISTINCT t FROM daily_deal WHERE t <= $1 ORDER BY t DESC LIMIT $2",
+            today.to_string(),
+            DAILY_DATES,
+        )
+        .await?;
+        let weekly = match aligned.weekly.latest(aligned.live) {
+            Some(week) => {
+                dates_before(
+                    pool,
+                    "SELECT DISTINCT t FROM weekly_deal WHERE t <= $1 ORDER BY t DESC LIMIT $2",
+                    week.to_string(),
+                    WEEKLY_DATES,
+                )
+                .await?
+            }
+            None => Vec::new(),
+        };
+        let hourly =
+            match aligned.hourly.latest(aligned.live) {
+                Some(hour) => dates_before(
+                    pool,
+                    "SELECT DISTINCT ts FROM hourly_deal WHERE ts <= $1 ORDER BY ts DESC LIMIT $2",
+                    hour.to_string(),
+                    HOURLY_DATES,
+                )
+                .await?,
+                None => Vec::new(),
+            };
+
+        Ok(Some(Self {
+            daily,
+            weekly,
+            hourly,
+        }))
+    }
+
+    pub fn today(&self) -> &str {
+        &self.daily[0]
+    }
+
+    pub fn previous_day(&self) -> &str {
+        self.daily.get(1).map_or("", String::as_str)
+    }
+
+    /// 回傳 None 表示條件使用的日期超出本地資料範圍
+    pub fn render(&self, template: &str) -> Option<String> {
+        let mut sql = template.to_string();
+        for (prefix, dates) in [
+            ("day", &self.daily),
+            ("week", &self.weekly),
+            ("hour", &self.hourly),
+        ] {
+            for (i, date) in dates.iter().enumerate() {
+                sql = sql.replace(&format!("{{{{{}:{}}}}}", prefix, i), date);
+            }
+        }
+        (!sql.contains("{{")).then_some(sql)
+    }
+}
+
+/// 名次欄位或 NULL，讓有無排名條件的查詢可以共用同一種外層 SQL
+fn rank_expr(ranked: bool) -> String {
+    if ranked {
+        format!("MIN({})", RANK_COLUMN)
+    } else {
+        "NULL".to_string()
+    }
+}
+
+/// 執行已填入日期的條件 SQL，回傳當日仍掛牌且符合的股票代號；`ranked` 時依名次排序
+pub async fn matched_stock_ids(
+    pool: &SqlitePool,
+    sql: &str,
+    day: NaiveDate,
+    ranked: bool,
+) -> Result<Vec<String>, String> {
+    sqlx::query_scalar(&format!(
+        "SELECT stock_id FROM ({}) WHERE {} GROUP BY stock_id ORDER BY {}, stock_id",
+        sql,
+        universe::active_on("stock_id", day),
+        rank_expr(ranked)
+    ))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("[Screen] query error: {}", e))
+}
+
+/// 以指定交易日執行選股，只納入當日掛牌的股票並顯示當時的名稱與市場別；日期不足時回傳空結果
+pub async fn screen(
+    pool: &SqlitePool,
+    template: &PromptTemplate,
+    date: Option<NaiveDate>,
+) -> Result<Vec<ScreenMatch>, String> {
+    let Some(dates) = DateContext::load(pool, date).await? else {
+        return Ok(Vec::new());
+    };
+    let Some(sql) = dates.render(&template.compile()?) else {
+        log::warn!("[Screen] {} skipped: not enough local dates", template.name);
+        return Ok(Vec::new());
+    };
+    let Some(day) = calendar::parse_day(dates.today()) else {
+        return Ok(Vec::new());
+    };
+    sqlx::query_as(&format!(
+        "SELECT s.stock_id,
+                COALESCE(h.stock_name, s.stock_name) AS stock_name,
+                COALESCE(h.industry_group, s.industry_group) AS industry_group,
+                COALESCE(h.market_type, s.market_type) AS market_type,
+                m.rank
+         FROM stock s
+         JOIN (SELECT stock_id, {} AS rank FROM ({}) GROUP BY stock_id) m
+              ON m.stock_id = s.stock_id
+         LEFT JOIN stock_history h ON h.stock_id = s.stock_id
+              AND h.valid_from <= $1 AND (h.valid_to IS NULL OR h.valid_to > $1)
+         WHERE {}
+         ORDER BY m.rank, s.stock_id",
+        rank_expr(template.is_ranked()),
+        sql,
+        universe::active_on("s.stock_id", day)
+    ))
+    .bind(dates.today())
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("[Screen] query error: {}", e))
+}
+
+/// 將策略樣板存為 JSON，供 `schoice screen --prompt` / `schoice backtest --prompt` 使用
+#[tauri::command]
+pub async fn save_prompt_template(
+    app: tauri::AppHandle,
+    template: PromptTemplate,
+) -> Result<Option<String>, String> {
+    let dialog = app
+        .dialog()
+        .file()
+        .add_filter("JSON", &["json"])
+        .set_file_name(format!("{}.json", template.name));
+    let picked = tauri::async_runtime::spawn_blocking(move || dialog.blocking_save_file())
+        .await
+        .map_err(|e| e.to_string())?;
+    let Some(path) = picked else {
+        return Ok(None);
+    };
+    let path = path.into_path().map_err(|e| e.to_string())?;
+    let text = serde_json::to_string_pretty(&template).map_err(|e| e.to_string())?;
+    std::fs::write(&path, text).map_err(|e| format!("[Screen] write error: {}", e))?;
+    Ok(Some(path.display().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sqlite::connection;
+
+    const DAY: &str = "20241007";
+
+    /// 1101~1105 收盤價 10、20、20、30、40；1105 已於前一日下市
+    async fn market() -> SqlitePool {
+        let pool = connection::memory().await;
+        connection::migrate(&pool, ..).await;
+        for (stock_id, c) in [
+            ("1101", 10.0),
+            ("1102", 20.0),
+            ("1103", 20.0),
+            ("1104", 30.0),
+            ("1105", 40.0),
+        ] {
+            sqlx::query(
+                "INSERT INTO stock (stock_id, stock_name, security_type) VALUES ($1, $1, 'stock')",
+            )
+            .bind(stock_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+            sqlx::query(
+                "INSERT INTO daily_deal (stock_id, t, o, h, l, c, v) VALUES ($1, $2, $3, $3, $3, $3, 1000)",
+            )
+            .bind(stock_id)
+            .bind(DAY)
+            .bind(c)
+            .execute(&pool)
+            .await
+            .unwrap();
+            let valid_to = (stock_id == "1105").then_some("20241007");
+            sqlx::query(
+                "INSERT INTO stock_history (stock_id, stock_name, security_type, valid_from, valid_to)
+                 VALUES ($1, $1, 'stock', '20200101', $2)",
+            )
+            .bind(stock_id)
+            .bind(valid_to)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        pool
+    }
+
+    async fn ranked(pool: &SqlitePool, rankings: &[&str]) -> Vec<(String, Option<i64>)> {
+        let template = PromptTemplate {
+            id: String::new(),
+            name: "rank".to_string(),
+            sql: String::new(),
+            fundamentals: Vec::new(),
+            rankings: rankings.iter().map(|r| r.to_string()).collect(),
+        };
+        screen(pool, &template, calendar::parse_day(DAY))
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|m| (m.stock_id, m.rank))
+            .collect()
+    }
+
+    fn ids(matches: &[(String, Option<i64>)]) -> Vec<&str> {
+        matches.iter().map(|(id, _)| id.as_str()).collect()
+    }
+
+    #[test]
+    fn top_n_skips_delisted_stocks_and_breaks_ties_by_id() {
+        tauri::async_runtime::block_on(async {
+            let pool = market().await;
+            // 下市的 1105 不佔名次，第一名為 1104
+            let top = ranked(&pool, &["c desc top 3"]).await;
+            assert_eq!(
+                top,
+                [
+                    ("1104".to_string(), Some(1)),
+                    ("1102".to_string(), Some(2)),
+                    ("1103".to_string(), Some(3)),
+                ]
+            );
+            let top = ranked(&pool, &["c asc top 2"]).await;
+            assert_eq!(ids(&top), ["1101", "1102"]);
+        });
+    }
+
+    #[test]
+    fn top_n_larger_than_candidates_returns_all() {
+        tauri::async_runtime::block_on(async {
+            let pool = market().await;
+            let top = ranked(&pool, &["c desc top 50"]).await;
+            assert_eq!(ids(&top), ["1104", "1102", "1103", "1101"]);
+        });
+    }
+
+    #[test]
+    fn percentile_covers_listed_stocks_only() {
+        tauri::async_runtime::block_on(async {
+            let pool = market().await;
+            // 掛牌中 4 檔的百分位為 0、33.3、33.3、100
+            let all = ranked(&pool, &["percentile(c) >= 0"]).await;
+            assert_eq!(ids(&all), ["1101", "1102", "1103", "1104"]);
+            let top = ranked(&pool, &["percentile(c) >= 100"]).await;
+            assert_eq!(ids(&top), ["1104"]);
+            let bottom = ranked(&pool, &["percentile(c) <= 0"]).await;
+            assert_eq!(ids(&bottom), ["1101"]);
+            let middle = ranked(&pool, &["percentile(c) > 30", "percentile(c) < 50"]).await;
+            assert_eq!(ids(&middle), ["1102", "1103"]);
+        });
+    }
+}
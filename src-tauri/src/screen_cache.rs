@@ -0,0 +1,153 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+
+use chrono::NaiveDate;
+use sqlx::SqlitePool;
+use tauri::State;
+
+use crate::alignment;
+use crate::calendar;
+use crate::screen::{self, PromptTemplate, ScreenMatch};
+use crate::sqlite::connection::Db;
+
+/// 最多保留的選股結果數，超過時先丟掉資料版本已過期的結果
+const MAX_ENTRIES: usize = 64;
+
+struct Entry {
+    /// 計算當下各資料表的版本，任一不同即視為過期
+    versions: Vec<(String, i64)>,
+    matches: Vec<ScreenMatch>,
+}
+
+/// 同一行程內所有視窗與 HTTP API 共用的選股結果
+static RESULTS: Mutex<Option<HashMap<u64, Entry>>> = Mutex::new(None);
+
+/// 以編譯後的 SQL (合併空白) 與評估日作為鍵，只有名稱不同的策略共用結果
+fn strategy_key(template: &PromptTemplate, date: Option<NaiveDate>) -> Result<u64, String> {
+    let sql = template.compile()?;
+    let mut hasher = DefaultHasher::new();
+    sql.split_whitespace()
+        .for_each(|token| token.hash(&mut hasher));
+    // 未指定日期時以今天區分，跨日後不會沿用前一天的最新結果
+    date.unwrap_or_else(|| alignment::now().date())
+        .hash(&mut hasher);
+    date.is_none().hash(&mut hasher);
+    Ok(hasher.finish())
+}
+
+/// 各資料表的寫入版本，寫入端每批提交時以 `writer::bump_versions` 遞增
+async fn versions(pool: &SqlitePool) -> Result<Vec<(String, i64)>, String> {
+    sqlx::query_as("SELECT table_name, version FROM data_version ORDER BY table_name")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("[ScreenCache] load versions error: {}", e))
+}
+
+fn lookup(key: u64, versions: &[(String, i64)]) -> Option<Vec<ScreenMatch>> {
+    let results = RESULTS.lock().ok()?;
+    let entry = results.as_ref()?.get(&key)?;
+    (entry.versions == versions).then(|| entry.matches.clone())
+}
+
+fn store(key: u64, versions: Vec<(String, i64)>, matches: Vec<ScreenMatch>) {
+    let Ok(mut results) = RESULTS.lock() else {
+        return;
+    };
+    let results = results.get_or_insert_with(HashMap::new);
+    if results.len() >= MAX_ENTRIES {
+        results.retain(|_, entry| entry.versions == versions);
+    }
+    if results.len() >= MAX_ENTRIES {
+        results.clear();
+    }
+    results.insert(key, Entry { versions, matches });
+}
+
+/// 與 `screen::screen` 相同，但資料版本未變時直接回傳先前的結果
+pub async fn screen(
+    pool: &SqlitePool,
+    template: &PromptTemplate,
+    date: Option<NaiveDate>,
+) -> Result<Vec<ScreenMatch>, String> {
+    let key = strategy_key(template, date)?;
+    let versions = versions(pool).await?;
+    if let Some(matches) = lookup(key, &versions) {
+        return Ok(matches);
+    }
+    let matches = screen::screen(pool, template, date).await?;
+    // 計算期間若有寫入則不快取，避免把半途的結果標上新版本
+    if self::versions(pool).await? == versions {
+        store(key, versions, matches.clone());
+    }
+    Ok(matches)
+}
+
+/// `date` 為 YYYYMMDD，未指定時為最新資料
+#[tauri::command]
+pub async fn run_screen(
+    db: State<'_, Db>,
+    template: PromptTemplate,
+    date: Option<String>,
+) -> Result<Vec<ScreenMatch>, String> {
+    let date = match date {
+        Some(date) => Some(
+            calendar::parse_day(&date)
+                .ok_or_else(|| format!("[ScreenCache] invalid date {}", date))?,
+        ),
+        None => None,
+    };
+    screen(&db.0, &template, date).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sqlite::{connection, writer};
+    use crate::types::{Bar, Timeframe};
+
+    fn version(versions: &[(String, i64)], table: &str) -> Option<i64> {
+        versions
+            .iter()
+            .find(|(name, _)| name == table)
+            .map(|(_, version)| *version)
+    }
+
+    #[test]
+    fn versions_bump_once_per_batch() {
+        tauri::async_runtime::block_on(async {
+            let pool = connection::memory().await;
+            connection::migrate(&pool, ..).await;
+            let before = versions(&pool).await.unwrap();
+            for table in ["daily_signals", "adjustment_factor", "industry_index"] {
+                assert_eq!(version(&before, table), Some(0));
+            }
+
+            let bars: Vec<Bar> = ["20240102", "20240103", "20240104"]
+                .iter()
+                .map(|t| Bar {
+                    t: t.to_string(),
+                    o: 10.0,
+                    h: 10.0,
+                    l: 10.0,
+                    c: 10.0,
+                    v: 1000,
+                })
+                .collect();
+            writer::save_deals(&pool, Timeframe::Daily, "2330", &bars)
+                .await
+                .unwrap();
+            let after = versions(&pool).await.unwrap();
+            assert_eq!(version(&after, "daily_deal"), Some(1));
+            assert_eq!(version(&after, "daily_skills"), Some(0));
+
+            // 沒有觸發器，批次外的單列寫入不會遞增版本
+            sqlx::query("DELETE FROM daily_deal WHERE t = '20240104'")
+                .execute(&pool)
+                .await
+                .unwrap();
+            assert_eq!(versions(&pool).await.unwrap(), after);
+        });
+    }
+}
@@ -0,0 +1,25 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tauri_plugin_store::StoreExt;
+
+/// 與前端 `Setting.store.ts` 的 `Store.load("store.json")` 共用
+pub const STORE_FILE: &str = "store.json";
+
+pub fn get<T: DeserializeOwned>(app: &tauri::AppHandle, key: &str) -> Option<T> {
+    let store = app.store(STORE_FILE).ok()?;
+    serde_json::from_value(store.get(key)?).ok()
+}
+
+pub fn set<T: Serialize>(app: &tauri::AppHandle, key: &str, value: T) -> Result<(), String> {
+    let store = app.store(STORE_FILE).map_err(|e| e.to_string())?;
+    store.set(key, serde_json::to_value(value).map_err(|e| e.to_string())?);
+    store.save().map_err(|e| e.to_string())
+}
+
+/// 不經 AppHandle 直接讀取 store.json，供 CLI 模式使用
+pub fn get_from_file<T: DeserializeOwned>(path: &std::path::Path, key: &str) -> Option<T> {
+    let text = std::fs::read_to_string(path).ok()?;
+    let mut values: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_str(&text).ok()?;
+    serde_json::from_value(values.remove(key)?).ok()
+}
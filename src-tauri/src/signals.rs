@@ -0,0 +1,698 @@
+use serde::Serialize;
+use sqlx::{QueryBuilder, SqlitePool};
+
+use crate::indicators::rsi::Rsi;
+use crate::listing;
+use crate::sqlite::writer;
+
+/// 每次自上次掃描點往前重新掃描的日線根數 (含暖機)
+const SCAN_BARS: i64 = 250;
+/// 視窗前段僅供轉折點與 RSI 暖機，不寫入訊號
+const WARMUP_BARS: usize = 120;
+/// RSI 週期與訊號線週期，與 Detail 頁 `useIndicatorSettings` 預設值一致
+const RSI_PERIOD: usize = 14;
+const RSI_SIGNAL_PERIOD: usize = 9;
+/// OBV 背離：判斷局部高低點的前後根數、往回尋找前一個高低點的根數、最小價格差
+const OBV_LOCAL_PERIOD: usize = 2;
+const OBV_LOOKBACK: usize = 30;
+const OBV_MIN_PRICE_DIFF: f64 = 0.02;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignalKind {
+    KdDivergence,
+    MacdDivergence,
+    RsiDivergence,
+    ObvDivergence,
+    Ma10Cross,
+    Ma20Cross,
+}
+
+impl SignalKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SignalKind::KdDivergence => "kd_divergence",
+            SignalKind::MacdDivergence => "macd_divergence",
+            SignalKind::RsiDivergence => "rsi_divergence",
+            SignalKind::ObvDivergence => "obv_divergence",
+            SignalKind::Ma10Cross => "ma10_cross",
+            SignalKind::Ma20Cross => "ma20_cross",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    /// 底背離 / 黃金交叉
+    Bullish,
+    /// 頂背離 / 死亡交叉
+    Bearish,
+}
+
+impl Direction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Direction::Bullish => "bullish",
+            Direction::Bearish => "bearish",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Signal {
+    pub kind: SignalKind,
+    pub direction: Direction,
+    /// 前一個轉折點；均線交叉與 end_t 相同
+    pub start_t: String,
+    /// 訊號成立日
+    pub end_t: String,
+    /// 背離為兩個轉折點間的價格變化幅度 (%)；均線交叉為 MA5 與長均線的乖離 (%)
+    pub strength: f64,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+struct Point {
+    t: String,
+    h: f64,
+    l: f64,
+    c: f64,
+    k: Option<f64>,
+    d: Option<f64>,
+    osc: Option<f64>,
+    obv: Option<f64>,
+    ma5: Option<f64>,
+    ma10: Option<f64>,
+    ma20: Option<f64>,
+}
+
+fn percent_change(from: f64, to: f64) -> f64 {
+    if from == 0.0 {
+        0.0
+    } else {
+        ((to - from) / from * 100.0).abs()
+    }
+}
+
+/// 以交叉點切分波段：上升段 (up = true) 取指標最高點，下降段取最低點，
+/// 比較相鄰兩個高點 (價格創高、指標未創高) 與低點 (價格創低、指標未創低)。
+/// 對應 `detectKdDivergence` / `detectMacdDivergence` / `detectRsiDivergence` 的共同流程，回傳 (方向, 前一轉折, 當前轉折)。
+fn segment_divergences(
+    points: &[Point],
+    crosses: &[(usize, bool)],
+    value: &[f64],
+) -> Vec<(Direction, usize, usize)> {
+    let pivot = |i: usize, up: bool| {
+        let end = crosses[i + 1..]
+            .iter()
+            .find(|(_, next_up)| *next_up != up)
+            .map_or(points.len() - 1, |(index, _)| *index);
+        let range = crosses[i].0..=end;
+        if up {
+            range.max_by(|a, b| value[*a].total_cmp(&value[*b]).then(b.cmp(a)))
+        } else {
+            range.min_by(|a, b| value[*a].total_cmp(&value[*b]))
+        }
+    };
+
+    let mut highs = Vec::new();
+    let mut lows = Vec::new();
+    for (i, (_, up)) in crosses.iter().enumerate() {
+        if let Some(index) = pivot(i, *up) {
+            if *up {
+                highs.push(index);
+            } else {
+                lows.push(index);
+            }
+        }
+    }
+
+    let mut found = Vec::new();
+    for pair in highs.windows(2) {
+        let (prev, curr) = (pair[0], pair[1]);
+        if points[curr].h > points[prev].h && value[curr] < value[prev] {
+            found.push((Direction::Bearish, prev, curr));
+        }
+    }
+    for pair in lows.windows(2) {
+        let (prev, curr) = (pair[0], pair[1]);
+        if points[curr].l < points[prev].l && value[curr] > value[prev] {
+            found.push((Direction::Bullish, prev, curr));
+        }
+    }
+    found
+}
+
+fn divergence_signal(
+    points: &[Point],
+    kind: SignalKind,
+    (direction, prev, curr): (Direction, usize, usize),
+    end: usize,
+) -> Signal {
+    let strength = match direction {
+        Direction::Bearish => percent_change(points[prev].h, points[curr].h),
+        Direction::Bullish => percent_change(points[prev].l, points[curr].l),
+    };
+    Signal {
+        kind,
+        direction,
+        start_t: points[prev].t.clone(),
+        end_t: points[end].t.clone(),
+        strength,
+    }
+}
+
+fn detect_kd(points: &[Point]) -> Vec<Signal> {
+    if points.len() < 10 {
+        return Vec::new();
+    }
+    let crosses: Vec<(usize, bool)> = (1..points.len())
+        .filter_map(|i| {
+            let (prev, curr) = (&points[i - 1], &points[i]);
+            let (pk, pd, ck, cd) = (prev.k?, prev.d?, curr.k?, curr.d?);
+            if pk < pd && ck > cd {
+                Some((i, true))
+            } else if pk > pd && ck < cd {
+                Some((i, false))
+            } else {
+                None
+            }
+        })
+        .collect();
+    let k: Vec<f64> = points.iter().map(|p| p.k.unwrap_or(0.0)).collect();
+    segment_divergences(points, &crosses, &k)
+        .into_iter()
+        .map(|found| divergence_signal(points, SignalKind::KdDivergence, found, found.2))
+        .collect()
+}
+
+fn detect_macd(points: &[Point]) -> Vec<Signal> {
+    if points.len() < 20 {
+        return Vec::new();
+    }
+    let osc: Vec<f64> = points.iter().map(|p| p.osc.unwrap_or(0.0)).collect();
+    let crosses: Vec<(usize, bool)> = (1..points.len())
+        .filter_map(|i| {
+            if osc[i - 1] < 0.0 && osc[i] >= 0.0 {
+                Some((i, true))
+            } else if osc[i - 1] > 0.0 && osc[i] <= 0.0 {
+                Some((i, false))
+            } else {
+                None
+            }
+        })
+        .collect();
+    segment_divergences(points, &crosses, &osc)
+        .into_iter()
+        .map(|found| divergence_signal(points, SignalKind::MacdDivergence, found, found.2))
+        .collect()
+}
+
+/// RSI 與其 SMA9 訊號線交叉切分波段，背離成立後 8 根內需有 MACD 柱狀體確認
+fn detect_rsi(points: &[Point]) -> Vec<Signal> {
+    if points.len() < 20 {
+        return Vec::new();
+    }
+    let mut rsi = Rsi::new(RSI_PERIOD);
+    let values: Vec<f64> = points
+        .iter()
+        .map(|p| rsi.next(p.c).unwrap_or(50.0))
+        .collect();
+    let signal_line: Vec<Option<f64>> = (0..values.len())
+        .map(|i| {
+            (i + 1 >= RSI_SIGNAL_PERIOD).then(|| {
+                values[i + 1 - RSI_SIGNAL_PERIOD..=i].iter().sum::<f64>() / RSI_SIGNAL_PERIOD as f64
+            })
+        })
+        .collect();
+    let crosses: Vec<(usize, bool)> = (1..points.len())
+        .filter_map(|i| {
+            let (prev_sig, curr_sig) = (signal_line[i - 1]?, signal_line[i]?);
+            if values[i - 1] < prev_sig && values[i] > curr_sig {
+                Some((i, true))
+            } else if values[i - 1] > prev_sig && values[i] < curr_sig {
+                Some((i, false))
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    let osc = |i: usize| points[i].osc.unwrap_or(0.0);
+    segment_divergences(points, &crosses, &values)
+        .into_iter()
+        .filter_map(|found| {
+            let (direction, _, curr) = found;
+            let confirmed = (curr.max(1)..=(curr + 8).min(points.len() - 1)).find(|&j| {
+                let (prev, now) = (osc(j - 1), osc(j));
+                match direction {
+                    Direction::Bullish => now > 0.0 || now > prev,
+                    Direction::Bearish => now < 0.0 || now < prev,
+                }
+            })?;
+            Some(divergence_signal(
+                points,
+                SignalKind::RsiDivergence,
+                found,
+                confirmed,
+            ))
+        })
+        .collect()
+}
+
+/// 與 `detectObvDivergence` 相同，以收盤價的局部高低點比較 OBV
+fn detect_obv(points: &[Point]) -> Vec<Signal> {
+    let period = OBV_LOCAL_PERIOD;
+    if points.len() < OBV_LOOKBACK + period * 2 {
+        return Vec::new();
+    }
+    let is_extreme = |index: usize, high: bool| {
+        if index < period || index + period >= points.len() {
+            return false;
+        }
+        let current = points[index].c;
+        (index - period..=index + period)
+            .filter(|&i| i != index)
+            .all(|i| {
+                if high {
+                    points[i].c < current
+                } else {
+                    points[i].c > current
+                }
+            })
+    };
+    let obv = |i: usize| points[i].obv.unwrap_or(0.0);
+
+    let mut signals = Vec::new();
+    for i in OBV_LOOKBACK + period..points.len() - period {
+        let current = &points[i];
+        for high in [true, false] {
+            if !is_extreme(i, high) {
+                continue;
+            }
+            let Some(j) = (i - OBV_LOOKBACK..i - period * 2).find(|&j| is_extreme(j, high)) else {
+                continue;
+            };
+            let prev = &points[j];
+            let (matched, strength, direction) = if high {
+                let close_rise = (current.c - prev.c) / prev.c;
+                let high_rise = (current.h - prev.h) / prev.h;
+                (
+                    (close_rise > OBV_MIN_PRICE_DIFF || high_rise > OBV_MIN_PRICE_DIFF)
+                        && obv(i) < obv(j),
+                    close_rise.abs() * 100.0,
+                    Direction::Bearish,
+                )
+            } else {
+                let close_drop = (prev.c - current.c) / prev.c;
+                (
+                    close_drop > OBV_MIN_PRICE_DIFF && obv(i) > obv(j),
+                    close_drop.abs() * 100.0,
+                    Direction::Bullish,
+                )
+            };
+            if matched {
+                signals.push(Signal {
+                    kind: SignalKind::ObvDivergence,
+                    direction,
+                    start_t: prev.t.clone(),
+                    end_t: current.t.clone(),
+                    strength,
+                });
+            }
+        }
+    }
+    signals
+}
+
+/// 與 `detectMaCrossDivergence` 相同，MA5 穿越 MA10 / MA20 且前兩根未交叉；
+/// 三根中任一根缺少 MA5 / MA10 / MA20 其中之一即兩種交叉都不判斷
+fn detect_ma_cross(points: &[Point]) -> Vec<Signal> {
+    let mut signals = Vec::new();
+    for i in 20..points.len() {
+        if points[i - 2..=i]
+            .iter()
+            .any(|p| p.ma5.is_none() || p.ma10.is_none() || p.ma20.is_none())
+        {
+            continue;
+        }
+        for kind in [SignalKind::Ma10Cross, SignalKind::Ma20Cross] {
+            let pair = |p: &Point| {
+                let long = match kind {
+                    SignalKind::Ma10Cross => p.ma10,
+                    _ => p.ma20,
+                };
+                Some((p.ma5?, long?))
+            };
+            let (Some((pp5, ppl)), Some((p5, pl)), Some((c5, cl))) =
+                (pair(&points[i - 2]), pair(&points[i - 1]), pair(&points[i]))
+            else {
+                continue;
+            };
+            let direction = if p5 < pl && c5 > cl && pp5 <= ppl {
+                Direction::Bullish
+            } else if p5 > pl && c5 < cl && pp5 >= ppl {
+                Direction::Bearish
+            } else {
+                continue;
+            };
+            signals.push(Signal {
+                kind,
+                direction,
+                start_t: points[i].t.clone(),
+                end_t: points[i].t.clone(),
+                strength: percent_change(cl, c5),
+            });
+        }
+    }
+    signals
+}
+
+/// 掃描整段日線，回傳依 end_t 排序的訊號
+fn detect(points: &[Point]) -> Vec<Signal> {
+    let mut signals = detect_kd(points);
+    signals.extend(detect_macd(points));
+    signals.extend(detect_rsi(points));
+    signals.extend(detect_obv(points));
+    signals.extend(detect_ma_cross(points));
+    signals.sort_by(|a, b| a.end_t.cmp(&b.end_t));
+    signals
+}
+
+/// 載入日線與指標；給定上次掃描點時只從其往前第 `SCAN_BARS` 根開始，歷史不足則載入全部
+async fn load_points(
+    pool: &SqlitePool,
+    stock_id: &str,
+    since: Option<&str>,
+) -> Result<Vec<Point>, String> {
+    sqlx::query_as(
+        "SELECT d.t, d.h, d.l, d.c, s.k, s.d, s.osc, s.obv, s.ma5, s.ma10, s.ma20
+         FROM daily_deal d
+         LEFT JOIN daily_skills s ON s.stock_id = d.stock_id AND s.t = d.t
+         WHERE d.stock_id = $1
+           AND d.t >= COALESCE((
+               SELECT t FROM daily_deal WHERE stock_id = $1 AND t <= $2
+               ORDER BY t DESC LIMIT 1 OFFSET $3
+           ), '')
+         ORDER BY d.t",
+    )
+    .bind(stock_id)
+    .bind(since)
+    .bind(SCAN_BARS - 1)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("[Signals] load deals error: {}", e))
+}
+
+async fn save_signals(
+    pool: &SqlitePool,
+    stock_id: &str,
+    from: &str,
+    signals: &[Signal],
+    scanned: Option<&str>,
+) -> Result<(), String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    sqlx::query("DELETE FROM daily_signals WHERE stock_id = $1 AND end_t >= $2")
+        .bind(stock_id)
+        .bind(from)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    for chunk in signals.chunks(500) {
+        let mut builder = QueryBuilder::new(
+            "INSERT OR REPLACE INTO daily_signals (stock_id, signal_type, direction, start_t, end_t, strength) ",
+        );
+        builder.push_values(chunk, |mut row, signal| {
+            row.push_bind(stock_id)
+                .push_bind(signal.kind.as_str())
+                .push_bind(signal.direction.as_str())
+                .push_bind(&signal.start_t)
+                .push_bind(&signal.end_t)
+                .push_bind(signal.strength);
+        });
+        builder
+            .build()
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("[Signals] save signals error: {}", e))?;
+    }
+    if let Some(t) = scanned {
+        sqlx::query("INSERT OR REPLACE INTO signal_scan (stock_id, t) VALUES ($1, $2)")
+            .bind(stock_id)
+            .bind(t)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("[Signals] save scan point error: {}", e))?;
+    }
+    writer::bump_versions(&mut *tx, &["daily_signals"]).await?;
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+/// 重新偵測單一股票的訊號並寫入 `daily_signals`。
+/// 從 `signal_scan` 記錄的上次掃描點往前 `SCAN_BARS` 根開始掃描，視窗前 `WARMUP_BARS` 根之前的訊號保持不動；
+/// 尚未掃描過或掃描點前的歷史不足一個視窗時，以完整歷史回補。
+pub async fn scan(pool: &SqlitePool, stock_id: &str) -> Result<usize, String> {
+    let last: Option<String> = sqlx::query_scalar("SELECT t FROM signal_scan WHERE stock_id = $1")
+        .bind(stock_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| format!("[Signals] load scan point error: {}", e))?;
+    let points = load_points(pool, stock_id, last.as_deref()).await?;
+
+    let window = last.as_deref().map_or(0, |last| {
+        points
+            .iter()
+            .take_while(|point| point.t.as_str() <= last)
+            .count()
+    });
+    let from = match points.get(WARMUP_BARS) {
+        Some(point) if window as i64 >= SCAN_BARS => point.t.clone(),
+        _ => String::new(),
+    };
+    let signals: Vec<Signal> = detect(&points)
+        .into_iter()
+        .filter(|signal| signal.end_t >= from)
+        .collect();
+    let scanned = points.last().map(|point| point.t.as_str());
+    save_signals(pool, stock_id, &from, &signals, scanned).await?;
+    Ok(signals.len())
+}
+
+/// 同步完成後掃描所有股票
+pub async fn on_sync_success(pool: &SqlitePool) -> Result<(), String> {
+    let stock_ids: Vec<String> = sqlx::query_scalar(&format!(
+        "SELECT stock_id FROM stock WHERE {}",
+        listing::SYNCED_TYPES_FILTER
+    ))
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let mut total = 0;
+    for stock_id in &stock_ids {
+        match scan(pool, stock_id).await {
+            Ok(count) => total += count,
+            Err(e) => log::warn!("[Signals] scan {} failed: {}", stock_id, e),
+        }
+    }
+    log::info!(
+        "[Signals] scanned {} stocks, wrote {} signals",
+        stock_ids.len(),
+        total
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::sqlite::connection;
+    use crate::types::{Bar, Timeframe};
+
+    /// `points` 為固定種子產生的日線與指標；`expected` 為前端 `src/utils/detect*.ts` 以相同輸入執行的結果 ([t, type])
+    const FIXTURE: &str = include_str!("../tests/fixtures/signals_parity.json");
+
+    #[derive(Deserialize)]
+    struct FixturePoint {
+        t: String,
+        h: f64,
+        l: f64,
+        c: f64,
+        k: Option<f64>,
+        d: Option<f64>,
+        osc: Option<f64>,
+        obv: Option<f64>,
+        ma5: Option<f64>,
+        ma10: Option<f64>,
+        ma20: Option<f64>,
+        rsi: Option<f64>,
+    }
+
+    /// 各偵測器的 [t, type]
+    type Expected = BTreeMap<String, Vec<(String, String)>>;
+
+    #[derive(Deserialize)]
+    struct Fixture {
+        points: Vec<FixturePoint>,
+        expected: Expected,
+    }
+
+    fn fixture() -> (Vec<Point>, Vec<Option<f64>>, Expected) {
+        let fixture: Fixture = serde_json::from_str(FIXTURE).unwrap();
+        let rsi = fixture.points.iter().map(|p| p.rsi).collect();
+        let points = fixture
+            .points
+            .into_iter()
+            .map(|p| Point {
+                t: p.t,
+                h: p.h,
+                l: p.l,
+                c: p.c,
+                k: p.k,
+                d: p.d,
+                osc: p.osc,
+                obv: p.obv,
+                ma5: p.ma5,
+                ma10: p.ma10,
+                ma20: p.ma20,
+            })
+            .collect();
+        (points, rsi, fixture.expected)
+    }
+
+    /// 前端訊號類型對應 (種類, 方向)，背離類型不分指標
+    fn ts_type(kind: SignalKind, name: &str) -> (SignalKind, Direction) {
+        match name {
+            "頂背離" => (kind, Direction::Bearish),
+            "底背離" => (kind, Direction::Bullish),
+            "金叉Ma10" => (SignalKind::Ma10Cross, Direction::Bullish),
+            "死叉Ma10" => (SignalKind::Ma10Cross, Direction::Bearish),
+            "金叉Ma20" => (SignalKind::Ma20Cross, Direction::Bullish),
+            "死叉Ma20" => (SignalKind::Ma20Cross, Direction::Bearish),
+            _ => panic!("unknown signal type {}", name),
+        }
+    }
+
+    fn assert_parity(detector: fn(&[Point]) -> Vec<Signal>, kind: SignalKind, key: &str) {
+        let (points, _, expected) = fixture();
+        let mut expected: Vec<(String, SignalKind, Direction)> = expected[key]
+            .iter()
+            .map(|(t, name)| {
+                let (kind, direction) = ts_type(kind, name);
+                (t.clone(), kind, direction)
+            })
+            .collect();
+        let mut actual: Vec<(String, SignalKind, Direction)> = detector(&points)
+            .into_iter()
+            .map(|signal| (signal.end_t, signal.kind, signal.direction))
+            .collect();
+        expected.sort_by(|a, b| a.0.cmp(&b.0).then(a.2.as_str().cmp(b.2.as_str())));
+        actual.sort_by(|a, b| a.0.cmp(&b.0).then(a.2.as_str().cmp(b.2.as_str())));
+        assert!(!expected.is_empty(), "fixture has no {} signals", key);
+        assert_eq!(actual, expected, "{} signals differ from TS", key);
+    }
+
+    #[test]
+    fn kd_matches_ts() {
+        assert_parity(detect_kd, SignalKind::KdDivergence, "kd");
+    }
+
+    #[test]
+    fn macd_matches_ts() {
+        assert_parity(detect_macd, SignalKind::MacdDivergence, "macd");
+    }
+
+    #[test]
+    fn rsi_matches_ts() {
+        assert_parity(detect_rsi, SignalKind::RsiDivergence, "rsi");
+    }
+
+    #[test]
+    fn obv_matches_ts() {
+        assert_parity(detect_obv, SignalKind::ObvDivergence, "obv");
+    }
+
+    #[test]
+    fn ma_cross_matches_ts() {
+        assert_parity(detect_ma_cross, SignalKind::Ma10Cross, "ma");
+    }
+
+    /// 前端的 RSI 輸入與 Rust 自行由收盤價計算的 RSI 一致
+    #[test]
+    fn rsi_input_matches_fixture() {
+        let (points, expected, _) = fixture();
+        let mut rsi = Rsi::new(RSI_PERIOD);
+        let actual: Vec<Option<f64>> = points.iter().map(|p| rsi.next(p.c)).collect();
+        assert_eq!(actual, expected);
+    }
+
+    fn flat_bars(range: std::ops::Range<usize>) -> Vec<Bar> {
+        range
+            .map(|i| Bar {
+                t: format!("{:08}", 20200101 + i),
+                o: 10.0,
+                h: 10.0,
+                l: 10.0,
+                c: 10.0,
+                v: 1000,
+            })
+            .collect()
+    }
+
+    async fn stale_signal(pool: &SqlitePool, t: &str) {
+        sqlx::query(
+            "INSERT INTO daily_signals (stock_id, signal_type, direction, start_t, end_t, strength)
+             VALUES ('2330', 'ma10_cross', 'bullish', $1, $1, 1.0)",
+        )
+        .bind(t)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    async fn remaining(pool: &SqlitePool) -> (Vec<String>, Option<String>) {
+        let signals = sqlx::query_scalar("SELECT end_t FROM daily_signals ORDER BY end_t")
+            .fetch_all(pool)
+            .await
+            .unwrap();
+        let scanned = sqlx::query_scalar("SELECT t FROM signal_scan WHERE stock_id = '2330'")
+            .fetch_optional(pool)
+            .await
+            .unwrap();
+        (signals, scanned)
+    }
+
+    #[test]
+    fn scan_resumes_from_last_scanned_bar() {
+        tauri::async_runtime::block_on(async {
+            let pool = connection::memory().await;
+            connection::migrate(&pool, ..).await;
+            writer::save_deals(&pool, Timeframe::Daily, "2330", &flat_bars(0..300))
+                .await
+                .unwrap();
+
+            // 已有舊訊號但尚未記錄掃描點，仍以完整歷史回補
+            stale_signal(&pool, "20200101").await;
+            scan(&pool, "2330").await.unwrap();
+            assert_eq!(
+                remaining(&pool).await,
+                (vec![], Some("20200400".to_string()))
+            );
+
+            // 沒有任何訊號也由掃描點往前 SCAN_BARS 根接續，視窗暖機段之前的訊號不動
+            writer::save_deals(&pool, Timeframe::Daily, "2330", &flat_bars(300..320))
+                .await
+                .unwrap();
+            stale_signal(&pool, "20200101").await;
+            stale_signal(&pool, "20200400").await;
+            scan(&pool, "2330").await.unwrap();
+            assert_eq!(
+                remaining(&pool).await,
+                (vec!["20200101".to_string()], Some("20200420".to_string()))
+            );
+        });
+    }
+}
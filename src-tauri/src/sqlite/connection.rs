@@ -0,0 +1,56 @@
+use std::path::{Path, PathBuf};
+
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use tauri::Manager;
+
+/// 與前端 `Database.load("sqlite:schoice.db")` 共用同一個檔案
+pub const DB_FILE: &str = "schoice.db";
+
+/// Rust 端共用的 SQLite 連線池，於 `setup` 時註冊為 managed state
+pub struct Db(pub SqlitePool);
+
+/// tauri_plugin_sql 以 app_config_dir 解析 `sqlite:` 路徑，這裡需保持一致
+pub fn db_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| format!("Failed to get app config directory: {}", e))?;
+    // 首次啟動時前端尚未載入資料庫，目錄可能還不存在
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    Ok(dir.join(DB_FILE))
+}
+
+pub async fn open(path: &Path) -> Result<SqlitePool, String> {
+    let options = SqliteConnectOptions::new()
+        .filename(path)
+        .create_if_missing(true);
+    SqlitePoolOptions::new()
+        .max_connections(4)
+        .connect_with(options)
+        .await
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))
+}
+
+/// 單一連線的記憶體資料庫 (多連線時每條連線各自一個資料庫)，供單元測試使用
+#[cfg(test)]
+pub async fn memory() -> SqlitePool {
+    SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .expect("open memory db")
+}
+
+/// 依序套用版本落在 `versions` 內的 migration
+#[cfg(test)]
+pub async fn migrate(pool: &SqlitePool, versions: impl std::ops::RangeBounds<i64>) {
+    for migration in super::migrations::value() {
+        if versions.contains(&migration.version) {
+            sqlx::raw_sql(migration.sql)
+                .execute(pool)
+                .await
+                .unwrap_or_else(|e| panic!("migration {}: {}", migration.version, e));
+        }
+    }
+}
@@ -610,5 +610,680 @@ pub fn value() -> Vec<Migration> {
             ",
             kind: MigrationKind::Up,
         },
+        Migration {
+            version: 16,
+            description: "add_adjustments_and_adjusted_daily_deal",
+            sql: "
+                CREATE TABLE adjustments (
+                    stock_id TEXT, -- 股票代號
+                    t TEXT, -- 除權息 / 減資恢復買賣日
+                    cash_dividend REAL DEFAULT 0, -- 每股現金股利
+                    share_ratio REAL DEFAULT 1, -- 除權後每股變為幾股 (配股 > 1，減資 < 1)
+                    prev_close REAL, -- 前一日收盤價
+                    ref_price REAL, -- 參考價，有值時優先使用
+                    source TEXT, -- twse / csv
+                    PRIMARY KEY (stock_id, t)
+                );
+
+                CREATE TABLE adjustment_factor (
+                    stock_id TEXT, -- 股票代號
+                    t_from TEXT, -- 區間起日 (含)
+                    t_to TEXT, -- 區間迄日 (不含)
+                    price_factor REAL, -- 價格還原乘數
+                    volume_factor REAL, -- 成交量還原乘數
+                    PRIMARY KEY (stock_id, t_from)
+                );
+
+                CREATE VIEW daily_adjusted_deal AS
+                SELECT
+                    d.stock_id,
+                    d.t,
+                    d.c * COALESCE(f.price_factor, 1) AS c,
+                    d.o * COALESCE(f.price_factor, 1) AS o,
+                    d.h * COALESCE(f.price_factor, 1) AS h,
+                    d.l * COALESCE(f.price_factor, 1) AS l,
+                    CAST(d.v * COALESCE(f.volume_factor, 1) AS INTEGER) AS v
+                FROM daily_deal d
+                LEFT JOIN adjustment_factor f
+                    ON f.stock_id = d.stock_id AND d.t >= f.t_from AND d.t < f.t_to;
+            ",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 17,
+            description: "add_indicator_state",
+            sql: "
+                CREATE TABLE indicator_state (
+                    stock_id TEXT, -- 股票代號
+                    timeframe TEXT, -- daily / weekly / hourly
+                    t TEXT, -- 狀態對應的最後一根 K 線時間
+                    version INTEGER, -- 狀態格式版本
+                    state TEXT, -- 指標引擎序列化後的 JSON
+                    PRIMARY KEY (stock_id, timeframe, t)
+                );
+            ",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 18,
+            description: "add_atr_cci_donchian_supertrend_mss_to_skills_tables",
+            sql: "
+                ALTER TABLE daily_skills ADD COLUMN atr REAL;
+                ALTER TABLE daily_skills ADD COLUMN cci REAL;
+                ALTER TABLE daily_skills ADD COLUMN donchian_ub REAL;
+                ALTER TABLE daily_skills ADD COLUMN donchian_ma REAL;
+                ALTER TABLE daily_skills ADD COLUMN donchian_lb REAL;
+                ALTER TABLE daily_skills ADD COLUMN supertrend REAL;
+                ALTER TABLE daily_skills ADD COLUMN supertrend_dir REAL;
+                ALTER TABLE daily_skills ADD COLUMN mss REAL;
+                ALTER TABLE weekly_skills ADD COLUMN atr REAL;
+                ALTER TABLE weekly_skills ADD COLUMN cci REAL;
+                ALTER TABLE weekly_skills ADD COLUMN donchian_ub REAL;
+                ALTER TABLE weekly_skills ADD COLUMN donchian_ma REAL;
+                ALTER TABLE weekly_skills ADD COLUMN donchian_lb REAL;
+                ALTER TABLE weekly_skills ADD COLUMN supertrend REAL;
+                ALTER TABLE weekly_skills ADD COLUMN supertrend_dir REAL;
+                ALTER TABLE weekly_skills ADD COLUMN mss REAL;
+                ALTER TABLE hourly_skills ADD COLUMN atr REAL;
+                ALTER TABLE hourly_skills ADD COLUMN cci REAL;
+                ALTER TABLE hourly_skills ADD COLUMN donchian_ub REAL;
+                ALTER TABLE hourly_skills ADD COLUMN donchian_ma REAL;
+                ALTER TABLE hourly_skills ADD COLUMN donchian_lb REAL;
+                ALTER TABLE hourly_skills ADD COLUMN supertrend REAL;
+                ALTER TABLE hourly_skills ADD COLUMN supertrend_dir REAL;
+                ALTER TABLE hourly_skills ADD COLUMN mss REAL;
+            ",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 19,
+            description: "add_daily_signals",
+            sql: "
+                CREATE TABLE daily_signals (
+                    stock_id TEXT, -- 股票代號
+                    signal_type TEXT, -- kd_divergence / macd_divergence / rsi_divergence / obv_divergence / ma10_cross / ma20_cross
+                    direction TEXT, -- bullish (底背離、黃金交叉) / bearish (頂背離、死亡交叉)
+                    start_t TEXT, -- 前一個轉折點日期，均線交叉與 end_t 相同
+                    end_t TEXT, -- 訊號成立日期
+                    strength REAL, -- 背離為兩轉折點間價格變化幅度 (%)，均線交叉為 MA5 與長均線乖離 (%)
+                    PRIMARY KEY (stock_id, signal_type, direction, end_t)
+                );
+
+                CREATE INDEX idx_daily_signals_end_t ON daily_signals (end_t, signal_type);
+            ",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 20,
+            description: "add_alert_history",
+            sql: "
+                CREATE TABLE alert_history (
+                    alarm_id TEXT, -- 警示條件 prompt_id
+                    alarm_name TEXT, -- 觸發當下的條件名稱
+                    stock_id TEXT, -- 股票代號
+                    t TEXT, -- 觸發的交易日
+                    notified INTEGER, -- 是否送出通知 (前一交易日已觸發則不重複通知)
+                    fired_at TEXT, -- 評估時間
+                    PRIMARY KEY (alarm_id, stock_id, t)
+                );
+            ",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 21,
+            description: "add_local_watchlists",
+            sql: "
+                CREATE TABLE watchlist (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    name TEXT NOT NULL, -- 清單名稱
+                    position INTEGER DEFAULT 0, -- 排序
+                    created_at TEXT -- 建立時間
+                );
+
+                CREATE TABLE watchlist_item (
+                    watchlist_id INTEGER, -- 所屬清單
+                    stock_id TEXT, -- 股票代號
+                    note TEXT, -- 備註
+                    target_price REAL, -- 目標價
+                    stop_price REAL, -- 停損價
+                    strategy_name TEXT, -- 加入時的策略名稱
+                    added_date TEXT, -- 加入時間
+                    position INTEGER DEFAULT 0, -- 清單內排序
+                    PRIMARY KEY (watchlist_id, stock_id)
+                );
+
+                CREATE TABLE watchlist_tag (
+                    watchlist_id INTEGER, -- 所屬清單
+                    stock_id TEXT, -- 股票代號
+                    tag TEXT, -- 標籤
+                    PRIMARY KEY (watchlist_id, stock_id, tag)
+                );
+
+                INSERT INTO watchlist (name, position, created_at) VALUES ('預設', 0, datetime('now', 'localtime'));
+            ",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 22,
+            description: "add_portfolio_and_trade",
+            sql: "
+                CREATE TABLE portfolio (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    name TEXT NOT NULL, -- 帳戶名稱
+                    cash REAL DEFAULT 0, -- 初始資金
+                    fee_discount REAL DEFAULT 1, -- 手續費折扣 (0.6 為六折)
+                    min_fee REAL DEFAULT 20, -- 最低手續費
+                    created_at TEXT -- 建立時間
+                );
+
+                CREATE TABLE trade (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    portfolio_id INTEGER, -- 所屬帳戶
+                    stock_id TEXT, -- 股票代號，帳戶層級的費用可為 NULL
+                    t TEXT, -- 交易日 YYYYMMDD
+                    kind TEXT, -- buy / sell / dividend / fee / tax
+                    quantity INTEGER DEFAULT 0, -- 股數 (股票股利為配發股數)
+                    price REAL DEFAULT 0, -- 成交價
+                    amount REAL DEFAULT 0, -- 現金股利或其他費用 / 稅負金額
+                    fee REAL DEFAULT 0, -- 手續費
+                    tax REAL DEFAULT 0, -- 證券交易稅
+                    day_trade INTEGER DEFAULT 0, -- 是否為現股當沖
+                    note TEXT, -- 備註
+                    created_at TEXT -- 建立時間
+                );
+
+                INSERT INTO portfolio (name, cash, created_at) VALUES ('預設帳戶', 0, datetime('now', 'localtime'));
+            ",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 23,
+            description: "add_security_type_to_stock",
+            sql: "
+                ALTER TABLE stock
+                    ADD COLUMN security_type TEXT NOT NULL DEFAULT 'stock'; -- 證券類別 stock / etf / etn / warrant / tdr / preferred / reit / other
+            ",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 24,
+            description: "create_stock_history",
+            sql: "
+                CREATE TABLE stock_history (
+                    stock_id TEXT, -- 股票代號
+                    stock_name TEXT, -- 股票名稱
+                    market_type TEXT, -- 上市/上櫃
+                    industry_group TEXT, -- 產業別
+                    security_type TEXT, -- 證券類別
+                    valid_from TEXT, -- 生效日 YYYYMMDD (含)，上市日不明時為 00000000
+                    valid_to TEXT, -- 失效日 YYYYMMDD (不含)，NULL 表示目前仍有效
+                    PRIMARY KEY (stock_id, valid_from)
+                );
+
+                CREATE INDEX idx_stock_history_valid ON stock_history (valid_from, valid_to);
+            ",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 25,
+            description: "create_long_format_fundamental_tables",
+            sql: "
+                CREATE TABLE monthly_revenue (
+                    stock_id TEXT, -- 股票代號
+                    period TEXT, -- 營收月份 YYYYMM
+                    revenue REAL, -- 當月營收 (仟元)
+                    mom REAL, -- 月增率 (%)
+                    yoy REAL, -- 年增率 (%)
+                    yoy_acc REAL, -- 累計營收年增率 (%)
+                    PRIMARY KEY (stock_id, period),
+                    CONSTRAINT fk_stock_monthly_revenue FOREIGN KEY (stock_id) REFERENCES stock(stock_id) ON DELETE CASCADE
+                );
+
+                CREATE TABLE quarterly_eps (
+                    stock_id TEXT, -- 股票代號
+                    year INTEGER, -- 年度
+                    quarter INTEGER, -- 季別 1~4，0 為全年度 EPS
+                    eps REAL, -- 每股盈餘 (元)
+                    PRIMARY KEY (stock_id, year, quarter),
+                    CONSTRAINT fk_stock_quarterly_eps FOREIGN KEY (stock_id) REFERENCES stock(stock_id) ON DELETE CASCADE
+                );
+
+                CREATE TABLE weekly_holder_ratio (
+                    stock_id TEXT, -- 股票代號
+                    t TEXT, -- 集保統計日 YYYYMMDD
+                    foreign_ratio REAL, -- 外資持股比例 (%)
+                    big_investor_ratio REAL, -- 千張大戶持股比例 (%)
+                    PRIMARY KEY (stock_id, t),
+                    CONSTRAINT fk_stock_weekly_holder_ratio FOREIGN KEY (stock_id) REFERENCES stock(stock_id) ON DELETE CASCADE
+                );
+
+                -- 舊寬表的營收只有月增率、年增率與累計年增率，營收金額由之後的同步補上；
+                -- 期別容許 `2026/9`、`2026-09` 與民國年 `115/09`
+                WITH months (stock_id, name, mom, yoy, yoy_acc) AS (
+                    SELECT stock_id, trim(replace(revenue_recent_m1_name, '-', '/')), revenue_recent_m1_mom, revenue_recent_m1_yoy, revenue_recent_m1_yoy_acc FROM recent_fundamental
+                    UNION ALL SELECT stock_id, trim(replace(revenue_recent_m2_name, '-', '/')), revenue_recent_m2_mom, revenue_recent_m2_yoy, revenue_recent_m2_yoy_acc FROM recent_fundamental
+                    UNION ALL SELECT stock_id, trim(replace(revenue_recent_m3_name, '-', '/')), revenue_recent_m3_mom, revenue_recent_m3_yoy, revenue_recent_m3_yoy_acc FROM recent_fundamental
+                    UNION ALL SELECT stock_id, trim(replace(revenue_recent_m4_name, '-', '/')), revenue_recent_m4_mom, revenue_recent_m4_yoy, revenue_recent_m4_yoy_acc FROM recent_fundamental
+                ), parsed AS (
+                    SELECT stock_id,
+                           CAST(substr(name, 1, instr(name, '/') - 1) AS INTEGER) AS year,
+                           CAST(substr(name, instr(name, '/') + 1) AS INTEGER) AS month,
+                           mom, yoy, yoy_acc
+                    FROM months WHERE instr(name, '/') > 1
+                )
+                INSERT OR IGNORE INTO monthly_revenue (stock_id, period, mom, yoy, yoy_acc)
+                SELECT stock_id, printf('%04d%02d', CASE WHEN year < 1911 THEN year + 1911 ELSE year END, month), mom, yoy, yoy_acc
+                FROM parsed WHERE year > 0 AND month BETWEEN 1 AND 12;
+
+                WITH quarters (stock_id, name, eps) AS (
+                    SELECT stock_id, trim(eps_recent_q1_name), eps_recent_q1 FROM recent_fundamental
+                    UNION ALL SELECT stock_id, trim(eps_recent_q2_name), eps_recent_q2 FROM recent_fundamental
+                    UNION ALL SELECT stock_id, trim(eps_recent_q3_name), eps_recent_q3 FROM recent_fundamental
+                    UNION ALL SELECT stock_id, trim(eps_recent_q4_name), eps_recent_q4 FROM recent_fundamental
+                )
+                INSERT OR IGNORE INTO quarterly_eps (stock_id, year, quarter, eps)
+                SELECT stock_id, CAST(substr(name, 1, 4) AS INTEGER), CAST(substr(name, 7, 1) AS INTEGER), eps
+                FROM quarters WHERE name LIKE '____ Q_' AND eps IS NOT NULL;
+
+                WITH years (stock_id, name, eps) AS (
+                    SELECT stock_id, trim(eps_recent_y1_name), eps_recent_y1 FROM recent_fundamental
+                    UNION ALL SELECT stock_id, trim(eps_recent_y2_name), eps_recent_y2 FROM recent_fundamental
+                    UNION ALL SELECT stock_id, trim(eps_recent_y3_name), eps_recent_y3 FROM recent_fundamental
+                    UNION ALL SELECT stock_id, trim(eps_recent_y4_name), eps_recent_y4 FROM recent_fundamental
+                )
+                INSERT OR IGNORE INTO quarterly_eps (stock_id, year, quarter, eps)
+                SELECT stock_id, CAST(name AS INTEGER), 0, eps
+                FROM years WHERE length(name) = 4 AND eps IS NOT NULL;
+
+                WITH weeks (stock_id, name, foreign_ratio, big_investor_ratio) AS (
+                    SELECT stock_id, trim(replace(recent_w1_name, '-', '/')), recent_w1_foreign_ratio, recent_w1_big_investor_ratio FROM investor_positions
+                    UNION ALL SELECT stock_id, trim(replace(recent_w2_name, '-', '/')), recent_w2_foreign_ratio, recent_w2_big_investor_ratio FROM investor_positions
+                    UNION ALL SELECT stock_id, trim(replace(recent_w3_name, '-', '/')), recent_w3_foreign_ratio, recent_w3_big_investor_ratio FROM investor_positions
+                    UNION ALL SELECT stock_id, trim(replace(recent_w4_name, '-', '/')), recent_w4_foreign_ratio, recent_w4_big_investor_ratio FROM investor_positions
+                )
+                INSERT OR IGNORE INTO weekly_holder_ratio (stock_id, t, foreign_ratio, big_investor_ratio)
+                SELECT stock_id, replace(name, '/', ''), foreign_ratio, big_investor_ratio
+                FROM weeks WHERE name LIKE '____/__/__';
+
+                DROP TABLE recent_fundamental;
+                DROP TABLE investor_positions;
+
+                CREATE VIEW recent_fundamental AS
+                SELECT ids.stock_id,
+                       m.revenue_recent_m1_mom,
+                       m.revenue_recent_m1_yoy,
+                       m.revenue_recent_m1_yoy_acc,
+                       m.revenue_recent_m1_name,
+                       m.revenue_recent_m2_mom,
+                       m.revenue_recent_m2_yoy,
+                       m.revenue_recent_m2_yoy_acc,
+                       m.revenue_recent_m2_name,
+                       m.revenue_recent_m3_mom,
+                       m.revenue_recent_m3_yoy,
+                       m.revenue_recent_m3_yoy_acc,
+                       m.revenue_recent_m3_name,
+                       m.revenue_recent_m4_mom,
+                       m.revenue_recent_m4_yoy,
+                       m.revenue_recent_m4_yoy_acc,
+                       m.revenue_recent_m4_name,
+                       q.eps_recent_q1,
+                       q.eps_recent_q1_name,
+                       q.eps_recent_q2,
+                       q.eps_recent_q2_name,
+                       q.eps_recent_q3,
+                       q.eps_recent_q3_name,
+                       q.eps_recent_q4,
+                       q.eps_recent_q4_name,
+                       y.eps_recent_y1,
+                       y.eps_recent_y1_name,
+                       y.eps_recent_y2,
+                       y.eps_recent_y2_name,
+                       y.eps_recent_y3,
+                       y.eps_recent_y3_name,
+                       y.eps_recent_y4,
+                       y.eps_recent_y4_name,
+                       m.revenue_recent_m1,
+                       m.revenue_recent_m2,
+                       m.revenue_recent_m3,
+                       m.revenue_recent_m4
+                FROM (SELECT stock_id FROM monthly_revenue UNION SELECT stock_id FROM quarterly_eps) ids
+                LEFT JOIN (
+                    SELECT stock_id,
+                           MAX(CASE WHEN rn = 1 THEN mom END) AS revenue_recent_m1_mom,
+                           MAX(CASE WHEN rn = 1 THEN yoy END) AS revenue_recent_m1_yoy,
+                           MAX(CASE WHEN rn = 1 THEN yoy_acc END) AS revenue_recent_m1_yoy_acc,
+                           MAX(CASE WHEN rn = 1 THEN substr(period, 1, 4) || '/' || substr(period, 5, 2) END) AS revenue_recent_m1_name,
+                           MAX(CASE WHEN rn = 1 THEN revenue END) AS revenue_recent_m1,
+                           MAX(CASE WHEN rn = 2 THEN mom END) AS revenue_recent_m2_mom,
+                           MAX(CASE WHEN rn = 2 THEN yoy END) AS revenue_recent_m2_yoy,
+                           MAX(CASE WHEN rn = 2 THEN yoy_acc END) AS revenue_recent_m2_yoy_acc,
+                           MAX(CASE WHEN rn = 2 THEN substr(period, 1, 4) || '/' || substr(period, 5, 2) END) AS revenue_recent_m2_name,
+                           MAX(CASE WHEN rn = 2 THEN revenue END) AS revenue_recent_m2,
+                           MAX(CASE WHEN rn = 3 THEN mom END) AS revenue_recent_m3_mom,
+                           MAX(CASE WHEN rn = 3 THEN yoy END) AS revenue_recent_m3_yoy,
+                           MAX(CASE WHEN rn = 3 THEN yoy_acc END) AS revenue_recent_m3_yoy_acc,
+                           MAX(CASE WHEN rn = 3 THEN substr(period, 1, 4) || '/' || substr(period, 5, 2) END) AS revenue_recent_m3_name,
+                           MAX(CASE WHEN rn = 3 THEN revenue END) AS revenue_recent_m3,
+                           MAX(CASE WHEN rn = 4 THEN mom END) AS revenue_recent_m4_mom,
+                           MAX(CASE WHEN rn = 4 THEN yoy END) AS revenue_recent_m4_yoy,
+                           MAX(CASE WHEN rn = 4 THEN yoy_acc END) AS revenue_recent_m4_yoy_acc,
+                           MAX(CASE WHEN rn = 4 THEN substr(period, 1, 4) || '/' || substr(period, 5, 2) END) AS revenue_recent_m4_name,
+                           MAX(CASE WHEN rn = 4 THEN revenue END) AS revenue_recent_m4
+                    FROM (SELECT *, ROW_NUMBER() OVER (PARTITION BY stock_id ORDER BY period DESC) AS rn FROM monthly_revenue)
+                    WHERE rn <= 4 GROUP BY stock_id
+                ) m ON m.stock_id = ids.stock_id
+                LEFT JOIN (
+                    SELECT stock_id,
+                           MAX(CASE WHEN rn = 1 THEN eps END) AS eps_recent_q1,
+                           MAX(CASE WHEN rn = 1 THEN year || ' Q' || quarter END) AS eps_recent_q1_name,
+                           MAX(CASE WHEN rn = 2 THEN eps END) AS eps_recent_q2,
+                           MAX(CASE WHEN rn = 2 THEN year || ' Q' || quarter END) AS eps_recent_q2_name,
+                           MAX(CASE WHEN rn = 3 THEN eps END) AS eps_recent_q3,
+                           MAX(CASE WHEN rn = 3 THEN year || ' Q' || quarter END) AS eps_recent_q3_name,
+                           MAX(CASE WHEN rn = 4 THEN eps END) AS eps_recent_q4,
+                           MAX(CASE WHEN rn = 4 THEN year || ' Q' || quarter END) AS eps_recent_q4_name
+                    FROM (SELECT *, ROW_NUMBER() OVER (PARTITION BY stock_id ORDER BY year DESC, quarter DESC) AS rn
+                          FROM quarterly_eps WHERE quarter > 0)
+                    WHERE rn <= 4 GROUP BY stock_id
+                ) q ON q.stock_id = ids.stock_id
+                LEFT JOIN (
+                    SELECT stock_id,
+                           MAX(CASE WHEN rn = 1 THEN eps END) AS eps_recent_y1,
+                           MAX(CASE WHEN rn = 1 THEN CAST(year AS TEXT) END) AS eps_recent_y1_name,
+                           MAX(CASE WHEN rn = 2 THEN eps END) AS eps_recent_y2,
+                           MAX(CASE WHEN rn = 2 THEN CAST(year AS TEXT) END) AS eps_recent_y2_name,
+                           MAX(CASE WHEN rn = 3 THEN eps END) AS eps_recent_y3,
+                           MAX(CASE WHEN rn = 3 THEN CAST(year AS TEXT) END) AS eps_recent_y3_name,
+                           MAX(CASE WHEN rn = 4 THEN eps END) AS eps_recent_y4,
+                           MAX(CASE WHEN rn = 4 THEN CAST(year AS TEXT) END) AS eps_recent_y4_name
+                    FROM (SELECT *, ROW_NUMBER() OVER (PARTITION BY stock_id ORDER BY year DESC) AS rn
+                          FROM quarterly_eps WHERE quarter = 0)
+                    WHERE rn <= 4 GROUP BY stock_id
+                ) y ON y.stock_id = ids.stock_id;
+
+                CREATE VIEW investor_positions AS
+                SELECT stock_id,
+                       MAX(CASE WHEN rn = 1 THEN foreign_ratio END) AS recent_w1_foreign_ratio,
+                       MAX(CASE WHEN rn = 1 THEN big_investor_ratio END) AS recent_w1_big_investor_ratio,
+                       MAX(CASE WHEN rn = 1 THEN substr(t, 1, 4) || '/' || substr(t, 5, 2) || '/' || substr(t, 7, 2) END) AS recent_w1_name,
+                       MAX(CASE WHEN rn = 2 THEN foreign_ratio END) AS recent_w2_foreign_ratio,
+                       MAX(CASE WHEN rn = 2 THEN big_investor_ratio END) AS recent_w2_big_investor_ratio,
+                       MAX(CASE WHEN rn = 2 THEN substr(t, 1, 4) || '/' || substr(t, 5, 2) || '/' || substr(t, 7, 2) END) AS recent_w2_name,
+                       MAX(CASE WHEN rn = 3 THEN foreign_ratio END) AS recent_w3_foreign_ratio,
+                       MAX(CASE WHEN rn = 3 THEN big_investor_ratio END) AS recent_w3_big_investor_ratio,
+                       MAX(CASE WHEN rn = 3 THEN substr(t, 1, 4) || '/' || substr(t, 5, 2) || '/' || substr(t, 7, 2) END) AS recent_w3_name,
+                       MAX(CASE WHEN rn = 4 THEN foreign_ratio END) AS recent_w4_foreign_ratio,
+                       MAX(CASE WHEN rn = 4 THEN big_investor_ratio END) AS recent_w4_big_investor_ratio,
+                       MAX(CASE WHEN rn = 4 THEN substr(t, 1, 4) || '/' || substr(t, 5, 2) || '/' || substr(t, 7, 2) END) AS recent_w4_name
+                FROM (SELECT *, ROW_NUMBER() OVER (PARTITION BY stock_id ORDER BY t DESC) AS rn FROM weekly_holder_ratio)
+                WHERE rn <= 4
+                GROUP BY stock_id;
+            ",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 26,
+            description: "create_daily_institutional_and_margin",
+            sql: "
+                CREATE TABLE daily_institutional (
+                    stock_id TEXT, -- 股票代號
+                    t TEXT, -- 交易日 YYYYMMDD
+                    foreign_net INTEGER DEFAULT 0, -- 外資及陸資 (不含外資自營商) 買賣超股數
+                    trust_net INTEGER DEFAULT 0, -- 投信買賣超股數
+                    dealer_net INTEGER DEFAULT 0, -- 自營商 (自行買賣 + 避險) 買賣超股數
+                    total_net INTEGER DEFAULT 0, -- 三大法人買賣超股數
+                    PRIMARY KEY (stock_id, t)
+                );
+
+                CREATE TABLE daily_margin (
+                    stock_id TEXT, -- 股票代號
+                    t TEXT, -- 交易日 YYYYMMDD
+                    margin_buy INTEGER DEFAULT 0, -- 融資買進 (張)
+                    margin_sell INTEGER DEFAULT 0, -- 融資賣出 (張)
+                    margin_balance INTEGER DEFAULT 0, -- 融資餘額 (張)
+                    short_sell INTEGER DEFAULT 0, -- 融券賣出 (張)
+                    short_buy INTEGER DEFAULT 0, -- 融券買進 (張)
+                    short_balance INTEGER DEFAULT 0, -- 融券餘額 (張)
+                    PRIMARY KEY (stock_id, t)
+                );
+
+                CREATE INDEX idx_daily_institutional_t ON daily_institutional (t);
+                CREATE INDEX idx_daily_margin_t ON daily_margin (t);
+            ",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 27,
+            description: "create_minute_deal",
+            sql: "
+                CREATE TABLE minute_deal (
+                    stock_id TEXT, -- 股票代號
+                    ts TEXT, -- 分鐘 YYYYMMDDHHmm
+                    c REAL, -- 收盤價
+                    o REAL, -- 開盤價
+                    h REAL, -- 最高價
+                    l REAL, -- 最低價
+                    v INTEGER, -- 成交量
+                    PRIMARY KEY (stock_id, ts)
+                );
+
+                CREATE INDEX idx_minute_deal_ts ON minute_deal (ts);
+            ",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 28,
+            description: "create_market_breadth",
+            sql: "
+                CREATE TABLE market_breadth (
+                    t TEXT PRIMARY KEY, -- 交易日 YYYYMMDD
+                    stock_count INTEGER DEFAULT 0, -- 有前一日收盤價可比較的股票數
+                    advancers INTEGER DEFAULT 0, -- 上漲家數
+                    decliners INTEGER DEFAULT 0, -- 下跌家數
+                    unchanged INTEGER DEFAULT 0, -- 平盤家數
+                    above_ma20_ratio REAL, -- 收盤價站上 MA20 的比例 (%)
+                    above_ma60_ratio REAL, -- 收盤價站上 MA60 的比例 (%)
+                    new_highs INTEGER DEFAULT 0, -- 收盤創 240 日新高家數
+                    new_lows INTEGER DEFAULT 0, -- 收盤創 240 日新低家數
+                    up_volume INTEGER DEFAULT 0, -- 上漲股票成交量合計
+                    down_volume INTEGER DEFAULT 0, -- 下跌股票成交量合計
+                    up_down_volume_ratio REAL -- 上漲量 / 下跌量
+                );
+            ",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 29,
+            description: "create_industry_index_and_rank",
+            sql: "
+                CREATE TABLE industry_index (
+                    industry_group TEXT, -- 產業別，全市場為大盤基準
+                    t TEXT, -- 交易日 YYYYMMDD
+                    members INTEGER DEFAULT 0, -- 成分股數
+                    equal_return REAL, -- 等權當日報酬 (%)
+                    cap_return REAL, -- 市值加權當日報酬 (%)
+                    equal_index REAL, -- 等權指數 (起始 100)
+                    cap_index REAL, -- 市值加權指數 (起始 100)
+                    relative_strength REAL, -- 市值加權 20 日漲幅相對全市場 (x 100)
+                    rs_percentile REAL, -- 當日各產業相對強弱百分位 (0-100)
+                    PRIMARY KEY (industry_group, t)
+                );
+
+                CREATE TABLE industry_rank (
+                    stock_id TEXT, -- 股票代號
+                    t TEXT, -- 交易日 YYYYMMDD
+                    industry_group TEXT, -- 當日所屬產業別
+                    return_20 REAL, -- 20 日報酬 (%)
+                    group_percentile REAL, -- 20 日報酬在產業內的百分位 (0-100)
+                    group_relative_strength REAL, -- 所屬產業的相對強弱
+                    group_rs_percentile REAL, -- 所屬產業的相對強弱百分位
+                    PRIMARY KEY (stock_id, t)
+                );
+
+                CREATE INDEX idx_industry_index_t ON industry_index (t);
+                CREATE INDEX idx_industry_rank_t ON industry_rank (t);
+            ",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 30,
+            description: "create_data_version",
+            sql: "
+                CREATE TABLE data_version (
+                    table_name TEXT PRIMARY KEY, -- 資料表名稱
+                    version INTEGER DEFAULT 0 -- 寫入端每批提交時遞增，選股結果快取以此判斷是否過期
+                );
+
+                INSERT INTO data_version (table_name) VALUES
+                    ('daily_deal'),
+                    ('daily_skills'),
+                    ('weekly_deal'),
+                    ('weekly_skills'),
+                    ('hourly_deal'),
+                    ('hourly_skills'),
+                    ('stock'),
+                    ('stock_history'),
+                    ('financial_metric'),
+                    ('monthly_revenue'),
+                    ('quarterly_eps'),
+                    ('weekly_holder_ratio'),
+                    ('daily_institutional'),
+                    ('daily_margin'),
+                    ('market_breadth'),
+                    ('industry_rank'),
+                    ('daily_signals'),
+                    ('adjustment_factor'),
+                    ('industry_index');
+            ",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 31,
+            description: "add_signal_scan",
+            sql: "
+                CREATE TABLE signal_scan (
+                    stock_id TEXT PRIMARY KEY, -- 股票代號
+                    t TEXT -- 上次掃描到的最後一根日線，下次由此往前 SCAN_BARS 根接續
+                );
+            ",
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 32,
+            description: "add_watchlist_synced",
+            sql: "
+                CREATE TABLE watchlist_synced (
+                    stock_id TEXT PRIMARY KEY -- 上次與雲端 watch_stock 對帳後兩邊都有的股票，用以判斷哪一邊刪除
+                );
+            ",
+            kind: MigrationKind::Up,
+        },
     ]
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::sqlite::connection;
+
+    async fn count(pool: &sqlx::SqlitePool, sql: &str) -> i64 {
+        sqlx::query_scalar(sql).fetch_one(pool).await.unwrap()
+    }
+
+    #[test]
+    fn long_format_migration_keeps_wide_rows() {
+        tauri::async_runtime::block_on(async {
+            let pool = connection::memory().await;
+            connection::migrate(&pool, ..=24).await;
+            for sql in [
+                "INSERT INTO stock (stock_id, stock_name) VALUES ('2330', '台積電'), ('2317', '鴻海')",
+                "INSERT INTO recent_fundamental (
+                     stock_id,
+                     revenue_recent_m1_name, revenue_recent_m1_mom, revenue_recent_m1_yoy, revenue_recent_m1_yoy_acc,
+                     revenue_recent_m2_name, revenue_recent_m2_mom, revenue_recent_m2_yoy, revenue_recent_m2_yoy_acc,
+                     revenue_recent_m3_name, revenue_recent_m3_mom, revenue_recent_m3_yoy, revenue_recent_m3_yoy_acc,
+                     revenue_recent_m4_name, revenue_recent_m4_mom,
+                     eps_recent_q1_name, eps_recent_q1, eps_recent_q2_name, eps_recent_q2,
+                     eps_recent_q3_name, eps_recent_q3, eps_recent_q4_name, eps_recent_q4,
+                     eps_recent_y1_name, eps_recent_y1, eps_recent_y2_name, eps_recent_y2)
+                 VALUES ('2330',
+                     '2026/09', 1.5, 20.1, 30.2,
+                     '2026/8', -2.5, 18.0, 29.9,
+                     '115-07', 3.0, 15.5, 28.0,
+                     NULL, 4.0,
+                     '2026 Q2', 15.36, '2026 Q1', 13.94, '2025 Q4', 14.45, '2025 Q3', 12.55,
+                     '2025', 45.25, '2024', 39.2)",
+                "INSERT INTO recent_fundamental (stock_id) VALUES ('2317')",
+                "INSERT INTO investor_positions (
+                     stock_id, recent_w1_name, recent_w1_foreign_ratio, recent_w1_big_investor_ratio,
+                     recent_w2_name, recent_w2_foreign_ratio, recent_w2_big_investor_ratio)
+                 VALUES ('2330', '2026/10/17', 72.1, 88.5, '2026-10-09', 72.0, 88.4)",
+            ] {
+                sqlx::query(sql).execute(&pool).await.unwrap();
+            }
+            let months = count(
+                &pool,
+                "SELECT SUM((revenue_recent_m1_name IS NOT NULL) + (revenue_recent_m2_name IS NOT NULL)
+                          + (revenue_recent_m3_name IS NOT NULL) + (revenue_recent_m4_name IS NOT NULL))
+                 FROM recent_fundamental",
+            )
+            .await;
+            let eps = count(
+                &pool,
+                "SELECT SUM((eps_recent_q1 IS NOT NULL) + (eps_recent_q2 IS NOT NULL) + (eps_recent_q3 IS NOT NULL)
+                          + (eps_recent_q4 IS NOT NULL) + (eps_recent_y1 IS NOT NULL) + (eps_recent_y2 IS NOT NULL)
+                          + (eps_recent_y3 IS NOT NULL) + (eps_recent_y4 IS NOT NULL))
+                 FROM recent_fundamental",
+            )
+            .await;
+            let weeks = count(
+                &pool,
+                "SELECT SUM((recent_w1_name IS NOT NULL) + (recent_w2_name IS NOT NULL)
+                          + (recent_w3_name IS NOT NULL) + (recent_w4_name IS NOT NULL))
+                 FROM investor_positions",
+            )
+            .await;
+            assert_eq!((months, eps, weeks), (3, 6, 2));
+
+            connection::migrate(&pool, 25..=25).await;
+            assert_eq!(count(&pool, "SELECT COUNT(*) FROM monthly_revenue").await, months);
+            assert_eq!(count(&pool, "SELECT COUNT(*) FROM quarterly_eps").await, eps);
+            assert_eq!(count(&pool, "SELECT COUNT(*) FROM weekly_holder_ratio").await, weeks);
+
+            let revenue: Vec<(String, f64, f64, f64)> = sqlx::query_as(
+                "SELECT period, mom, yoy, yoy_acc FROM monthly_revenue WHERE stock_id = '2330' ORDER BY period DESC",
+            )
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+            assert_eq!(
+                revenue,
+                [
+                    ("202609".to_string(), 1.5, 20.1, 30.2),
+                    ("202608".to_string(), -2.5, 18.0, 29.9),
+                    ("202607".to_string(), 3.0, 15.5, 28.0),
+                ]
+            );
+            // 寬表 view 與遷移前的欄位一致
+            let wide: (String, f64, String, f64, String, f64) = sqlx::query_as(
+                "SELECT r.revenue_recent_m3_name, r.revenue_recent_m3_yoy, r.eps_recent_q4_name, r.eps_recent_q4,
+                        p.recent_w2_name, p.recent_w2_big_investor_ratio
+                 FROM recent_fundamental r JOIN investor_positions p ON p.stock_id = r.stock_id
+                 WHERE r.stock_id = '2330'",
+            )
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            assert_eq!(
+                wide,
+                (
+                    "2026/07".to_string(),
+                    15.5,
+                    "2025 Q3".to_string(),
+                    12.55,
+                    "2026/10/09".to_string(),
+                    88.4
+                )
+            );
+        });
+    }
+}
@@ -1 +1,3 @@
+pub mod connection;
 pub mod migrations;
+pub mod writer;
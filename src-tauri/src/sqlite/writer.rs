@@ -0,0 +1,203 @@
+use sqlx::{Executor, QueryBuilder, Sqlite, SqlitePool};
+
+use crate::indicators::{SkillsRow, SKILLS_COLUMNS};
+use crate::types::{Bar, Timeframe};
+
+/// 寬表每批筆數，與前端 `executeBatch` 相同避免超過 SQLite 參數上限
+const DEAL_BATCH: usize = 200;
+const SKILLS_BATCH: usize = 50;
+
+/// 遞增資料表的寫入版本，選股結果快取以此判斷是否過期；每批寫入在提交前呼叫一次
+pub async fn bump_versions<'e, E>(executor: E, tables: &[&str]) -> Result<(), String>
+where
+    E: Executor<'e, Database = Sqlite>,
+{
+    let tables = serde_json::to_string(tables).map_err(|e| e.to_string())?;
+    sqlx::query(
+        "UPDATE data_version SET version = version + 1
+         WHERE table_name IN (SELECT value FROM json_each($1))",
+    )
+    .bind(tables)
+    .execute(executor)
+    .await
+    .map(|_| ())
+    .map_err(|e| format!("[Writer] bump data_version error: {}", e))
+}
+
+/// 批次寫入 K 線 (INSERT OR REPLACE)
+pub async fn save_deals(
+    pool: &SqlitePool,
+    timeframe: Timeframe,
+    stock_id: &str,
+    bars: &[Bar],
+) -> Result<(), String> {
+    if bars.is_empty() {
+        return Ok(());
+    }
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    for chunk in bars.chunks(DEAL_BATCH) {
+        let mut qb: QueryBuilder<Sqlite> = QueryBuilder::new(format!(
+            "INSERT OR REPLACE INTO {} (stock_id, {}, c, o, h, l, v) ",
+            timeframe.deal_table(),
+            timeframe.time_column()
+        ));
+        qb.push_values(chunk, |mut b, bar| {
+            b.push_bind(stock_id)
+                .push_bind(&bar.t)
+                .push_bind(bar.c)
+                .push_bind(bar.o)
+                .push_bind(bar.h)
+                .push_bind(bar.l)
+                .push_bind(bar.v);
+        });
+        qb.build()
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("[Writer] save {} error: {}", timeframe.deal_table(), e))?;
+    }
+    bump_versions(&mut *tx, &[timeframe.deal_table()]).await?;
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+/// 批次寫入技術指標 (INSERT OR REPLACE)
+pub async fn save_skills(
+    pool: &SqlitePool,
+    timeframe: Timeframe,
+    stock_id: &str,
+    rows: &[SkillsRow],
+) -> Result<(), String> {
+    if rows.is_empty() {
+        return Ok(());
+    }
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    for chunk in rows.chunks(SKILLS_BATCH) {
+        let mut qb: QueryBuilder<Sqlite> = QueryBuilder::new(format!(
+            "INSERT OR REPLACE INTO {} (stock_id, {}, {}) ",
+            timeframe.skills_table(),
+            timeframe.time_column(),
+            SKILLS_COLUMNS.join(", ")
+        ));
+        qb.push_values(chunk, |mut b, row| {
+            b.push_bind(stock_id).push_bind(&row.t);
+            for value in row.values() {
+                b.push_bind(value);
+            }
+        });
+        qb.build()
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("[Writer] save {} error: {}", timeframe.skills_table(), e))?;
+    }
+    bump_versions(&mut *tx, &[timeframe.skills_table()]).await?;
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+/// 刪除指定時間點的技術指標 (例如找不到對應 K 線的孤兒資料)
+pub async fn delete_skills(
+    pool: &SqlitePool,
+    timeframe: Timeframe,
+    stock_id: &str,
+    times: &[String],
+) -> Result<(), String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    for t in times {
+        sqlx::query(&format!(
+            "DELETE FROM {} WHERE stock_id = $1 AND {} = $2",
+            timeframe.skills_table(),
+            timeframe.time_column()
+        ))
+        .bind(stock_id)
+        .bind(t)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+    bump_versions(&mut *tx, &[timeframe.skills_table()]).await?;
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+/// 刪除指定時間點的 K 線 (例如以舊格式重複寫入的小時線)
+pub async fn delete_deals(
+    pool: &SqlitePool,
+    timeframe: Timeframe,
+    stock_id: &str,
+    times: &[String],
+) -> Result<(), String> {
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    for t in times {
+        sqlx::query(&format!(
+            "DELETE FROM {} WHERE stock_id = $1 AND {} = $2",
+            timeframe.deal_table(),
+            timeframe.time_column()
+        ))
+        .bind(stock_id)
+        .bind(t)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+    bump_versions(&mut *tx, &[timeframe.deal_table()]).await?;
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+/// 依時間排序讀取某檔股票的全部 K 線
+pub async fn load_deals(
+    pool: &SqlitePool,
+    timeframe: Timeframe,
+    stock_id: &str,
+) -> Result<Vec<Bar>, String> {
+    load_deals_after(pool, timeframe, stock_id, "").await
+}
+
+/// 依時間排序讀取某檔股票在 `after` 之後 (不含) 的 K 線
+pub async fn load_deals_after(
+    pool: &SqlitePool,
+    timeframe: Timeframe,
+    stock_id: &str,
+    after: &str,
+) -> Result<Vec<Bar>, String> {
+    let sql = format!(
+        "SELECT {col} AS t, o, h, l, c, CAST(v AS INTEGER) AS v FROM {table} WHERE stock_id = $1 AND {col} > $2 ORDER BY {col}",
+        col = timeframe.time_column(),
+        table = timeframe.deal_table()
+    );
+    type Row = (
+        String,
+        Option<f64>,
+        Option<f64>,
+        Option<f64>,
+        Option<f64>,
+        Option<i64>,
+    );
+    let rows: Vec<Row> = sqlx::query_as(&sql)
+        .bind(stock_id)
+        .bind(after)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    // 缺值的 K 線無法參與指標計算，直接略過
+    Ok(rows
+        .into_iter()
+        .filter_map(|(t, o, h, l, c, v)| {
+            Some(Bar {
+                t,
+                o: o?,
+                h: h?,
+                l: l?,
+                c: c?,
+                v: v.unwrap_or(0),
+            })
+        })
+        .collect())
+}
+
+/// 讀取股本，用於計算週轉率
+pub async fn issued_shares(pool: &SqlitePool, stock_id: &str) -> Result<Option<i64>, String> {
+    let row: Option<(Option<i64>,)> =
+        sqlx::query_as("SELECT issued_shares FROM stock WHERE stock_id = $1")
+            .bind(stock_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    Ok(row.and_then(|(shares,)| shares))
+}
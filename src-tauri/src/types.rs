@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+
+/****
+ * Data Example: {"t":"20241007","o":199.0,"h":199.0,"l":195.0,"c":197.5,"v":83451}
+ * 對應前端 `TaType`，日/週線 t 為 YYYYMMDD，小時線為 YYYYMMDDHHmm
+ ****/
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bar {
+    pub t: String,
+    pub o: f64,
+    pub h: f64,
+    pub l: f64,
+    pub c: f64,
+    pub v: i64,
+}
+
+impl Bar {
+    /// 價格皆為正數且 h/l 包住 o/c
+    pub fn is_valid(&self) -> bool {
+        self.o > 0.0
+            && self.h > 0.0
+            && self.l > 0.0
+            && self.c > 0.0
+            && self.h >= self.o.max(self.c)
+            && self.l <= self.o.min(self.c)
+            && self.v >= 0
+    }
+}
+
+/// K 線時框，對應 `*_deal` / `*_skills` 資料表
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Timeframe {
+    Daily,
+    Weekly,
+    Hourly,
+}
+
+impl Timeframe {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Timeframe::Daily => "daily",
+            Timeframe::Weekly => "weekly",
+            Timeframe::Hourly => "hourly",
+        }
+    }
+
+    pub fn deal_table(self) -> &'static str {
+        match self {
+            Timeframe::Daily => "daily_deal",
+            Timeframe::Weekly => "weekly_deal",
+            Timeframe::Hourly => "hourly_deal",
+        }
+    }
+
+    pub fn skills_table(self) -> &'static str {
+        match self {
+            Timeframe::Daily => "daily_skills",
+            Timeframe::Weekly => "weekly_skills",
+            Timeframe::Hourly => "hourly_skills",
+        }
+    }
+
+    /// 日/週線以 `t` 為主鍵，小時線以 `ts` 為主鍵
+    pub fn time_column(self) -> &'static str {
+        match self {
+            Timeframe::Hourly => "ts",
+            _ => "t",
+        }
+    }
+
+    /// 對應前端 `UrlTaPerdOptions`
+    pub fn yahoo_period(self) -> &'static str {
+        match self {
+            Timeframe::Daily => "d",
+            Timeframe::Weekly => "w",
+            Timeframe::Hourly => "60m",
+        }
+    }
+}
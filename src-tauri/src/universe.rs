@@ -0,0 +1,248 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::NaiveDate;
+use serde::Serialize;
+use sqlx::{Sqlite, SqlitePool, Transaction};
+use tauri::State;
+
+use crate::calendar;
+use crate::listing::{Listing, SecurityType};
+use crate::sqlite::connection::Db;
+use crate::sqlite::writer;
+
+/// 上市日不明 (早於本地紀錄) 時的生效日
+const UNKNOWN_START: &str = "00000000";
+
+#[derive(Debug, Clone, PartialEq, Serialize, sqlx::FromRow)]
+pub struct StockHistory {
+    pub stock_id: String,
+    pub stock_name: Option<String>,
+    pub market_type: Option<String>,
+    pub industry_group: Option<String>,
+    pub security_type: Option<String>,
+    pub valid_from: String,
+    pub valid_to: Option<String>,
+}
+
+impl StockHistory {
+    fn same_as(&self, listing: &Listing) -> bool {
+        self.stock_name.as_deref() == Some(listing.stock_name.as_str())
+            && self.market_type.as_deref() == Some(listing.market_type.as_str())
+            && self.industry_group.as_deref() == Some(listing.industry_group.as_str())
+            && self.security_type.as_deref() == Some(listing.security_type.as_str())
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct UniverseChanges {
+    pub listed: usize,
+    /// 更名、轉市場或產業別變更
+    pub changed: usize,
+    pub delisted: usize,
+}
+
+/// 某日仍掛牌的條件式。沒有任何歷史紀錄的股票 (尚未執行過清單同步) 一律視為有效
+pub fn active_on(column: &str, day: NaiveDate) -> String {
+    active_at(column, &calendar::format_day(day))
+}
+
+/// 同 `active_on`，`day` 為 YYYYMMDD 或選股樣板中的 `{{day:0}}` 佔位符
+pub fn active_at(column: &str, day: &str) -> String {
+    format!(
+        "({column} NOT IN (SELECT stock_id FROM stock_history)
+          OR {column} IN (SELECT stock_id FROM stock_history
+                          WHERE valid_from <= '{day}' AND (valid_to IS NULL OR valid_to > '{day}')))"
+    )
+}
+
+async fn insert(
+    tx: &mut Transaction<'_, Sqlite>,
+    listing: &Listing,
+    valid_from: &str,
+) -> Result<(), String> {
+    sqlx::query(
+        "INSERT OR REPLACE INTO stock_history
+            (stock_id, stock_name, market_type, industry_group, security_type, valid_from, valid_to)
+         VALUES ($1, $2, $3, $4, $5, $6, NULL)",
+    )
+    .bind(&listing.stock_id)
+    .bind(&listing.stock_name)
+    .bind(&listing.market_type)
+    .bind(&listing.industry_group)
+    .bind(listing.security_type.as_str())
+    .bind(valid_from)
+    .execute(&mut **tx)
+    .await
+    .map(|_| ())
+    .map_err(|e| format!("[Universe] insert error: {}", e))
+}
+
+async fn close(
+    tx: &mut Transaction<'_, Sqlite>,
+    stock_id: &str,
+    valid_from: &str,
+    valid_to: &str,
+) -> Result<(), String> {
+    sqlx::query("UPDATE stock_history SET valid_to = $3 WHERE stock_id = $1 AND valid_from = $2")
+        .bind(stock_id)
+        .bind(valid_from)
+        .bind(valid_to)
+        .execute(&mut **tx)
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("[Universe] close error: {}", e))
+}
+
+/// 比對最新清單與目前有效的紀錄，新增上市、關閉下市並切分更名 / 轉市場的區間。
+/// 權證存續期短且不在選股範圍內，不列入歷史。
+pub async fn record_listings(
+    pool: &SqlitePool,
+    listings: &[Listing],
+    as_of: NaiveDate,
+) -> Result<UniverseChanges, String> {
+    let today = calendar::format_day(as_of);
+    let mut tx = pool
+        .begin()
+        .await
+        .map_err(|e| format!("[Universe] begin error: {}", e))?;
+
+    let mut open: BTreeMap<String, StockHistory> =
+        sqlx::query_as::<_, StockHistory>("SELECT * FROM stock_history WHERE valid_to IS NULL")
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| format!("[Universe] load history error: {}", e))?
+            .into_iter()
+            .map(|row| (row.stock_id.clone(), row))
+            .collect();
+    let known: BTreeSet<String> = sqlx::query_scalar("SELECT DISTINCT stock_id FROM stock_history")
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| format!("[Universe] load history error: {}", e))?
+        .into_iter()
+        .collect();
+
+    let mut changes = UniverseChanges::default();
+    let mut listed_ids = BTreeSet::new();
+    for listing in listings
+        .iter()
+        .filter(|l| l.security_type != SecurityType::Warrant)
+    {
+        listed_ids.insert(listing.stock_id.as_str());
+        match open.remove(&listing.stock_id) {
+            Some(row) if row.same_as(listing) => {}
+            Some(row) => {
+                // 同一天內再次變更時直接覆寫，避免產生空區間
+                if row.valid_from < today {
+                    close(&mut tx, &row.stock_id, &row.valid_from, &today).await?;
+                }
+                insert(&mut tx, listing, row.valid_from.max(today.clone()).as_str()).await?;
+                changes.changed += 1;
+            }
+            None => {
+                // 首次出現時以公告的上市日為起點，曾下市後重新掛牌則從今日起算
+                let valid_from = match (&listing.listed_at, known.contains(&listing.stock_id)) {
+                    (Some(listed_at), false) if *listed_at <= today => listed_at.clone(),
+                    (None, false) => UNKNOWN_START.to_string(),
+                    _ => today.clone(),
+                };
+                insert(&mut tx, listing, &valid_from).await?;
+                changes.listed += 1;
+            }
+        }
+    }
+
+    for row in open.into_values() {
+        close(&mut tx, &row.stock_id, &row.valid_from, &today).await?;
+        changes.delisted += 1;
+    }
+
+    // 清單同步前就已同步過、但已不在清單上的股票，補一筆到今日為止的紀錄
+    let unlisted: Vec<StockHistory> = sqlx::query_as(&format!(
+        "SELECT stock_id, stock_name, market_type, industry_group, security_type,
+                '{}' AS valid_from, NULL AS valid_to
+         FROM stock
+         WHERE security_type != 'warrant'
+           AND stock_id NOT IN (SELECT stock_id FROM stock_history)",
+        UNKNOWN_START
+    ))
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|e| format!("[Universe] load stocks error: {}", e))?;
+    for row in unlisted {
+        if listed_ids.contains(row.stock_id.as_str()) {
+            continue;
+        }
+        sqlx::query(
+            "INSERT INTO stock_history
+                (stock_id, stock_name, market_type, industry_group, security_type, valid_from, valid_to)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)",
+        )
+        .bind(&row.stock_id)
+        .bind(&row.stock_name)
+        .bind(&row.market_type)
+        .bind(&row.industry_group)
+        .bind(&row.security_type)
+        .bind(&row.valid_from)
+        .bind(&today)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("[Universe] insert error: {}", e))?;
+        changes.delisted += 1;
+    }
+
+    writer::bump_versions(&mut *tx, &["stock_history"]).await?;
+    tx.commit()
+        .await
+        .map_err(|e| format!("[Universe] commit error: {}", e))?;
+    Ok(changes)
+}
+
+/// 某日掛牌中的證券，名稱與市場別取當時的值
+pub async fn as_of(pool: &SqlitePool, day: NaiveDate) -> Result<Vec<StockHistory>, String> {
+    let t = calendar::format_day(day);
+    sqlx::query_as(&format!(
+        "SELECT s.stock_id,
+                COALESCE(h.stock_name, s.stock_name) AS stock_name,
+                COALESCE(h.market_type, s.market_type) AS market_type,
+                COALESCE(h.industry_group, s.industry_group) AS industry_group,
+                COALESCE(h.security_type, s.security_type) AS security_type,
+                COALESCE(h.valid_from, '{unknown}') AS valid_from,
+                h.valid_to
+         FROM stock s
+         LEFT JOIN stock_history h ON h.stock_id = s.stock_id
+              AND h.valid_from <= $1 AND (h.valid_to IS NULL OR h.valid_to > $1)
+         WHERE {active}
+         ORDER BY s.stock_id",
+        unknown = UNKNOWN_START,
+        active = active_on("s.stock_id", day)
+    ))
+    .bind(&t)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("[Universe] load universe error: {}", e))
+}
+
+/// `date` 為 YYYYMMDD，未指定時為今日
+#[tauri::command]
+pub async fn get_universe(
+    db: State<'_, Db>,
+    date: Option<String>,
+) -> Result<Vec<StockHistory>, String> {
+    let day = match date {
+        Some(date) => calendar::parse_day(&date).ok_or(format!("invalid date {}", date))?,
+        None => chrono::Local::now().date_naive(),
+    };
+    as_of(&db.0, day).await
+}
+
+#[tauri::command]
+pub async fn get_stock_history(
+    db: State<'_, Db>,
+    stock_id: String,
+) -> Result<Vec<StockHistory>, String> {
+    sqlx::query_as("SELECT * FROM stock_history WHERE stock_id = $1 ORDER BY valid_from")
+        .bind(stock_id)
+        .fetch_all(&db.0)
+        .await
+        .map_err(|e| format!("[Universe] load history error: {}", e))
+}
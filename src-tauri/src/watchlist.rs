@@ -0,0 +1,484 @@
+use std::collections::{BTreeMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use tauri::State;
+
+use crate::sqlite::connection::Db;
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct Watchlist {
+    pub id: i64,
+    pub name: String,
+    pub position: i64,
+    pub item_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct WatchlistItem {
+    pub watchlist_id: i64,
+    pub stock_id: String,
+    pub stock_name: Option<String>,
+    pub note: Option<String>,
+    pub target_price: Option<f64>,
+    pub stop_price: Option<f64>,
+    pub strategy_name: Option<String>,
+    pub added_date: Option<String>,
+    pub position: i64,
+    #[sqlx(skip)]
+    pub tags: Vec<String>,
+}
+
+/// 新增或更新自選股，備註與目標 / 停損價以傳入值覆寫 (None 即清除)
+#[derive(Debug, Clone, Deserialize)]
+pub struct WatchlistItemInput {
+    pub watchlist_id: i64,
+    pub stock_id: String,
+    pub note: Option<String>,
+    pub target_price: Option<f64>,
+    pub stop_price: Option<f64>,
+    pub strategy_name: Option<String>,
+}
+
+/// 雲端 `watch_stock` 的一筆資料，對應前端 `WatchStockItem`
+#[derive(Debug, Clone, Deserialize)]
+pub struct CloudWatchStock {
+    pub stock_id: String,
+    pub added_date: Option<String>,
+    pub strategy_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReconcileResult {
+    /// 由雲端補進本地清單的股票數
+    pub imported: usize,
+    /// 雲端已刪除而自本地清單移除的股票數
+    pub removed: usize,
+    /// 本地新增而雲端沒有，需由前端上傳的股票
+    pub missing_in_cloud: Vec<String>,
+    /// 本地已刪除而雲端仍有，需由前端自雲端刪除的股票
+    pub removed_locally: Vec<String>,
+}
+
+async fn default_watchlist(pool: &SqlitePool) -> Result<i64, String> {
+    let id: Option<i64> =
+        sqlx::query_scalar("SELECT id FROM watchlist ORDER BY position, id LIMIT 1")
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+    match id {
+        Some(id) => Ok(id),
+        None => create(pool, "預設").await,
+    }
+}
+
+async fn create(pool: &SqlitePool, name: &str) -> Result<i64, String> {
+    let result = sqlx::query(
+        "INSERT INTO watchlist (name, position, created_at)
+         VALUES ($1, (SELECT COALESCE(MAX(position), -1) + 1 FROM watchlist), datetime('now', 'localtime'))",
+    )
+    .bind(name)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("[Watchlist] create error: {}", e))?;
+    Ok(result.last_insert_rowid())
+}
+
+async fn items(pool: &SqlitePool, watchlist_id: i64) -> Result<Vec<WatchlistItem>, String> {
+    let mut items: Vec<WatchlistItem> = sqlx::query_as(
+        "SELECT i.watchlist_id, i.stock_id, s.stock_name, i.note, i.target_price, i.stop_price,
+                i.strategy_name, i.added_date, i.position
+         FROM watchlist_item i
+         LEFT JOIN stock s ON s.stock_id = i.stock_id
+         WHERE i.watchlist_id = $1
+         ORDER BY i.position, i.added_date",
+    )
+    .bind(watchlist_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| format!("[Watchlist] load items error: {}", e))?;
+
+    let tags: Vec<(String, String)> = sqlx::query_as(
+        "SELECT stock_id, tag FROM watchlist_tag WHERE watchlist_id = $1 ORDER BY tag",
+    )
+    .bind(watchlist_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    let mut by_stock: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (stock_id, tag) in tags {
+        by_stock.entry(stock_id).or_default().push(tag);
+    }
+    for item in &mut items {
+        item.tags = by_stock.remove(&item.stock_id).unwrap_or_default();
+    }
+    Ok(items)
+}
+
+async fn upsert_item(pool: &SqlitePool, item: &WatchlistItemInput) -> Result<(), String> {
+    sqlx::query(
+        "INSERT INTO watchlist_item (watchlist_id, stock_id, note, target_price, stop_price, strategy_name, added_date, position)
+         VALUES ($1, $2, $3, $4, $5, $6, datetime('now', 'localtime'),
+                 (SELECT COALESCE(MAX(position), -1) + 1 FROM watchlist_item WHERE watchlist_id = $1))
+         ON CONFLICT (watchlist_id, stock_id) DO UPDATE SET
+             note = excluded.note,
+             target_price = excluded.target_price,
+             stop_price = excluded.stop_price,
+             strategy_name = COALESCE(excluded.strategy_name, strategy_name)",
+    )
+    .bind(item.watchlist_id)
+    .bind(&item.stock_id)
+    .bind(&item.note)
+    .bind(item.target_price)
+    .bind(item.stop_price)
+    .bind(&item.strategy_name)
+    .execute(pool)
+    .await
+    .map_err(|e| format!("[Watchlist] save item error: {}", e))?;
+    Ok(())
+}
+
+/// 所有清單中的股票，供警示評估使用
+pub async fn all_stock_ids(pool: &SqlitePool) -> Result<Vec<String>, String> {
+    sqlx::query_scalar("SELECT DISTINCT stock_id FROM watchlist_item")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 以上次對帳的快照 (`watchlist_synced`) 與雲端 `watch_stock` 雙向同步：
+/// 只在一邊出現的股票，若在快照中代表另一邊已刪除，否則為新加入；
+/// 本地刪除與新增回傳給前端處理雲端，雲端的則直接套用到本地清單。
+/// 新快照只記錄兩邊都確定有的股票，前端上傳或刪除失敗時下次仍會重試
+async fn reconcile(
+    pool: &SqlitePool,
+    watchlist_id: Option<i64>,
+    cloud: &[CloudWatchStock],
+) -> Result<ReconcileResult, String> {
+    let watchlist_id = match watchlist_id {
+        Some(id) => id,
+        None => default_watchlist(pool).await?,
+    };
+    let local: HashSet<String> = all_stock_ids(pool).await?.into_iter().collect();
+    let synced: HashSet<String> = sqlx::query_scalar("SELECT stock_id FROM watchlist_synced")
+        .fetch_all(pool)
+        .await
+        .map_err(|e| format!("[Watchlist] load synced error: {}", e))?
+        .into_iter()
+        .collect();
+    let cloud_ids: HashSet<&str> = cloud.iter().map(|item| item.stock_id.as_str()).collect();
+
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+    let mut imported = 0;
+    let mut removed_locally = Vec::new();
+    for item in cloud.iter().filter(|item| !local.contains(&item.stock_id)) {
+        if synced.contains(&item.stock_id) {
+            removed_locally.push(item.stock_id.clone());
+            continue;
+        }
+        sqlx::query(
+            "INSERT OR IGNORE INTO watchlist_item (watchlist_id, stock_id, strategy_name, added_date, position)
+             VALUES ($1, $2, $3, COALESCE($4, datetime('now', 'localtime')),
+                     (SELECT COALESCE(MAX(position), -1) + 1 FROM watchlist_item WHERE watchlist_id = $1))",
+        )
+        .bind(watchlist_id)
+        .bind(&item.stock_id)
+        .bind(&item.strategy_name)
+        .bind(&item.added_date)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("[Watchlist] import error: {}", e))?;
+        imported += 1;
+    }
+
+    let mut removed = 0;
+    let mut missing_in_cloud = Vec::new();
+    for stock_id in local.iter().filter(|id| !cloud_ids.contains(id.as_str())) {
+        if !synced.contains(stock_id) {
+            missing_in_cloud.push(stock_id.clone());
+            continue;
+        }
+        for sql in [
+            "DELETE FROM watchlist_tag WHERE stock_id = $1",
+            "DELETE FROM watchlist_item WHERE stock_id = $1",
+        ] {
+            sqlx::query(sql)
+                .bind(stock_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| format!("[Watchlist] remove item error: {}", e))?;
+        }
+        removed += 1;
+    }
+    missing_in_cloud.sort();
+    removed_locally.sort();
+
+    sqlx::query("DELETE FROM watchlist_synced")
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    for item in cloud
+        .iter()
+        .filter(|item| !removed_locally.contains(&item.stock_id))
+    {
+        sqlx::query("INSERT OR IGNORE INTO watchlist_synced (stock_id) VALUES ($1)")
+            .bind(&item.stock_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("[Watchlist] save synced error: {}", e))?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())?;
+
+    log::info!(
+        "[Watchlist] reconciled: {} imported, {} removed, {} missing in cloud, {} removed locally",
+        imported,
+        removed,
+        missing_in_cloud.len(),
+        removed_locally.len()
+    );
+    Ok(ReconcileResult {
+        imported,
+        removed,
+        missing_in_cloud,
+        removed_locally,
+    })
+}
+
+#[tauri::command]
+pub async fn list_watchlists(db: State<'_, Db>) -> Result<Vec<Watchlist>, String> {
+    default_watchlist(&db.0).await?;
+    sqlx::query_as(
+        "SELECT w.id, w.name, w.position, COUNT(i.stock_id) AS item_count
+         FROM watchlist w
+         LEFT JOIN watchlist_item i ON i.watchlist_id = w.id
+         GROUP BY w.id
+         ORDER BY w.position, w.id",
+    )
+    .fetch_all(&db.0)
+    .await
+    .map_err(|e| format!("[Watchlist] load lists error: {}", e))
+}
+
+#[tauri::command]
+pub async fn create_watchlist(db: State<'_, Db>, name: String) -> Result<i64, String> {
+    create(&db.0, name.trim()).await
+}
+
+#[tauri::command]
+pub async fn rename_watchlist(db: State<'_, Db>, id: i64, name: String) -> Result<(), String> {
+    sqlx::query("UPDATE watchlist SET name = $1 WHERE id = $2")
+        .bind(name.trim())
+        .bind(id)
+        .execute(&db.0)
+        .await
+        .map_err(|e| format!("[Watchlist] rename error: {}", e))?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn delete_watchlist(db: State<'_, Db>, id: i64) -> Result<(), String> {
+    let mut tx = db.0.begin().await.map_err(|e| e.to_string())?;
+    for sql in [
+        "DELETE FROM watchlist_tag WHERE watchlist_id = $1",
+        "DELETE FROM watchlist_item WHERE watchlist_id = $1",
+        "DELETE FROM watchlist WHERE id = $1",
+    ] {
+        sqlx::query(sql)
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("[Watchlist] delete error: {}", e))?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+/// 依傳入順序重設清單排序
+#[tauri::command]
+pub async fn reorder_watchlists(db: State<'_, Db>, ids: Vec<i64>) -> Result<(), String> {
+    let mut tx = db.0.begin().await.map_err(|e| e.to_string())?;
+    for (position, id) in ids.iter().enumerate() {
+        sqlx::query("UPDATE watchlist SET position = $1 WHERE id = $2")
+            .bind(position as i64)
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("[Watchlist] reorder error: {}", e))?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_watchlist_items(
+    db: State<'_, Db>,
+    watchlist_id: i64,
+) -> Result<Vec<WatchlistItem>, String> {
+    items(&db.0, watchlist_id).await
+}
+
+#[tauri::command]
+pub async fn upsert_watchlist_item(
+    db: State<'_, Db>,
+    item: WatchlistItemInput,
+) -> Result<(), String> {
+    upsert_item(&db.0, &item).await
+}
+
+#[tauri::command]
+pub async fn remove_watchlist_item(
+    db: State<'_, Db>,
+    watchlist_id: i64,
+    stock_id: String,
+) -> Result<(), String> {
+    let mut tx = db.0.begin().await.map_err(|e| e.to_string())?;
+    for sql in [
+        "DELETE FROM watchlist_tag WHERE watchlist_id = $1 AND stock_id = $2",
+        "DELETE FROM watchlist_item WHERE watchlist_id = $1 AND stock_id = $2",
+    ] {
+        sqlx::query(sql)
+            .bind(watchlist_id)
+            .bind(&stock_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("[Watchlist] remove item error: {}", e))?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+/// 依傳入順序重設清單內股票排序
+#[tauri::command]
+pub async fn reorder_watchlist_items(
+    db: State<'_, Db>,
+    watchlist_id: i64,
+    stock_ids: Vec<String>,
+) -> Result<(), String> {
+    let mut tx = db.0.begin().await.map_err(|e| e.to_string())?;
+    for (position, stock_id) in stock_ids.iter().enumerate() {
+        sqlx::query(
+            "UPDATE watchlist_item SET position = $1 WHERE watchlist_id = $2 AND stock_id = $3",
+        )
+        .bind(position as i64)
+        .bind(watchlist_id)
+        .bind(stock_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("[Watchlist] reorder error: {}", e))?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+/// 以傳入的標籤取代該股票原有的標籤
+#[tauri::command]
+pub async fn set_watchlist_tags(
+    db: State<'_, Db>,
+    watchlist_id: i64,
+    stock_id: String,
+    tags: Vec<String>,
+) -> Result<(), String> {
+    let mut tx = db.0.begin().await.map_err(|e| e.to_string())?;
+    sqlx::query("DELETE FROM watchlist_tag WHERE watchlist_id = $1 AND stock_id = $2")
+        .bind(watchlist_id)
+        .bind(&stock_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+    for tag in tags
+        .iter()
+        .map(|tag| tag.trim())
+        .filter(|tag| !tag.is_empty())
+    {
+        sqlx::query(
+            "INSERT OR IGNORE INTO watchlist_tag (watchlist_id, stock_id, tag) VALUES ($1, $2, $3)",
+        )
+        .bind(watchlist_id)
+        .bind(&stock_id)
+        .bind(tag)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| format!("[Watchlist] save tags error: {}", e))?;
+    }
+    tx.commit().await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn reconcile_watchlist(
+    db: State<'_, Db>,
+    watchlist_id: Option<i64>,
+    cloud: Vec<CloudWatchStock>,
+) -> Result<ReconcileResult, String> {
+    reconcile(&db.0, watchlist_id, &cloud).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sqlite::connection;
+
+    fn cloud(ids: &[&str]) -> Vec<CloudWatchStock> {
+        ids.iter()
+            .map(|id| CloudWatchStock {
+                stock_id: id.to_string(),
+                added_date: None,
+                strategy_name: None,
+            })
+            .collect()
+    }
+
+    async fn local(pool: &SqlitePool) -> Vec<String> {
+        let mut ids = all_stock_ids(pool).await.unwrap();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn reconcile_propagates_deletes_both_ways() {
+        tauri::async_runtime::block_on(async {
+            let pool = connection::memory().await;
+            connection::migrate(&pool, ..).await;
+            let list = default_watchlist(&pool).await.unwrap();
+            for stock_id in ["2330", "2317"] {
+                upsert_item(
+                    &pool,
+                    &WatchlistItemInput {
+                        watchlist_id: list,
+                        stock_id: stock_id.to_string(),
+                        note: None,
+                        target_price: None,
+                        stop_price: None,
+                        strategy_name: None,
+                    },
+                )
+                .await
+                .unwrap();
+            }
+
+            // 首次對帳取聯集
+            let result = reconcile(&pool, None, &cloud(&["2330", "2454"]))
+                .await
+                .unwrap();
+            assert_eq!(result.imported, 1);
+            assert_eq!(result.missing_in_cloud, vec!["2317"]);
+            assert!(result.removed_locally.is_empty());
+            assert_eq!(local(&pool).await, vec!["2317", "2330", "2454"]);
+
+            // 上傳成功後雲端刪除 2454、本地刪除 2330，兩邊的刪除都不會被補回
+            sqlx::query("DELETE FROM watchlist_item WHERE stock_id = '2330'")
+                .execute(&pool)
+                .await
+                .unwrap();
+            let result = reconcile(&pool, None, &cloud(&["2330", "2317"]))
+                .await
+                .unwrap();
+            assert_eq!(result.imported, 0);
+            assert_eq!(result.removed, 1);
+            assert_eq!(result.removed_locally, vec!["2330"]);
+            assert!(result.missing_in_cloud.is_empty());
+            assert_eq!(local(&pool).await, vec!["2317"]);
+
+            // 前端自雲端刪除 2330 後兩邊一致
+            let result = reconcile(&pool, None, &cloud(&["2317"])).await.unwrap();
+            assert_eq!((result.imported, result.removed), (0, 0));
+            assert!(result.removed_locally.is_empty() && result.missing_in_cloud.is_empty());
+            assert_eq!(local(&pool).await, vec!["2317"]);
+        });
+    }
+}
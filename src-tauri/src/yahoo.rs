@@ -0,0 +1,219 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, FixedOffset};
+use serde::Deserialize;
+use tauri_plugin_http::reqwest;
+
+use crate::types::{Bar, Timeframe};
+
+/// 遭 Yahoo 限流 (403/429) 後暫停請求的秒數
+const COOLDOWN_SECS: u64 = 60;
+/// 台北時區 (UTC+8)，Yahoo 回傳的 timestamp 以此換算交易日
+pub const TAIPEI_OFFSET_SECS: i32 = 8 * 3600;
+
+static COOLDOWN_UNTIL: AtomicU64 = AtomicU64::new(0);
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// 對應前端 `generateDealDataDownloadUrl({ type: UrlType.Indicators })`
+pub fn indicators_url(stock_id: &str, timeframe: Timeframe) -> String {
+    format!(
+        "https://tw.stock.yahoo.com/_td-stock/api/resource/FinanceChartService.ApacLibraCharts;period={};symbols=[\"{}\"]",
+        timeframe.yahoo_period(),
+        stock_id
+    )
+}
+
+#[derive(Deserialize)]
+struct ChartResponse {
+    chart: Chart,
+}
+
+#[derive(Deserialize)]
+struct Chart {
+    timestamp: Vec<i64>,
+    indicators: Indicators,
+}
+
+#[derive(Deserialize)]
+struct Indicators {
+    quote: Vec<Quote>,
+}
+
+/// 分時 (tick) 回應可能只有 close / volume
+#[derive(Deserialize)]
+struct Quote {
+    #[serde(default)]
+    open: Vec<Option<f64>>,
+    #[serde(default)]
+    high: Vec<Option<f64>>,
+    #[serde(default)]
+    low: Vec<Option<f64>>,
+    close: Vec<Option<f64>>,
+    #[serde(default)]
+    volume: Vec<Option<f64>>,
+}
+
+fn format_time(timestamp: i64, timeframe: Timeframe) -> Option<String> {
+    let offset = FixedOffset::east_opt(TAIPEI_OFFSET_SECS)?;
+    let time = DateTime::from_timestamp(timestamp, 0)?.with_timezone(&offset);
+    Some(match timeframe {
+        Timeframe::Hourly => time.format("%Y%m%d%H%M").to_string(),
+        _ => time.format("%Y%m%d").to_string(),
+    })
+}
+
+/// 對應前端 `generateDealDataDownloadUrl({ type: UrlType.Tick })`，為當日逐分鐘資料
+pub fn tick_url(stock_id: &str) -> String {
+    format!(
+        "https://tw.stock.yahoo.com/_td-stock/api/resource/FinanceChartService.ApacLibraCharts;symbols=[\"{}\"];type=tick",
+        stock_id
+    )
+}
+
+fn parse_chart(text: &str) -> Result<(Vec<i64>, Quote), String> {
+    let json: Vec<ChartResponse> =
+        serde_json::from_str(text).map_err(|e| format!("[Yahoo] parse error: {}", e))?;
+    let chart = json
+        .into_iter()
+        .next()
+        .ok_or("[Yahoo] empty response")?
+        .chart;
+    let quote = chart
+        .indicators
+        .quote
+        .into_iter()
+        .next()
+        .ok_or("[Yahoo] missing quote")?;
+    Ok((chart.timestamp, quote))
+}
+
+/// 解析 FinanceChartService 回應，與前端 `analyzeIndicatorsData` 相同略過開盤價為 null 的資料
+pub fn parse_indicators(text: &str, timeframe: Timeframe) -> Result<Vec<Bar>, String> {
+    let (timestamp, quote) = parse_chart(text)?;
+    let mut bars = Vec::with_capacity(timestamp.len());
+    for (i, ts) in timestamp.iter().enumerate() {
+        let field = |values: &[Option<f64>]| values.get(i).copied().flatten();
+        let (Some(o), Some(h), Some(l), Some(c)) = (
+            field(&quote.open),
+            field(&quote.high),
+            field(&quote.low),
+            field(&quote.close),
+        ) else {
+            continue;
+        };
+        let Some(t) = format_time(*ts, timeframe) else {
+            continue;
+        };
+        bars.push(Bar {
+            t,
+            o,
+            h,
+            l,
+            c,
+            v: field(&quote.volume).unwrap_or(0.0) as i64,
+        });
+    }
+    Ok(bars)
+}
+
+/// 解析分時回應為 1 分鐘 K 線 (t 為 YYYYMMDDHHmm)，只有成交價時開高低皆以成交價代替
+pub fn parse_tick(text: &str) -> Result<Vec<Bar>, String> {
+    let (timestamp, quote) = parse_chart(text)?;
+    let mut bars = Vec::with_capacity(timestamp.len());
+    for (i, ts) in timestamp.iter().enumerate() {
+        let field = |values: &[Option<f64>]| values.get(i).copied().flatten();
+        let Some(c) = field(&quote.close) else {
+            continue;
+        };
+        let Some(t) = format_time(*ts, Timeframe::Hourly) else {
+            continue;
+        };
+        bars.push(Bar {
+            t,
+            o: field(&quote.open).unwrap_or(c),
+            h: field(&quote.high).unwrap_or(c),
+            l: field(&quote.low).unwrap_or(c),
+            c,
+            v: field(&quote.volume).unwrap_or(0.0) as i64,
+        });
+    }
+    Ok(bars)
+}
+
+/// 限流期間直接回傳錯誤，遇到 403/429 時開始冷卻
+async fn fetch_text(url: &str) -> Result<String, String> {
+    let until = COOLDOWN_UNTIL.load(Ordering::Relaxed);
+    if until > now_secs() {
+        return Err(format!("[Yahoo] cooling down for {}s", until - now_secs()));
+    }
+
+    // 與前端同步相同，每次請求前稍作停頓避免觸發限流
+    let jitter = 200 + (now_secs() % 7) * 50;
+    tokio::time::sleep(Duration::from_millis(jitter)).await;
+
+    let response = reqwest::get(url)
+        .await
+        .map_err(|e| format!("[Yahoo] request error: {}", e))?;
+    let status = response.status();
+    if status.as_u16() == 403 || status.as_u16() == 429 {
+        COOLDOWN_UNTIL.store(now_secs() + COOLDOWN_SECS, Ordering::Relaxed);
+        return Err(format!("[Yahoo] rate limited: {}", status));
+    }
+    if !status.is_success() {
+        return Err(format!("[Yahoo] fetch failed: {}", status));
+    }
+    response
+        .text()
+        .await
+        .map_err(|e| format!("[Yahoo] read error: {}", e))
+}
+
+/// 下載某檔股票的 K 線，限流期間直接回傳錯誤
+pub async fn fetch_bars(stock_id: &str, timeframe: Timeframe) -> Result<Vec<Bar>, String> {
+    let text = fetch_text(&indicators_url(stock_id, timeframe)).await?;
+    parse_indicators(&text, timeframe)
+}
+
+/// 下載某檔股票當日的 1 分鐘 K 線
+pub async fn fetch_minutes(stock_id: &str) -> Result<Vec<Bar>, String> {
+    let text = fetch_text(&tick_url(stock_id)).await?;
+    parse_tick(&text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> String {
+        let path = format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name);
+        std::fs::read_to_string(path).unwrap()
+    }
+
+    #[test]
+    fn parses_tick_with_close_and_volume_only() {
+        let bars = parse_tick(&fixture("yahoo_tick.json")).unwrap();
+        // 成交價為 null 的分鐘略過
+        assert_eq!(bars.len(), 8);
+        assert_eq!(
+            bars[0],
+            Bar {
+                t: "202410070900".to_string(),
+                o: 1000.0,
+                h: 1000.0,
+                l: 1000.0,
+                c: 1000.0,
+                v: 120,
+            }
+        );
+        assert_eq!(bars[2].t, "202410070959");
+        assert_eq!(bars.last().unwrap().t, "202410071330");
+        assert!(parse_tick("[]").is_err());
+    }
+}
@@ -0,0 +1,107 @@
+// 產生 indicators_parity.json：以 `src/cls_tools` 的 supertrend/cci/donchian/mss 跑固定種子的 K 線，
+// 作為 Rust 指標 (atr/supertrend/cci/donchian/mss) 的對照。需 Node 22.6 以上直接載入 TS：
+//   node --experimental-strip-types src-tauri/tests/fixtures/indicators_parity.mjs
+import { writeFileSync } from "node:fs";
+import cci from "../../../src/cls_tools/cci.ts";
+import donchian from "../../../src/cls_tools/donchian.ts";
+import mss from "../../../src/cls_tools/mss.ts";
+import supertrend from "../../../src/cls_tools/supertrend.ts";
+
+const COUNT = 300;
+
+// 與 indicators::tests::sample_bars 相同的 64 位元 LCG
+function lcg() {
+  let seed = 20241007n;
+  const mask = (1n << 64n) - 1n;
+  return () => {
+    seed = (seed * 6364136223846793005n + 1442695040888963407n) & mask;
+    return Number(seed >> 33n) / 2 ** 31;
+  };
+}
+
+function sampleBars(count, random) {
+  let close = 100;
+  const bars = [];
+  for (let i = 0; i < count; i++) {
+    const open = close;
+    close = Math.max(open * (1 + (random() - 0.5) * 0.08), 1);
+    bars.push({
+      t: String(20200101 + i).padStart(8, "0"),
+      o: open,
+      h: Math.max(open, close) * (1 + random() * 0.02),
+      l: Math.min(open, close) * (1 - random() * 0.02),
+      c: close,
+      v: Math.floor(random() * 50000),
+    });
+  }
+  return bars;
+}
+
+const random = lcg();
+const bars = sampleBars(COUNT, random);
+
+// MSS 的其他指標輸入：簡單 EMA 與布林通道，ADX 以亂數模擬
+const ema = (period) => {
+  let value = null;
+  return (close) => {
+    value = value === null ? close : value + ((close - value) * 2) / (period + 1);
+    return value;
+  };
+};
+const ema5 = ema(5);
+const ema10 = ema(10);
+const ema60 = ema(60);
+const closes = [];
+
+let stState = supertrend.init();
+let cciState = cci.init();
+let dcState = donchian.init();
+let mssState = mss.init();
+
+const rows = bars.map((bar, i) => {
+  const prev = i > 0 ? bars[i - 1] : null;
+
+  const st = supertrend.next(bar, prev, stState, 14, 2.5, i);
+  stState = st.state;
+  const cc = cci.next(bar, cciState, 14);
+  cciState = cc.state;
+  const dc = donchian.next(bar, dcState, 20);
+  dcState = dc.state;
+
+  closes.push(bar.c);
+  const window = closes.slice(-20);
+  const mid = window.reduce((a, b) => a + b, 0) / window.length;
+  const sd = Math.sqrt(
+    window.reduce((a, b) => a + (b - mid) ** 2, 0) / window.length,
+  );
+  const input = {
+    ema5: ema5(bar.c),
+    ema10: ema10(bar.c),
+    ema60: ema60(bar.c),
+    bollUb: window.length === 20 ? mid + 2 * sd : null,
+    bollLb: window.length === 20 ? mid - 2 * sd : null,
+    adx: 10 + random() * 30,
+  };
+  const ms = mss.next(bar, prev, mssState, input, i, {
+    atrPeriod: 20,
+    kcMultiplier: 1.5,
+  });
+  mssState = ms.state;
+
+  return {
+    atr: st.atr,
+    supertrend: st.value,
+    direction: st.direction,
+    cci: cc.cci,
+    dc_upper: dc.upper,
+    dc_middle: dc.middle,
+    dc_lower: dc.lower,
+    mss_input: input,
+    mss: ms.mss,
+  };
+});
+
+writeFileSync(
+  new URL("./indicators_parity.json", import.meta.url),
+  JSON.stringify({ bars, rows }),
+);
@@ -0,0 +1,188 @@
+// 產生 skills_parity.json：以前端同步原本使用的 anysis 計算器 (SyncEngine.calculateIndicators) 跑固定種子的 K 線，
+// 作為 Rust 指標引擎的對照。於專案根目錄安裝依賴後執行：
+//   node src-tauri/tests/fixtures/skills_parity.mjs
+import { writeFileSync } from "node:fs";
+import {
+  Boll,
+  Cmf,
+  Dmi,
+  Ema,
+  Ichimoku,
+  Kd,
+  Ma,
+  Macd,
+  Mfi,
+  Obv,
+  ObvEma,
+  Rsi,
+} from "@ch20026103/anysis";
+
+const ISSUED_SHARES = 25_000_000;
+const COUNT = 400;
+
+// 與 indicators::tests::sample_bars 相同的 64 位元 LCG
+function sampleBars(count) {
+  let seed = 20241007n;
+  const mask = (1n << 64n) - 1n;
+  const random = () => {
+    seed = (seed * 6364136223846793005n + 1442695040888963407n) & mask;
+    return Number(seed >> 33n) / 2 ** 31;
+  };
+  let close = 100;
+  const bars = [];
+  for (let i = 0; i < count; i++) {
+    const open = close;
+    close = Math.max(open * (1 + (random() - 0.5) * 0.08), 1);
+    bars.push({
+      t: String(20200101 + i).padStart(8, "0"),
+      o: open,
+      h: Math.max(open, close) * (1 + random() * 0.02),
+      l: Math.min(open, close) * (1 - random() * 0.02),
+      c: close,
+      v: Math.floor(random() * 50000),
+    });
+  }
+  return bars;
+}
+
+const ta = sampleBars(COUNT);
+const boll = new Boll();
+const ma = new Ma();
+const ema = new Ema();
+const macd = new Macd();
+const kd = new Kd();
+const rsi = new Rsi();
+const obv = new Obv();
+const obvEma = new ObvEma();
+const mfi = new Mfi();
+const ichimoku = new Ichimoku();
+const dmi = new Dmi();
+const cmf = new Cmf();
+
+const state = {
+  ma5: ma.init(ta[0], 5),
+  ma10: ma.init(ta[0], 10),
+  ma20: ma.init(ta[0], 20),
+  ma30: ma.init(ta[0], 30),
+  ma50: ma.init(ta[0], 50),
+  ma60: ma.init(ta[0], 60),
+  ma120: ma.init(ta[0], 120),
+  ma240: ma.init(ta[0], 240),
+  ema5: ema.init(ta[0], 5),
+  ema10: ema.init(ta[0], 10),
+  ema20: ema.init(ta[0], 20),
+  ema60: ema.init(ta[0], 60),
+  ema120: ema.init(ta[0], 120),
+  ema200: ema.init(ta[0], 200),
+  boll: boll.init(ta[0]),
+  macd: macd.init(ta[0]),
+  kd: kd.init(ta[0], 9),
+  rsi5: rsi.init(ta[0], 5),
+  rsi10: rsi.init(ta[0], 10),
+  obv: obv.init(ta[0]),
+  obv_ma5: obvEma.init(obv.init(ta[0]).obv, 5),
+  obv_ma10: obvEma.init(obv.init(ta[0]).obv, 10),
+  obv_ma20: obvEma.init(obv.init(ta[0]).obv, 20),
+  obv_ma60: obvEma.init(obv.init(ta[0]).obv, 60),
+  mfi: mfi.init(ta[0], 14),
+  ichimoku: ichimoku.init(ta[0]),
+  dmi: dmi.init(ta[0], 14),
+  cmf: cmf.init(ta[0]),
+};
+
+const num = (value) =>
+  typeof value === "number" && Number.isFinite(value) ? value : null;
+
+const rows = ta.map((val, i) => {
+  if (i > 0) {
+    state.ma5 = ma.next(val, state.ma5, 5);
+    state.ma10 = ma.next(val, state.ma10, 10);
+    state.ma20 = ma.next(val, state.ma20, 20);
+    state.ma30 = ma.next(val, state.ma30, 30);
+    state.ma50 = ma.next(val, state.ma50, 50);
+    state.ma60 = ma.next(val, state.ma60, 60);
+    state.ma120 = ma.next(val, state.ma120, 120);
+    state.ma240 = ma.next(val, state.ma240, 240);
+    state.ema5 = ema.next(val, state.ema5, 5);
+    state.ema10 = ema.next(val, state.ema10, 10);
+    state.ema20 = ema.next(val, state.ema20, 20);
+    state.ema60 = ema.next(val, state.ema60, 60);
+    state.ema120 = ema.next(val, state.ema120, 120);
+    state.ema200 = ema.next(val, state.ema200, 200);
+    state.boll = boll.next(val, state.boll, 20);
+    state.macd = macd.next(val, state.macd);
+    state.kd = kd.next(val, state.kd, 9);
+    state.rsi5 = rsi.next(val, state.rsi5, 5);
+    state.rsi10 = rsi.next(val, state.rsi10, 10);
+    state.obv = obv.next(val, state.obv);
+    state.obv_ma5 = obvEma.next(state.obv.obv, state.obv_ma5, 5);
+    state.obv_ma10 = obvEma.next(state.obv.obv, state.obv_ma10, 10);
+    state.obv_ma20 = obvEma.next(state.obv.obv, state.obv_ma20, 20);
+    state.obv_ma60 = obvEma.next(state.obv.obv, state.obv_ma60, 60);
+    state.mfi = mfi.next(val, state.mfi, 14);
+    state.ichimoku = ichimoku.next(val, state.ichimoku);
+    state.dmi = dmi.next(val, state.dmi, 14);
+    state.cmf = cmf.next(val, state.cmf, 21, 5);
+  }
+  return {
+    ma5: num(state.ma5.ma),
+    ma5_ded: num(state.ma5.exclusionValue["d-1"]),
+    ma10: num(state.ma10.ma),
+    ma10_ded: num(state.ma10.exclusionValue["d-1"]),
+    ma20: num(state.ma20.ma),
+    ma20_ded: num(state.ma20.exclusionValue["d-1"]),
+    ma30: num(state.ma30.ma),
+    ma30_ded: num(state.ma30.exclusionValue["d-1"]),
+    ma50: num(state.ma50.ma),
+    ma50_ded: num(state.ma50.exclusionValue["d-1"]),
+    ma60: num(state.ma60.ma),
+    ma60_ded: num(state.ma60.exclusionValue["d-1"]),
+    ma120: num(state.ma120.ma),
+    ma120_ded: num(state.ma120.exclusionValue["d-1"]),
+    ma240: num(state.ma240.ma),
+    ma240_ded: num(state.ma240.exclusionValue["d-1"]),
+    ema5: num(state.ema5.ema),
+    ema10: num(state.ema10.ema),
+    ema20: num(state.ema20.ema),
+    ema60: num(state.ema60.ema),
+    ema120: num(state.ema120.ema),
+    ema200: num(state.ema200.ema),
+    macd: num(state.macd.macd),
+    dif: num(state.macd.dif[state.macd.dif.length - 1] || 0),
+    osc: num(state.macd.osc),
+    k: num(state.kd.k),
+    d: num(state.kd.d),
+    j: num(state.kd.j),
+    rsi5: num(state.rsi5.rsi),
+    rsi10: num(state.rsi10.rsi),
+    bollUb: num(state.boll.bollUb),
+    bollMa: num(state.boll.bollMa),
+    bollLb: num(state.boll.bollLb),
+    obv: num(state.obv.obv),
+    obv_ma5: num(state.obv_ma5.ma),
+    obv_ma10: num(state.obv_ma10.ma),
+    obv_ma20: num(state.obv_ma20.ma),
+    obv_ma60: num(state.obv_ma60.ma),
+    obv_ema5: num(state.obv_ma5.ema),
+    obv_ema10: num(state.obv_ma10.ema),
+    obv_ema20: num(state.obv_ma20.ema),
+    obv_ema60: num(state.obv_ma60.ema),
+    mfi: num(state.mfi.mfi),
+    tenkan: num(state.ichimoku.ichimoku.tenkan),
+    kijun: num(state.ichimoku.ichimoku.kijun),
+    senkouA: num(state.ichimoku.ichimoku.senkouA),
+    senkouB: num(state.ichimoku.ichimoku.senkouB),
+    chikou: num(state.ichimoku.ichimoku.chikou),
+    di_plus: num(state.dmi.pDi),
+    di_minus: num(state.dmi.mDi),
+    adx: num(state.dmi.adx),
+    cmf: num(state.cmf.cmf),
+    cmf_ema5: num(state.cmf.ema),
+    turnover_rate: ((val.v * 1000) / ISSUED_SHARES) * 100,
+  };
+});
+
+writeFileSync(
+  new URL("./skills_parity.json", import.meta.url),
+  JSON.stringify({ issued_shares: ISSUED_SHARES, bars: ta, rows }),
+);
@@ -29,6 +29,7 @@ import {
   TimeSharingSkillsTableOptions,
   TimeSharingSkillsTableType,
 } from "../types";
+import bumpDataVersion from "../utils/bumpDataVersion";
 
 export default class SqliteDataManager {
   public db: Database;
@@ -46,6 +47,15 @@ export default class SqliteDataManager {
       await this.db.execute("DELETE FROM daily_skills;");
       await this.db.execute("DELETE FROM daily_deal;");
       await this.db.execute("DELETE FROM stock;");
+      await bumpDataVersion(this.db, [
+        "hourly_skills",
+        "hourly_deal",
+        "weekly_skills",
+        "weekly_deal",
+        "daily_skills",
+        "daily_deal",
+        "stock",
+      ]);
       return true;
     } catch (e) {
       error(`${e}`);
@@ -81,6 +91,14 @@ export default class SqliteDataManager {
       await this.db.execute(
         `DELETE FROM daily_deal WHERE  stock_id = '${stock_id}' AND t > '${t}';`,
       );
+      await bumpDataVersion(this.db, [
+        "hourly_skills",
+        "hourly_deal",
+        "weekly_skills",
+        "weekly_deal",
+        "daily_skills",
+        "daily_deal",
+      ]);
       return true;
     } catch (e) {
       error(`${e}`);
@@ -481,15 +499,17 @@ export default class SqliteDataManager {
   async saveStockTable(stock: StockTableType) {
     try {
       await this.db.execute(
-        "INSERT OR REPLACE INTO stock (stock_id, stock_name, industry_group, market_type, issued_shares) VALUES ($1, $2, $3, $4, $5)",
+        "INSERT OR REPLACE INTO stock (stock_id, stock_name, industry_group, market_type, issued_shares, security_type) VALUES ($1, $2, $3, $4, $5, $6)",
         [
           stock.stock_id,
           stock.stock_name,
           stock.industry_group,
           stock.market_type,
           stock.issued_shares || null,
+          stock.security_type ?? "stock",
         ],
       );
+      await bumpDataVersion(this.db, ["stock"]);
       return true;
     } catch (e) {
       throw new Error(`${stock.stock_name}:${e}`);
@@ -509,6 +529,7 @@ export default class SqliteDataManager {
         )
         .join(", ")}`;
       await this.db.execute(sql);
+      await bumpDataVersion(this.db, [table]);
       return true;
     } catch (e) {
       throw new Error(`${stock.stock_name}:${e}`);
@@ -584,6 +605,7 @@ export default class SqliteDataManager {
             )
             .join(", ")}`;
       await this.db.execute(sql);
+      await bumpDataVersion(this.db, [table]);
       return true;
     } catch (e) {
       throw new Error(`${stock.stock_name}:${e}`);
@@ -604,6 +626,7 @@ export default class SqliteDataManager {
         )
         .join(", ")}`;
       await this.db.execute(sql);
+      await bumpDataVersion(this.db, [table]);
       return true;
     } catch (e) {
       throw new Error(`${stock.stock_name}:${e}`);
@@ -679,6 +702,7 @@ export default class SqliteDataManager {
             )
             .join(", ")}`;
       await this.db.execute(sql);
+      await bumpDataVersion(this.db, [table]);
       return true;
     } catch (e) {
       throw new Error(`${stock.stock_name}:${e}`);
@@ -62,6 +62,27 @@ export class StockDailyQueryBuilder extends BaseQueryBuilder {
     cmf: { key: "cmf", group: "_day_ago_sk" },
     cmf_ema5: { key: "cmf_ema5", group: "_day_ago_sk" },
     週轉率: { key: "turnover_rate", group: "_day_ago_sk", unit: "%" },
+    atr: { key: "atr", group: "_day_ago_sk" },
+    cci: { key: "cci", group: "_day_ago_sk" },
+    唐奇安上軌: { key: "donchian_ub", group: "_day_ago_sk" },
+    唐奇安中軌: { key: "donchian_ma", group: "_day_ago_sk" },
+    唐奇安下軌: { key: "donchian_lb", group: "_day_ago_sk" },
+    超級趨勢: { key: "supertrend", group: "_day_ago_sk" },
+    "超級趨勢方向(1多/-1空)": { key: "supertrend_dir", group: "_day_ago_sk" },
+    市場狀態分數: { key: "mss", group: "_day_ago_sk" },
+    // daily_signals：所選日期至今天之間出現的訊號次數，例如「5天前 MACD底背離 大於 0」
+    KD底背離: { key: "kd_divergence:bullish", group: "_signal", unit: "次" },
+    KD頂背離: { key: "kd_divergence:bearish", group: "_signal", unit: "次" },
+    MACD底背離: { key: "macd_divergence:bullish", group: "_signal", unit: "次" },
+    MACD頂背離: { key: "macd_divergence:bearish", group: "_signal", unit: "次" },
+    RSI底背離: { key: "rsi_divergence:bullish", group: "_signal", unit: "次" },
+    RSI頂背離: { key: "rsi_divergence:bearish", group: "_signal", unit: "次" },
+    OBV底背離: { key: "obv_divergence:bullish", group: "_signal", unit: "次" },
+    OBV頂背離: { key: "obv_divergence:bearish", group: "_signal", unit: "次" },
+    金叉Ma10: { key: "ma10_cross:bullish", group: "_signal", unit: "次" },
+    死叉Ma10: { key: "ma10_cross:bearish", group: "_signal", unit: "次" },
+    金叉Ma20: { key: "ma20_cross:bullish", group: "_signal", unit: "次" },
+    死叉Ma20: { key: "ma20_cross:bearish", group: "_signal", unit: "次" },
   };
 
   protected othersMapping: Record<string, QueryBuilderMappingItem> = {
@@ -120,33 +141,28 @@ export class StockDailyQueryBuilder extends BaseQueryBuilder {
     return dayMapping[day] || 0;
   }
 
+  private resolveKey(day: string, indicator: string): string {
+    if (day === "其他") {
+      const mapping = this.othersMapping[indicator];
+      return `"${mapping.group}".${mapping.key}`;
+    }
+    const mapping = this.mapping[indicator];
+    const dayNumber = this.convertDayToNumber(day);
+    if (mapping.group === "_signal") {
+      // 以 "N_day_ago".t 為起點，generateSqlQuery 會自動 join 該日 K 線
+      const [signalType, direction] = mapping.key.split(":");
+      return `(SELECT COUNT(*) FROM daily_signals sg WHERE sg.stock_id = "0_day_ago".stock_id AND sg.signal_type = '${signalType}' AND sg.direction = '${direction}' AND sg.end_t BETWEEN "${dayNumber}_day_ago".t AND "0_day_ago".t)`;
+    }
+    return `"${dayNumber}${mapping.group}".${mapping.key}`;
+  }
+
   public generateExpression(prompt: StorePrompt): string[] {
     const { day1, indicator1, operator, day2, indicator2 } = prompt;
     const operatorKey = this.convertOperator(operator);
 
-    let day1Key = "";
-    if (day1 === "其他") {
-      const day1Mapping = this.othersMapping[indicator1];
-      day1Key = `"${day1Mapping.group}".${day1Mapping.key}`;
-    } else {
-      const day1Mapping = this.mapping[indicator1];
-      day1Key = `"${this.convertDayToNumber(day1)}${day1Mapping.group}".${
-        day1Mapping.key
-      }`;
-    }
-
-    let day2Key = "";
-    if (day2 === "自定義數值") {
-      day2Key = indicator2;
-    } else if (day2 === "其他") {
-      const day2Mapping = this.othersMapping[indicator2];
-      day2Key = `"${day2Mapping.group}".${day2Mapping.key}`;
-    } else {
-      const day2Mapping = this.mapping[indicator2];
-      day2Key = `"${this.convertDayToNumber(day2)}${day2Mapping.group}".${
-        day2Mapping.key
-      }`;
-    }
+    const day1Key = this.resolveKey(day1, indicator1);
+    const day2Key =
+      day2 === "自定義數值" ? indicator2 : this.resolveKey(day2, indicator2);
     return [day1Key, operatorKey, day2Key];
   }
 
@@ -154,11 +170,14 @@ export class StockDailyQueryBuilder extends BaseQueryBuilder {
     conditions,
     dates,
     stockIds,
+    adjusted = false,
   }: {
     conditions: string[];
     dates: string[];
     stockIds?: string[];
+    adjusted?: boolean; // 使用還原權值後的價格 (daily_adjusted_deal)
   }): string {
+    const dealTable = adjusted ? "daily_adjusted_deal" : "daily_deal";
     const conditionsStr = conditions.join(" ");
 
     // 1. 提取所有需要的 alias，例如 "1_day_ago", "2_day_ago_sk" 等
@@ -203,7 +222,7 @@ export class StockDailyQueryBuilder extends BaseQueryBuilder {
         if (!dates[idx]) return "";
 
         if (needDeal) {
-          joins += ` JOIN daily_deal "${number}_day_ago" ON "0_day_ago".stock_id = "${number}_day_ago".stock_id AND "${number}_day_ago".t = '${dates[idx]}'`;
+          joins += ` JOIN ${dealTable} "${number}_day_ago" ON "0_day_ago".stock_id = "${number}_day_ago".stock_id AND "${number}_day_ago".t = '${dates[idx]}'`;
         }
         if (needSkills) {
           joins += ` LEFT JOIN daily_skills "${number}_day_ago_sk" ON "0_day_ago".stock_id = "${number}_day_ago_sk".stock_id AND "${number}_day_ago_sk".t = '${dates[idx]}'`;
@@ -218,7 +237,7 @@ export class StockDailyQueryBuilder extends BaseQueryBuilder {
 
     const query = `
       SELECT "0_day_ago".stock_id as stock_id
-      FROM daily_deal "0_day_ago"
+      FROM ${dealTable} "0_day_ago"
       LEFT JOIN daily_skills "0_day_ago_sk" ON "0_day_ago".stock_id = "0_day_ago_sk".stock_id AND "0_day_ago".t = "0_day_ago_sk".t
       ${dayJoins}
       WHERE "0_day_ago".t = '${
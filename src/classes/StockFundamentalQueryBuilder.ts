@@ -205,6 +205,22 @@ export class StockFundamentalQueryBuilder {
     return allStockIds[0] || [];
   }
 
+  /**
+   * 轉為 Rust `PromptTemplate.fundamentals` 使用的條件式 (例如 `pe < 15`)，
+   * 由 Rust 端與技術面條件編譯成同一個查詢
+   */
+  public toExpressions(conditions: FundamentalPrompts): string[] {
+    return conditions.flatMap((condition) => {
+      const column = this.mapping[condition.indicator]?.key;
+      const operator = this.operatorMapping[condition.operator];
+      const value = isNaN(Number(condition.value))
+        ? this.mapping[condition.value]?.key
+        : condition.value;
+      if (!column || !operator || value === undefined) return [];
+      return [`${column} ${operator} ${value}`];
+    });
+  }
+
   public getOptions() {
     const indicators = Object.keys(this.mapping);
     const valuesByIndicator = indicators.reduce((acc, indicator) => {
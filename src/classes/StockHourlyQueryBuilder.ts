@@ -73,6 +73,14 @@ export class StockHourlyQueryBuilder extends BaseQueryBuilder {
     cmf: { key: "cmf", group: "_hour_ago_sk" },
     cmf_ema5: { key: "cmf_ema5", group: "_hour_ago_sk" },
     週轉率: { key: "turnover_rate", group: "_hour_ago_sk", unit: "%" },
+    atr: { key: "atr", group: "_hour_ago_sk" },
+    cci: { key: "cci", group: "_hour_ago_sk" },
+    唐奇安上軌: { key: "donchian_ub", group: "_hour_ago_sk" },
+    唐奇安中軌: { key: "donchian_ma", group: "_hour_ago_sk" },
+    唐奇安下軌: { key: "donchian_lb", group: "_hour_ago_sk" },
+    超級趨勢: { key: "supertrend", group: "_hour_ago_sk" },
+    "超級趨勢方向(1多/-1空)": { key: "supertrend_dir", group: "_hour_ago_sk" },
+    市場狀態分數: { key: "mss", group: "_hour_ago_sk" },
   };
 
   protected getSpecificOptions(): Record<string, readonly string[]> {
@@ -62,6 +62,14 @@ export class StockWeeklyQueryBuilder extends BaseQueryBuilder {
     cmf: { key: "cmf", group: "_week_ago_sk" },
     cmf_ema5: { key: "cmf_ema5", group: "_week_ago_sk" },
     週轉率: { key: "turnover_rate", group: "_week_ago_sk", unit: "%" },
+    atr: { key: "atr", group: "_week_ago_sk" },
+    cci: { key: "cci", group: "_week_ago_sk" },
+    唐奇安上軌: { key: "donchian_ub", group: "_week_ago_sk" },
+    唐奇安中軌: { key: "donchian_ma", group: "_week_ago_sk" },
+    唐奇安下軌: { key: "donchian_lb", group: "_week_ago_sk" },
+    超級趨勢: { key: "supertrend", group: "_week_ago_sk" },
+    "超級趨勢方向(1多/-1空)": { key: "supertrend_dir", group: "_week_ago_sk" },
+    市場狀態分數: { key: "mss", group: "_week_ago_sk" },
   };
 
   protected getTimeOptions(): readonly string[] {
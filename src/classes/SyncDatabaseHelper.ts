@@ -11,6 +11,7 @@ import {
   TimeSharingSkillsTableOptions,
   TimeSharingSkillsTableType,
 } from "../types";
+import bumpDataVersion from "../utils/bumpDataVersion";
 
 /**
  * SyncDatabaseHelper - Dedicated helper for Data Sync Engine.
@@ -109,6 +110,7 @@ export default class SyncDatabaseHelper {
           [stockId]
         );
       }
+      await bumpDataVersion(this.db, tables.map(([table]) => table));
     } catch (e) {
       error(`[SyncDB] DeletePartial error for ${stockId}: ${e}`);
     }
@@ -124,6 +126,7 @@ export default class SyncDatabaseHelper {
         `DELETE FROM ${skillTable} WHERE stock_id = $1 AND t >= $2`,
         [stockId, date]
       );
+      await bumpDataVersion(this.db, [dealTable, skillTable]);
       info(`[SyncDB] Cleaned up buffer range for ${stockId} from ${date}`);
     } catch (e) {
       error(`[SyncDB] DeleteRecordsFromDate error: ${e}`);
@@ -141,6 +144,7 @@ export default class SyncDatabaseHelper {
       );
       const sqlBase = `INSERT OR REPLACE INTO ${table} (stock_id, t, c, o, h, l, v) VALUES`;
       await this.executeBatch(sqlBase, entries);
+      await bumpDataVersion(this.db, [table]);
     } catch (e) {
       error(`[SyncDB] SaveDeals error: ${e}`);
     }
@@ -177,6 +181,7 @@ export default class SyncDatabaseHelper {
 
       const sqlBase = `INSERT OR REPLACE INTO ${table} (${columns.join(", ")}) VALUES`;
       await this.executeBatch(sqlBase, entries, 50); // Small batch for very wide table
+      await bumpDataVersion(this.db, [table]);
     } catch (e) {
       error(`[SyncDB] SaveSkills error: ${e}`);
     }
@@ -195,48 +200,84 @@ export default class SyncDatabaseHelper {
         metrics.gross_profit_margin, metrics.operating_margin, metrics.pre_tax_profit_margin,
         metrics.roa, metrics.roe, metrics.book_value_per_share, new Date().toISOString()
       ]);
+      await bumpDataVersion(this.db, ["financial_metric"]);
     } catch (e) {
       error(`[SyncDB] SaveFinancialMetrics error: ${e}`);
     }
   }
 
+  /**
+   * 將 `recent_fundamental` 格式 (近四月營收、近四季 / 四年 EPS) 拆成逐期資料，
+   * 累積寫入 `monthly_revenue` 與 `quarterly_eps`，已存在的期別只補上非空欄位。
+   */
   async saveRecentFundamentals(data: any) {
     try {
-      const columns = [
-        "stock_id",
-        "revenue_recent_m1_mom", "revenue_recent_m1_yoy", "revenue_recent_m1_yoy_acc", "revenue_recent_m1_name",
-        "revenue_recent_m2_mom", "revenue_recent_m2_yoy", "revenue_recent_m2_yoy_acc", "revenue_recent_m2_name",
-        "revenue_recent_m3_mom", "revenue_recent_m3_yoy", "revenue_recent_m3_yoy_acc", "revenue_recent_m3_name",
-        "revenue_recent_m4_mom", "revenue_recent_m4_yoy", "revenue_recent_m4_yoy_acc", "revenue_recent_m4_name",
-        "eps_recent_q1", "eps_recent_q1_name", "eps_recent_q2", "eps_recent_q2_name", "eps_recent_q3", "eps_recent_q3_name", "eps_recent_q4", "eps_recent_q4_name",
-        "eps_recent_y1", "eps_recent_y1_name", "eps_recent_y2", "eps_recent_y2_name", "eps_recent_y3", "eps_recent_y3_name", "eps_recent_y4", "eps_recent_y4_name"
-      ];
-      
-      const values = columns.map(col => data[col] ?? null);
-      const placeholders = columns.map((_, i) => `$${i + 1}`).join(", ");
-      const sql = `INSERT OR REPLACE INTO recent_fundamental (${columns.join(", ")}) VALUES (${placeholders})`;
-      
-      await this.db.execute(sql, values);
+      for (let i = 1; i <= 4; i++) {
+        const name: string | undefined = data[`revenue_recent_m${i}_name`];
+        const period = name?.replace(/\D/g, "");
+        if (period?.length !== 6) continue;
+        await this.db.execute(
+          `INSERT INTO monthly_revenue (stock_id, period, revenue, mom, yoy, yoy_acc)
+           VALUES ($1, $2, $3, $4, $5, $6)
+           ON CONFLICT(stock_id, period) DO UPDATE SET
+             revenue = COALESCE(excluded.revenue, revenue),
+             mom = COALESCE(excluded.mom, mom),
+             yoy = COALESCE(excluded.yoy, yoy),
+             yoy_acc = COALESCE(excluded.yoy_acc, yoy_acc)`,
+          [
+            data.stock_id,
+            period,
+            data[`revenue_recent_m${i}`] ?? null,
+            data[`revenue_recent_m${i}_mom`] ?? null,
+            data[`revenue_recent_m${i}_yoy`] ?? null,
+            data[`revenue_recent_m${i}_yoy_acc`] ?? null,
+          ],
+        );
+      }
+
+      for (const kind of ["q", "y"]) {
+        for (let i = 1; i <= 4; i++) {
+          const eps = data[`eps_recent_${kind}${i}`];
+          const name: string | undefined = data[`eps_recent_${kind}${i}_name`];
+          // 季度為 "2026 Q2"，年度為 "2025" (quarter 記為 0)
+          const match = name?.match(/^(\d{4})(?:\s*Q(\d))?$/);
+          if (eps == null || !match) continue;
+          await this.db.execute(
+            "INSERT OR REPLACE INTO quarterly_eps (stock_id, year, quarter, eps) VALUES ($1, $2, $3, $4)",
+            [data.stock_id, Number(match[1]), Number(match[2] ?? 0), eps],
+          );
+        }
+      }
+      await bumpDataVersion(this.db, ["monthly_revenue", "quarterly_eps"]);
     } catch (e) {
       error(`[SyncDB] SaveRecentFundamentals error: ${e}`);
     }
   }
 
+  /**
+   * 將 `investor_positions` 格式 (近四週) 拆成逐週資料累積寫入 `weekly_holder_ratio`
+   */
   async saveInvestorPositions(data: any) {
     try {
-      const columns = [
-        "stock_id",
-        "recent_w1_foreign_ratio", "recent_w1_big_investor_ratio", "recent_w1_name",
-        "recent_w2_foreign_ratio", "recent_w2_big_investor_ratio", "recent_w2_name",
-        "recent_w3_foreign_ratio", "recent_w3_big_investor_ratio", "recent_w3_name",
-        "recent_w4_foreign_ratio", "recent_w4_big_investor_ratio", "recent_w4_name"
-      ];
-      
-      const values = columns.map(col => data[col] ?? null);
-      const placeholders = columns.map((_, i) => `$${i + 1}`).join(", ");
-      const sql = `INSERT OR REPLACE INTO investor_positions (${columns.join(", ")}) VALUES (${placeholders})`;
-      
-      await this.db.execute(sql, values);
+      for (let i = 1; i <= 4; i++) {
+        const name: string | undefined = data[`recent_w${i}_name`];
+        const t = name?.replace(/\D/g, "");
+        if (t?.length !== 8) continue;
+        await this.db.execute(
+          `INSERT INTO weekly_holder_ratio (stock_id, t, foreign_ratio, big_investor_ratio)
+           VALUES ($1, $2, $3, $4)
+           ON CONFLICT(stock_id, t) DO UPDATE SET
+             foreign_ratio = COALESCE(excluded.foreign_ratio, foreign_ratio),
+             big_investor_ratio = COALESCE(excluded.big_investor_ratio, big_investor_ratio)`,
+          [
+            data.stock_id,
+            t,
+            data[`recent_w${i}_foreign_ratio`] ?? null,
+            data[`recent_w${i}_big_investor_ratio`] ?? null,
+          ],
+        );
+      }
+      await bumpDataVersion(this.db, ["weekly_holder_ratio"]);
     } catch (e) {
       error(`[SyncDB] SaveInvestorPositions error: ${e}`);
     }
@@ -249,9 +290,10 @@ export default class SyncDatabaseHelper {
     try {
       info(`[SyncDB] Saving stock ${stock.stock_id}: name=${stock.stock_name}, shares=${stock.issued_shares}`);
       await this.db.execute(
-        "INSERT OR REPLACE INTO stock (stock_id, stock_name, industry_group, market_type, issued_shares) VALUES ($1, $2, $3, $4, $5)",
-        [stock.stock_id, stock.stock_name, stock.industry_group, stock.market_type, stock.issued_shares || null]
+        "INSERT OR REPLACE INTO stock (stock_id, stock_name, industry_group, market_type, issued_shares, security_type) VALUES ($1, $2, $3, $4, $5, $6)",
+        [stock.stock_id, stock.stock_name, stock.industry_group, stock.market_type, stock.issued_shares || null, stock.security_type ?? "stock"]
       );
+      await bumpDataVersion(this.db, ["stock"]);
     } catch (e) {
       error(`[SyncDB] SaveStock error for ${stock.stock_id}: ${e}`);
     }
@@ -331,6 +373,7 @@ export default class SyncDatabaseHelper {
       );
       const sqlBase = `INSERT OR REPLACE INTO ${table} (stock_id, ts, c, o, h, l, v) VALUES`;
       await this.executeBatch(sqlBase, entries);
+      await bumpDataVersion(this.db, [table]);
     } catch (e) {
       error(`[SyncDB] SaveTimeSharingDeals error: ${e}`);
     }
@@ -348,6 +391,7 @@ export default class SyncDatabaseHelper {
       });
       const sqlBase = `INSERT OR REPLACE INTO ${table} (stock_id, ts, ma5, ma5_ded, ma10, ma10_ded, ma20, ma20_ded, ma30, ma30_ded, ma50, ma50_ded, ma60, ma60_ded, ma120, ma120_ded, ma240, ma240_ded, ema5, ema10, ema20, ema60, ema120, ema200, macd, dif, osc, k, d, j, rsi5, rsi10, bollUb, bollMa, bollLb, obv, obv_ma5, obv_ma10, obv_ma20, obv_ma60, obv_ema5, obv_ema10, obv_ema20, obv_ema60, mfi, tenkan, kijun, senkouA, senkouB, chikou, di_plus, di_minus, adx, cmf, cmf_ema5, turnover_rate) VALUES`;
       await this.executeBatch(sqlBase, entries, 50);
+      await bumpDataVersion(this.db, [table]);
     } catch (e) {
       error(`[SyncDB] SaveTimeSharingSkills error: ${e}`);
     }
@@ -362,6 +406,7 @@ export default class SyncDatabaseHelper {
       await this.db.execute("DELETE FROM daily_skills WHERE t LIKE '%-%'");
       await this.db.execute("DELETE FROM weekly_deal WHERE t LIKE '%-%'");
       await this.db.execute("DELETE FROM weekly_skills WHERE t LIKE '%-%'");
+      await bumpDataVersion(this.db, ["daily_deal", "daily_skills", "weekly_deal", "weekly_skills"]);
       info("[SyncDB] Cleaned up records with dashed dates.");
     } catch (e) {
       error(`[SyncDB] Cleanup error: ${e}`);
@@ -1,19 +1,6 @@
-import {
-  Boll,
-  Cmf,
-  dateFormat,
-  Dmi,
-  Ema,
-  Ichimoku,
-  Kd,
-  Ma,
-  Macd,
-  Mfi,
-  Obv,
-  ObvEma,
-  Rsi,
-} from "@ch20026103/anysis";
+import { dateFormat } from "@ch20026103/anysis";
 import { Mode } from "@ch20026103/anysis/dist/esm/stockSkills/utils/dateFormat";
+import { invoke } from "@tauri-apps/api/core";
 import { emit, listen } from "@tauri-apps/api/event";
 import { error, info } from "@tauri-apps/plugin-log";
 import useSyncDashboardStore, {
@@ -22,13 +9,9 @@ import useSyncDashboardStore, {
 import { fetchStockExtData, fetchStockProfile } from "../tools/stockScraper";
 import {
   DealTableOptions,
-  DealTableType,
   SkillsTableOptions,
-  SkillsTableType,
   StockTableType,
   TaListType,
-  TimeSharingDealTableOptions,
-  TimeSharingSkillsTableOptions,
   UrlTaPerdOptions,
   UrlType,
 } from "../types";
@@ -708,16 +691,12 @@ export default class SyncEngine {
       );
     }
 
-    // Calculate Indicators (Simplified for brevity, following original logic patterns)
-    // In a real implementation, we'd use the full anysis calculators here
-    const { deals, skills } = await this.calculateIndicators(
+    // 指標由 Rust 端以保存的狀態增量計算，只需推進新增的 K 線
+    await this.appendBars(
       stock,
-      ta,
-      missing.map((m) => String(m.t)),
+      dealTable === DealTableOptions.WeeklyDeal ? "weekly" : "daily",
+      missing,
     );
-
-    await this.dbHelper.saveDeals(deals, dealTable);
-    await this.dbHelper.saveSkills(skills, skillTable);
   }
 
   private async processHourly(stock: StockTableType, ta: TaListType) {
@@ -749,198 +728,26 @@ export default class SyncEngine {
     });
     if (missing.length === 0) return;
 
-    const { deals, skills } = await this.calculateHourlyIndicators(
-      stock,
-      ta,
-      missing.map((m) => String(m.t)),
-    );
-    await this.dbHelper.saveTimeSharingDeals(
-      deals,
-      TimeSharingDealTableOptions.HourlyDeal,
-    );
-    await this.dbHelper.saveTimeSharingSkills(
-      skills,
-      TimeSharingSkillsTableOptions.HourlySkills,
-    );
+    await this.appendBars(stock, "hourly", missing);
   }
 
-  // --- Support methods for calculation (Internalized logic from SqliteDataManager) ---
-
-  private async calculateIndicators(
+  private async appendBars(
     stock: StockTableType,
+    timeframe: "daily" | "weekly" | "hourly",
     ta: TaListType,
-    missingDates: string[],
   ) {
-    const deals: DealTableType[] = [];
-    const skills: SkillsTableType[] = [];
-    const missingSet = new Set(missingDates);
-
-    // Initialize Calculators
-    const boll = new Boll();
-    const ma = new Ma();
-    const ema = new Ema();
-    const macd = new Macd();
-    const kd = new Kd();
-    const rsi = new Rsi();
-    const obv = new Obv();
-    const obvEma = new ObvEma();
-    const mfi = new Mfi();
-    const ichimoku = new Ichimoku();
-    const dmi = new Dmi();
-    const cmf = new Cmf();
-
-    let state: any = {
-      ma5: ma.init(ta[0], 5),
-      ma10: ma.init(ta[0], 10),
-      ma20: ma.init(ta[0], 20),
-      ma30: ma.init(ta[0], 30),
-      ma50: ma.init(ta[0], 50),
-      ma60: ma.init(ta[0], 60),
-      ma120: ma.init(ta[0], 120),
-      ma240: ma.init(ta[0], 240),
-      ema5: ema.init(ta[0], 5),
-      ema10: ema.init(ta[0], 10),
-      ema20: ema.init(ta[0], 20),
-      ema60: ema.init(ta[0], 60),
-      ema120: ema.init(ta[0], 120),
-      ema200: ema.init(ta[0], 200),
-      boll: boll.init(ta[0]),
-      macd: macd.init(ta[0]),
-      kd: kd.init(ta[0], 9),
-      rsi5: rsi.init(ta[0], 5),
-      rsi10: rsi.init(ta[0], 10),
-      obv: obv.init(ta[0]),
-      obv_ma5: obvEma.init(obv.init(ta[0]).obv, 5),
-      obv_ma10: obvEma.init(obv.init(ta[0]).obv, 10),
-      obv_ma20: obvEma.init(obv.init(ta[0]).obv, 20),
-      obv_ma60: obvEma.init(obv.init(ta[0]).obv, 60),
-      mfi: mfi.init(ta[0], 14),
-      ichimoku: ichimoku.init(ta[0]),
-      dmi: dmi.init(ta[0], 14),
-      cmf: cmf.init(ta[0]),
-    };
-
-    for (let i = 0; i < ta.length; i++) {
-      const val = ta[i];
-      const tStr = String(val.t);
-
-      if (i % 50 === 0) await yieldControl();
-
-      if (i > 0) {
-        state.ma5 = ma.next(val, state.ma5, 5);
-        state.ma10 = ma.next(val, state.ma10, 10);
-        state.ma20 = ma.next(val, state.ma20, 20);
-        state.ma30 = ma.next(val, state.ma30, 30);
-        state.ma50 = ma.next(val, state.ma50, 50);
-        state.ma60 = ma.next(val, state.ma60, 60);
-        state.ma120 = ma.next(val, state.ma120, 120);
-        state.ma240 = ma.next(val, state.ma240, 240);
-        state.ema5 = ema.next(val, state.ema5, 5);
-        state.ema10 = ema.next(val, state.ema10, 10);
-        state.ema20 = ema.next(val, state.ema20, 20);
-        state.ema60 = ema.next(val, state.ema60, 60);
-        state.ema120 = ema.next(val, state.ema120, 120);
-        state.ema200 = ema.next(val, state.ema200, 200);
-        state.boll = boll.next(val, state.boll, 20);
-        state.macd = macd.next(val, state.macd);
-        state.kd = kd.next(val, state.kd, 9);
-        state.rsi5 = rsi.next(val, state.rsi5, 5);
-        state.rsi10 = rsi.next(val, state.rsi10, 10);
-        state.obv = obv.next(val, state.obv);
-        state.obv_ma5 = obvEma.next(state.obv.obv, state.obv_ma5, 5);
-        state.obv_ma10 = obvEma.next(state.obv.obv, state.obv_ma10, 10);
-        state.obv_ma20 = obvEma.next(state.obv.obv, state.obv_ma20, 20);
-        state.obv_ma60 = obvEma.next(state.obv.obv, state.obv_ma60, 60);
-        state.mfi = mfi.next(val, state.mfi, 14);
-        state.ichimoku = ichimoku.next(val, state.ichimoku);
-        state.dmi = dmi.next(val, state.dmi, 14);
-        state.cmf = cmf.next(val, state.cmf, 21, 5);
-      }
-
-      if (missingSet.has(tStr)) {
-        deals.push({ stock_id: stock.stock_id, ...val, t: tStr });
-        skills.push({
-          stock_id: stock.stock_id,
-          t: tStr,
-          ma5: state.ma5.ma,
-          ma5_ded: state.ma5.exclusionValue["d-1"],
-          ma10: state.ma10.ma,
-          ma10_ded: state.ma10.exclusionValue["d-1"],
-          ma20: state.ma20.ma,
-          ma20_ded: state.ma20.exclusionValue["d-1"],
-          ma30: state.ma30.ma,
-          ma30_ded: state.ma30.exclusionValue["d-1"],
-          ma50: state.ma50.ma,
-          ma50_ded: state.ma50.exclusionValue["d-1"],
-          ma60: state.ma60.ma,
-          ma60_ded: state.ma60.exclusionValue["d-1"],
-          ma120: state.ma120.ma,
-          ma120_ded: state.ma120.exclusionValue["d-1"],
-          ma240: state.ma240.ma,
-          ma240_ded: state.ma240.exclusionValue["d-1"],
-          ema5: state.ema5.ema,
-          ema10: state.ema10.ema,
-          ema20: state.ema20.ema,
-          ema60: state.ema60.ema,
-          ema120: state.ema120.ema,
-          ema200: state.ema200.ema,
-          macd: state.macd.macd,
-          dif: state.macd.dif[state.macd.dif.length - 1] || 0,
-          osc: state.macd.osc,
-          k: state.kd.k,
-          d: state.kd.d,
-          j: state.kd.j,
-          rsi5: state.rsi5.rsi,
-          rsi10: state.rsi10.rsi,
-          bollUb: state.boll.bollUb,
-          bollMa: state.boll.bollMa,
-          bollLb: state.boll.bollLb,
-          obv: state.obv.obv,
-          obv_ma5: state.obv_ma5.ma,
-          obv_ma10: state.obv_ma10.ma,
-          obv_ma20: state.obv_ma20.ma,
-          obv_ma60: state.obv_ma60.ma,
-          obv_ema5: state.obv_ma5.ema,
-          obv_ema10: state.obv_ma10.ema,
-          obv_ema20: state.obv_ma20.ema,
-          obv_ema60: state.obv_ma60.ema,
-          mfi: state.mfi.mfi,
-          tenkan: state.ichimoku.ichimoku.tenkan,
-          kijun: state.ichimoku.ichimoku.kijun,
-          senkouA: state.ichimoku.ichimoku.senkouA,
-          senkouB: state.ichimoku.ichimoku.senkouB,
-          chikou: state.ichimoku.ichimoku.chikou,
-          di_plus: state.dmi.pDi,
-          di_minus: state.dmi.mDi,
-          adx: state.dmi.adx,
-          cmf: state.cmf.cmf,
-          cmf_ema5: state.cmf.ema,
-          turnover_rate: stock.issued_shares
-            ? ((val.v * 1000) / stock.issued_shares) * 100
-            : 0,
-        });
-      }
-    }
-
-    return { deals, skills };
-  }
-
-  private async calculateHourlyIndicators(
-    stock: StockTableType,
-    ta: TaListType,
-    missingTs: string[],
-  ) {
-    // Similar to calculateIndicators but for hourly data
-    // For brevity, using the same pattern but mapping to TimeSharing types
-    const { deals, skills } = await this.calculateIndicators(
-      stock,
-      ta,
-      missingTs,
-    );
-    return {
-      deals: deals.map((d) => ({ ...d, ts: d.t })),
-      skills: skills.map((s) => ({ ...s, ts: s.t })),
-    } as any;
+    await invoke<number>("append_bars", {
+      stockId: stock.stock_id,
+      timeframe,
+      bars: ta.map((item) => ({
+        t: String(item.t),
+        o: item.o,
+        h: item.h,
+        l: item.l,
+        c: item.c,
+        v: Math.round(item.v || 0),
+      })),
+    });
   }
 
   private async waitForCoolDown() {
@@ -0,0 +1,89 @@
+import { FileDownload } from "@mui/icons-material";
+import { IconButton, Menu, MenuItem, Tooltip } from "@mui/material";
+import { invoke } from "@tauri-apps/api/core";
+import { error } from "@tauri-apps/plugin-log";
+import React, { useState } from "react";
+import { toast } from "react-toastify";
+
+export type ExportFormat = "csv" | "xlsx" | "json";
+
+/** 對應 Rust `export::ExportData` */
+export type ExportData =
+  | { source: "screen"; date: string; rows: object[] }
+  | { source: "backtest"; trades: object[] };
+
+const options: {
+  label: string;
+  format: ExportFormat;
+  include_fundamentals: boolean;
+}[] = [
+  { label: "CSV", format: "csv", include_fundamentals: false },
+  { label: "Excel (XLSX)", format: "xlsx", include_fundamentals: false },
+  { label: "JSON", format: "json", include_fundamentals: false },
+  { label: "Excel (含基本面)", format: "xlsx", include_fundamentals: true },
+];
+
+export default function ExportButton({
+  getData,
+  fileName,
+  disabled,
+}: {
+  getData: () => ExportData;
+  fileName?: string;
+  disabled?: boolean;
+}) {
+  const [anchorEl, setAnchorEl] = useState<null | HTMLElement>(null);
+  const [exporting, setExporting] = useState(false);
+
+  const handleClick = (event: React.MouseEvent<HTMLElement>) => {
+    setAnchorEl(event.currentTarget);
+  };
+
+  const handleExport = async (option: (typeof options)[number]) => {
+    setAnchorEl(null);
+    setExporting(true);
+    try {
+      const path = await invoke<string | null>("export_results", {
+        request: {
+          ...getData(),
+          format: option.format,
+          include_fundamentals: option.include_fundamentals,
+          file_name: fileName,
+        },
+      });
+      if (path) toast.success(`已匯出至 ${path}`);
+    } catch (e) {
+      error(`[ExportButton] export_results: ${e}`);
+      toast.error(`匯出失敗: ${e}`);
+    } finally {
+      setExporting(false);
+    }
+  };
+
+  return (
+    <>
+      <Tooltip title="匯出">
+        <span>
+          <IconButton
+            size="small"
+            onClick={handleClick}
+            disabled={disabled || exporting}
+          >
+            <FileDownload fontSize="small" />
+          </IconButton>
+        </span>
+      </Tooltip>
+      <Menu
+        anchorEl={anchorEl}
+        open={Boolean(anchorEl)}
+        onClose={() => setAnchorEl(null)}
+      >
+        {options.map((option) => (
+          <MenuItem key={option.label} onClick={() => handleExport(option)}>
+            {option.label}
+          </MenuItem>
+        ))}
+      </Menu>
+    </>
+  );
+}
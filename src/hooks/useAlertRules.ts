@@ -0,0 +1,38 @@
+import { error } from "@tauri-apps/plugin-log";
+import { useEffect } from "react";
+import useCloudStore from "../store/Cloud.store";
+import { getStore } from "../store/Setting.store";
+import compilePromptTemplate from "../utils/compilePromptTemplate";
+
+/**
+ * 將警示條件編譯為 SQL 樣板並與自選股一併寫入 store.json，
+ * 供同步完成後 Rust 端在背景評估並送出系統通知。
+ */
+export default function useAlertRules() {
+  const { alarms, watchStocks } = useCloudStore();
+
+  useEffect(() => {
+    (async () => {
+      try {
+        const store = await getStore();
+        const adjusted =
+          (await store.get<string>("priceAdjustment")) === "adjusted";
+        const rules = Object.entries(alarms)
+          .map(([id, item]) => ({
+            id,
+            name: item.name,
+            sql: compilePromptTemplate(item, adjusted),
+          }))
+          .filter((rule) => rule.sql);
+        await store.set("alertRules", rules);
+        await store.set(
+          "alertStocks",
+          watchStocks.map((item) => item.stock_id),
+        );
+        await store.save();
+      } catch (e) {
+        error(`[useAlertRules] ${e}`);
+      }
+    })();
+  }, [alarms, watchStocks]);
+}
@@ -3,6 +3,7 @@ import { stockDailyQueryBuilder } from "../classes/StockDailyQueryBuilder";
 import { stockHourlyQueryBuilder } from "../classes/StockHourlyQueryBuilder";
 import { stockWeeklyQueryBuilder } from "../classes/StockWeeklyQueryBuilder";
 import { DatabaseContext } from "../context/DatabaseContext";
+import { getStore } from "../store/Setting.store";
 import useSchoiceStore from "../store/Schoice.store";
 import { PromptItem } from "../types";
 import useDatabaseQuery from "./useDatabaseQuery";
@@ -68,6 +69,8 @@ export default function useFindStocksByPrompt() {
 
       let dailySQL = "";
       if (select.conditions.daily.length > 0) {
+        const store = await getStore();
+        const priceAdjustment = await store.get<string>("priceAdjustment");
         const customDailyConditions = select.conditions.daily.map((prompt) =>
           stockDailyQueryBuilder.generateExpression(prompt).join(" "),
         );
@@ -75,6 +78,7 @@ export default function useFindStocksByPrompt() {
           conditions: customDailyConditions,
           dates: dates.filter((_, index) => index >= dateIndex),
           stockIds,
+          adjusted: priceAdjustment === "adjusted" && dbType !== "postgres",
         });
         dailySQL = sqlDailyQuery;
       }
@@ -114,7 +118,7 @@ export default function useFindStocksByPrompt() {
 
       return [dailySQL, weeklySQL, hourlySQL];
     },
-    [dates, dateIndex, filterStocks, getWeekDates, getHourDates, weekIndex],
+    [dates, dateIndex, dbType, filterStocks, getWeekDates, getHourDates, weekIndex],
   );
 
   const getCombinedSqlScript = useCallback((sqls: string[]) => {
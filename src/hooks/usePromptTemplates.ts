@@ -0,0 +1,42 @@
+import { error } from "@tauri-apps/plugin-log";
+import { useEffect } from "react";
+import { stockFundamentalQueryBuilder } from "../classes/StockFundamentalQueryBuilder";
+import useCloudStore from "../store/Cloud.store";
+import { getStore } from "../store/Setting.store";
+import compilePromptTemplate from "../utils/compilePromptTemplate";
+
+/**
+ * 將多空策略編譯為 SQL 樣板寫入 store.json，
+ * 供本地 API 的 `POST /screen` 以策略 id 或名稱執行，
+ * 並帶上目前的基本面條件，由 Rust 端合併為同一個查詢。
+ */
+export default function usePromptTemplates() {
+  const { bulls, bears, fundamentalCondition } = useCloudStore();
+
+  useEffect(() => {
+    (async () => {
+      try {
+        const store = await getStore();
+        const adjusted =
+          (await store.get<string>("priceAdjustment")) === "adjusted";
+        const fundamentals = stockFundamentalQueryBuilder.toExpressions(
+          fundamentalCondition ?? [],
+        );
+        await store.set(
+          "promptTemplates",
+          Object.entries({ ...bulls, ...bears })
+            .map(([id, item]) => ({
+              id,
+              name: item.name,
+              sql: compilePromptTemplate(item, adjusted),
+              fundamentals,
+            }))
+            .filter((prompt) => prompt.sql || prompt.fundamentals.length > 0),
+        );
+        await store.save();
+      } catch (e) {
+        error(`[usePromptTemplates] ${e}`);
+      }
+    })();
+  }, [bulls, bears, fundamentalCondition]);
+}
@@ -0,0 +1,138 @@
+import { invoke } from "@tauri-apps/api/core";
+import { useCallback, useContext } from "react";
+import { stockFundamentalQueryBuilder } from "../classes/StockFundamentalQueryBuilder";
+import { DatabaseContext } from "../context/DatabaseContext";
+import useCloudStore from "../store/Cloud.store";
+import useSchoiceStore from "../store/Schoice.store";
+import { getStore } from "../store/Setting.store";
+import { FundamentalPrompts, PromptItem, StockTableType } from "../types";
+import compilePromptTemplate from "../utils/compilePromptTemplate";
+import useDatabaseQuery from "./useDatabaseQuery";
+import useFindStocksByPrompt from "./useFindStocksByPrompt";
+
+/**
+ * 以 Rust `run_screen` 執行策略：技術面 SQL 與基本面條件編譯為同一個查詢，
+ * 資料未變動時直接取用快取結果。雲端資料庫不經由 Rust 端，仍以前端組出的 SQL 查詢。
+ */
+export default function useScreenStocks() {
+  const { dates, dbType } = useContext(DatabaseContext);
+  const dateIndex = useSchoiceStore((state) => state.dateIndex);
+  const filterStocks = useSchoiceStore((state) => state.filterStocks);
+  const fundamentalCondition = useCloudStore(
+    (state) => state.fundamentalCondition,
+  );
+  const { getPromptSqlScripts, getCombinedSqlScript } = useFindStocksByPrompt();
+  const query = useDatabaseQuery();
+
+  /** 本地資料庫：將策略編譯為樣板交給 Rust 端執行 */
+  const runScreen = useCallback(
+    async (
+      id: string,
+      item: PromptItem,
+      fundamentals: string[],
+    ): Promise<StockTableType[]> => {
+      const store = await getStore();
+      const adjusted =
+        (await store.get<string>("priceAdjustment")) === "adjusted";
+      const sql = compilePromptTemplate(item, adjusted);
+      if (!sql && fundamentals.length === 0) return [];
+      return invoke<StockTableType[]>("run_screen", {
+        template: { id, name: item.name, sql, fundamentals },
+        date: dates[dateIndex] ?? null,
+      });
+    },
+    [dates, dateIndex],
+  );
+
+  const screenPrompt = useCallback(
+    async (id: string, item: PromptItem): Promise<StockTableType[]> => {
+      if (dbType === "sqlite") {
+        // 篩選器被清除 (filterStocks 為 null) 時不套用基本面條件
+        const fundamentals = filterStocks
+          ? stockFundamentalQueryBuilder.toExpressions(
+              fundamentalCondition ?? [],
+            )
+          : [];
+        return runScreen(id, item, fundamentals);
+      }
+
+      // 如果 filterStocks 是空陣列 []，傳遞 undefined 給 SQL 產生器以避免 IN ('') 查不到資料
+      const stockIds =
+        filterStocks && filterStocks.length > 0
+          ? filterStocks.map((stock) => stock.stock_id)
+          : undefined;
+      const sqls = await getPromptSqlScripts(item, stockIds);
+      const combinedSQL = getCombinedSqlScript(sqls);
+      if (!combinedSQL || !combinedSQL.trim()) return [];
+      const res: { stock_id: string }[] | undefined = await query(combinedSQL);
+      if (!res || res.length === 0) return [];
+      const data: StockTableType[] | null = await query(
+        `SELECT * FROM stock WHERE stock_id IN (${res
+          .map((r) => `'${r.stock_id}'`)
+          .join(",")}) Order By stock_id ASC`,
+      );
+      return data || [];
+    },
+    [
+      dbType,
+      filterStocks,
+      fundamentalCondition,
+      runScreen,
+      getPromptSqlScripts,
+      getCombinedSqlScript,
+      query,
+    ],
+  );
+
+  /** 只檢查指定股票 (例如自選股) 是否符合策略，不套用基本面篩選器 */
+  const screenStockIds = useCallback(
+    async (
+      id: string,
+      item: PromptItem,
+      stockIds: string[],
+    ): Promise<string[]> => {
+      if (stockIds.length === 0) return [];
+      if (dbType === "sqlite") {
+        const targets = new Set(stockIds);
+        return (await runScreen(id, item, []))
+          .map((stock) => stock.stock_id)
+          .filter((stockId) => targets.has(stockId));
+      }
+      const sqls = await getPromptSqlScripts(item, stockIds);
+      const combinedSQL = getCombinedSqlScript(sqls);
+      if (!combinedSQL || !combinedSQL.trim()) return [];
+      const res: { stock_id: string }[] | undefined = await query(combinedSQL);
+      return (res ?? []).map((r) => r.stock_id);
+    },
+    [dbType, runScreen, getPromptSqlScripts, getCombinedSqlScript, query],
+  );
+
+  /** 只有基本面條件時的符合清單，取最新資料 */
+  const screenFundamentals = useCallback(
+    async (conditions: FundamentalPrompts): Promise<StockTableType[]> => {
+      const fundamentals =
+        stockFundamentalQueryBuilder.toExpressions(conditions);
+      if (fundamentals.length === 0) return [];
+      if (dbType === "sqlite") {
+        return invoke<StockTableType[]>("run_screen", {
+          template: { name: "fundamentals", fundamentals },
+          date: null,
+        });
+      }
+      const stockIds =
+        await stockFundamentalQueryBuilder.getStocksByConditions({
+          conditions,
+        });
+      if (stockIds.length === 0) return [];
+      const data: StockTableType[] | null = await query(
+        `SELECT * FROM stock WHERE stock_id IN (${stockIds
+          .map((id) => `'${id}'`)
+          .join(",")})`,
+      );
+      return data || [];
+    },
+    [dbType, query],
+  );
+
+  return { screenPrompt, screenStockIds, screenFundamentals };
+}
@@ -3,6 +3,7 @@ import { Mode } from "@ch20026103/anysis/dist/esm/stockSkills/utils/dateFormat";
 import { Context } from "@ch20026103/backtest-lib";
 import {
   Paper,
+  Stack,
   Table,
   TableBody,
   TableCell,
@@ -13,6 +14,7 @@ import {
 } from "@mui/material";
 import { alpha, styled } from "@mui/material/styles";
 import { useTranslation } from "react-i18next";
+import ExportButton from "../../../../components/ExportButton";
 import StockTextButton from "./StockTextButton";
 
 const GlassPaper = styled(Paper)(({ theme }) => ({
@@ -53,19 +55,42 @@ export default function History({ ctx }: { ctx: Context }) {
 
   return (
     <GlassPaper elevation={0}>
-      <Typography
-        variant="caption"
-        fontWeight={800}
-        color="text.secondary"
-        sx={{
-          textTransform: "uppercase",
-          mb: 2,
-          display: "block",
-          letterSpacing: "0.1em",
-        }}
+      <Stack
+        direction="row"
+        alignItems="center"
+        justifyContent="space-between"
+        mb={2}
       >
-        {t("Pages.Schoice.Backtest.historyTitle")}
-      </Typography>
+        <Typography
+          variant="caption"
+          fontWeight={800}
+          color="text.secondary"
+          sx={{
+            textTransform: "uppercase",
+            display: "block",
+            letterSpacing: "0.1em",
+          }}
+        >
+          {t("Pages.Schoice.Backtest.historyTitle")}
+        </Typography>
+        <ExportButton
+          fileName="backtest_history"
+          disabled={!ctx?.record.history}
+          getData={() => ({
+            source: "backtest",
+            // 與下方表格相同的每股損益，匯出時不再由 Rust 端重算
+            trades: Object.values(ctx?.record.history || {}).map((item) => {
+              const profit = item.sellPrice - item.buyPrice;
+              return {
+                ...item,
+                profit,
+                returnPct:
+                  item.buyPrice > 0 ? (profit / item.buyPrice) * 100 : null,
+              };
+            }),
+          })}
+        />
+      </Stack>
       <TableContainer sx={{ flex: 1, maxHeight: "500px" }}>
         <Table stickyHeader size="small">
           <TableHead>
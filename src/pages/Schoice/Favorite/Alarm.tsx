@@ -14,8 +14,7 @@ import {
 import { alpha, styled } from "@mui/material/styles";
 import { useEffect, useState } from "react";
 import { useTranslation } from "react-i18next";
-import useDatabaseQuery from "../../../hooks/useDatabaseQuery";
-import useFindStocksByPrompt from "../../../hooks/useFindStocksByPrompt";
+import useScreenStocks from "../../../hooks/useScreenStocks";
 import useCloudStore from "../../../store/Cloud.store";
 import { StockTableType } from "../../../types";
 
@@ -46,8 +45,7 @@ const ConditionCard = styled(Card)(({ theme }) => ({
 export default function Alarm({ stocks }: { stocks: StockTableType[] }) {
   const { t } = useTranslation();
   const { alarms } = useCloudStore();
-  const { getPromptSqlScripts, getCombinedSqlScript } = useFindStocksByPrompt();
-  const query = useDatabaseQuery();
+  const { screenStockIds } = useScreenStocks();
 
   const [stockAlarmMap, setStockAlarmMap] = useState<Record<string, string[]>>(
     {}
@@ -74,17 +72,10 @@ export default function Alarm({ stocks }: { stocks: StockTableType[] }) {
         for (let key of Object.keys(alarms)) {
           const promptItem = alarms[key];
           try {
-            const sqls = await getPromptSqlScripts(promptItem, stockIds);
-            const conbineSql = getCombinedSqlScript(sqls);
-
-            if (conbineSql) {
-              const ids = (await query(conbineSql)) as { stock_id: string }[];
-              if (ids && Array.isArray(ids)) {
-                for (let { stock_id } of ids) {
-                  if (stocksObj[stock_id]) {
-                    stocksObj[stock_id].push(alarms[key].name);
-                  }
-                }
+            const ids = await screenStockIds(key, promptItem, stockIds);
+            for (let stock_id of ids) {
+              if (stocksObj[stock_id]) {
+                stocksObj[stock_id].push(alarms[key].name);
               }
             }
           } catch (itemErr) {
@@ -107,7 +98,7 @@ export default function Alarm({ stocks }: { stocks: StockTableType[] }) {
     return () => {
       ignore = true;
     };
-  }, [alarms, stocks, query, getPromptSqlScripts, getCombinedSqlScript]);
+  }, [alarms, stocks, screenStockIds]);
 
   const alarmStocks = stocks.filter(
     (stock) =>
@@ -18,6 +18,7 @@ import {
   Typography,
 } from "@mui/material";
 import { alpha, styled } from "@mui/material/styles";
+import { error } from "@tauri-apps/plugin-log";
 import { AnimatePresence, motion } from "framer-motion";
 import { useContext, useEffect, useState } from "react";
 import { useTranslation } from "react-i18next";
@@ -26,7 +27,7 @@ import { stockFundamentalQueryBuilder } from "../../../classes/StockFundamentalQ
 import ResultTable from "../../../components/ResultTable/ResultTable";
 import { DatabaseContext } from "../../../context/DatabaseContext";
 import { useUser } from "../../../context/UserContext";
-import useDatabaseQuery from "../../../hooks/useDatabaseQuery";
+import useScreenStocks from "../../../hooks/useScreenStocks";
 import useCloudStore from "../../../store/Cloud.store";
 import useSchoiceStore from "../../../store/Schoice.store";
 import {
@@ -90,7 +91,7 @@ export default function Fundamental() {
   const { t } = useTranslation();
   const { indicators, operators, valuesByIndicator } =
     stockFundamentalQueryBuilder.getOptions();
-  const query = useDatabaseQuery();
+  const { screenFundamentals } = useScreenStocks();
   const { db } = useContext(DatabaseContext);
   const { user } = useUser();
   const { fundamentalCondition, reload, setFundamentalCondition } =
@@ -127,21 +128,16 @@ export default function Fundamental() {
     setIsFetching(true);
     const timer = setTimeout(() => {
       if (db) stockFundamentalQueryBuilder.setDatabase(db);
-      stockFundamentalQueryBuilder
-        .getStocksByConditions({ conditions: prompts })
-        .then((stockIds) => {
-          if (!stockIds || stockIds.length === 0) {
-            setResults([]);
-          } else {
-            query(
-              `SELECT * FROM stock WHERE stock_id IN (${stockIds.map((id) => `'${id}'`).join(",")})`,
-            ).then((data: StockTableType[] | null) => setResults(data || []));
-          }
+      screenFundamentals(prompts)
+        .then(setResults)
+        .catch((e) => {
+          error(`[Fundamental] screen: ${e}`);
+          setResults([]);
         })
         .finally(() => setIsFetching(false));
     }, 400);
     return () => clearTimeout(timer);
-  }, [prompts, db, query]);
+  }, [prompts, db, screenFundamentals]);
 
   const handleAdd = () => {
     if (!selects.indicator || !selects.operator || !selects.value) return;
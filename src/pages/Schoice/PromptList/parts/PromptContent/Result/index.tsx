@@ -1,15 +1,32 @@
 import AnalyticsIcon from "@mui/icons-material/Analytics";
 import SearchOffIcon from "@mui/icons-material/SearchOff";
-import { Box, CircularProgress, Paper, Stack, Typography } from "@mui/material";
+import TerminalIcon from "@mui/icons-material/Terminal";
+import {
+  Box,
+  CircularProgress,
+  IconButton,
+  Paper,
+  Stack,
+  Tooltip,
+  Typography,
+} from "@mui/material";
 import { alpha, styled } from "@mui/material/styles";
+import { invoke } from "@tauri-apps/api/core";
+import { error } from "@tauri-apps/plugin-log";
 import { AnimatePresence, motion } from "framer-motion";
+import { useContext } from "react";
+import { toast } from "react-toastify";
 import useSWR from "swr";
+import { stockFundamentalQueryBuilder } from "../../../../../../classes/StockFundamentalQueryBuilder";
+import ExportButton from "../../../../../../components/ExportButton";
 import ResultTable from "../../../../../../components/ResultTable/ResultTable";
-import useDatabaseQuery from "../../../../../../hooks/useDatabaseQuery";
-import useFindStocksByPrompt from "../../../../../../hooks/useFindStocksByPrompt";
+import { DatabaseContext } from "../../../../../../context/DatabaseContext";
+import useScreenStocks from "../../../../../../hooks/useScreenStocks";
 import useCloudStore from "../../../../../../store/Cloud.store";
 import useSchoiceStore from "../../../../../../store/Schoice.store";
-import { SelectType, StockTableType } from "../../../../../../types";
+import { getStore } from "../../../../../../store/Setting.store";
+import { SelectType } from "../../../../../../types";
+import compilePromptTemplate from "../../../../../../utils/compilePromptTemplate";
 
 // --- Styled Components ---
 
@@ -63,12 +80,15 @@ const AnimatedContent = styled(motion.div)({
 });
 
 export default function Result({ select }: { select: SelectType }) {
-  const { getPromptSqlScripts, getCombinedSqlScript } = useFindStocksByPrompt();
+  const { screenPrompt } = useScreenStocks();
   const bulls = useCloudStore((state) => state.bulls);
   const bears = useCloudStore((state) => state.bears);
+  const fundamentalCondition = useCloudStore(
+    (state) => state.fundamentalCondition,
+  );
   const dateIndex = useSchoiceStore((state) => state.dateIndex);
   const filterStocks = useSchoiceStore((state) => state.filterStocks);
-  const query = useDatabaseQuery();
+  const { dates } = useContext(DatabaseContext);
 
   // 使用 SWR 處理資料抓取，將策略清單長度加入 Key 以確保雲端資料載入後自動重新整理
   const { data: result = [], isValidating: loading } = useSWR(
@@ -77,6 +97,7 @@ export default function Result({ select }: { select: SelectType }) {
       select.prompt_id,
       dateIndex,
       filterStocks?.length,
+      fundamentalCondition,
       Object.keys(bulls).length,
       Object.keys(bears).length,
     ],
@@ -86,32 +107,7 @@ export default function Result({ select }: { select: SelectType }) {
           ? bulls[select.prompt_id]
           : bears[select.prompt_id];
       if (!item) return [];
-
-      // 如果 filterStocks 是空陣列 []，傳遞 undefined 給 SQL 產生器以避免 IN ('') 查不到資料
-      const stockIds =
-        filterStocks && filterStocks.length > 0
-          ? filterStocks.map((item) => item.stock_id)
-          : undefined;
-
-      const sqls = await getPromptSqlScripts(item, stockIds);
-      if (sqls.length === 0) return [];
-
-      const combinedSQL = getCombinedSqlScript(sqls);
-      if (!combinedSQL || !combinedSQL.trim()) {
-        console.log("No valid SQL generated");
-        return [];
-      }
-
-      const res: { stock_id: string }[] | undefined = await query(combinedSQL);
-      if (res && res.length > 0) {
-        const data: StockTableType[] | null = await query(
-          `SELECT * FROM stock WHERE stock_id IN (${res
-            .map((r) => `'${r.stock_id}'`)
-            .join(",")}) Order By stock_id ASC`,
-        );
-        return data || [];
-      }
-      return [];
+      return screenPrompt(select.prompt_id, item);
     },
     {
       keepPreviousData: true,
@@ -129,6 +125,34 @@ export default function Result({ select }: { select: SelectType }) {
       : bears[select.prompt_id]?.conditions,
   );
 
+  // 存成 `schoice screen --prompt` / `schoice backtest --prompt` 使用的策略檔
+  const savePromptTemplate = async () => {
+    const item =
+      select.type === "bull"
+        ? bulls[select.prompt_id]
+        : bears[select.prompt_id];
+    if (!item) return;
+    try {
+      const store = await getStore();
+      const adjusted =
+        (await store.get<string>("priceAdjustment")) === "adjusted";
+      const path = await invoke<string | null>("save_prompt_template", {
+        template: {
+          id: select.prompt_id,
+          name: item.name,
+          sql: compilePromptTemplate(item, adjusted),
+          fundamentals: stockFundamentalQueryBuilder.toExpressions(
+            fundamentalCondition ?? [],
+          ),
+        },
+      });
+      if (path) toast.success(`已儲存至 ${path}`);
+    } catch (e) {
+      error(`[Result] save_prompt_template: ${e}`);
+      toast.error(`儲存失敗: ${e}`);
+    }
+  };
+
   return (
     <GlassWrapper>
       <AnimatePresence>
@@ -223,18 +247,33 @@ export default function Result({ select }: { select: SelectType }) {
           </Stack>
 
           {result.length > 0 && (
-            <StatsBadge elevation={0}>
-              <Typography
-                variant="caption"
-                fontWeight={900}
-                color="primary.main"
-              >
-                MATCHES
-              </Typography>
-              <Typography variant="body2" fontWeight={900}>
-                {result.length}
-              </Typography>
-            </StatsBadge>
+            <Stack direction="row" spacing={1} alignItems="center">
+              <Tooltip title="儲存 CLI 策略檔">
+                <IconButton size="small" onClick={savePromptTemplate}>
+                  <TerminalIcon fontSize="small" />
+                </IconButton>
+              </Tooltip>
+              <ExportButton
+                fileName={`${strategyName || "screen"}_${dates[dateIndex]}`}
+                getData={() => ({
+                  source: "screen",
+                  date: dates[dateIndex],
+                  rows: result,
+                })}
+              />
+              <StatsBadge elevation={0}>
+                <Typography
+                  variant="caption"
+                  fontWeight={900}
+                  color="primary.main"
+                >
+                  MATCHES
+                </Typography>
+                <Typography variant="body2" fontWeight={900}>
+                  {result.length}
+                </Typography>
+              </StatsBadge>
+            </Stack>
           )}
         </Stack>
 
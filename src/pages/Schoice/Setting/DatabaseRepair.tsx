@@ -4,12 +4,50 @@ import {
   Card,
   CardActions,
   CardContent,
+  CircularProgress,
   Grid,
   Stack,
   Typography,
 } from "@mui/material";
+import { invoke } from "@tauri-apps/api/core";
+import { error } from "@tauri-apps/plugin-log";
+import { useCallback, useState } from "react";
+import { toast } from "react-toastify";
+
+type DataQualityReport = {
+  checked_stocks: number;
+  issues: { stock_id: string; timeframe: string; kind: string; t: string }[];
+  repaired: { stock_id: string; unresolved: number; error: string | null }[];
+};
 
 export default function DatabaseRepair() {
+  const [loading, setLoading] = useState(false);
+
+  const handleRepair = useCallback(async () => {
+    setLoading(true);
+    try {
+      const report = await invoke<DataQualityReport>("validate_data", {
+        repair: true,
+      });
+      const unresolved = report.repaired.reduce(
+        (sum, item) => sum + item.unresolved,
+        0,
+      );
+      if (report.issues.length === 0) {
+        toast.success(`已檢查 ${report.checked_stocks} 檔股票，未發現異常`);
+      } else {
+        toast.info(
+          `發現 ${report.issues.length} 筆異常，修復 ${report.repaired.length} 組，仍有 ${unresolved} 筆未解決`,
+        );
+      }
+    } catch (e) {
+      error(`[DatabaseRepair] ${e}`);
+      toast.error(`修復失敗: ${e}`);
+    } finally {
+      setLoading(false);
+    }
+  }, []);
+
   return (
     <Grid size={{ xs: 12, md: 6 }}>
       <Card sx={{ height: "100%" }}>
@@ -28,7 +66,11 @@ export default function DatabaseRepair() {
           <Button
             variant="contained"
             color="primary"
-            startIcon={<Settings />}
+            startIcon={
+              loading ? <CircularProgress size={16} color="inherit" /> : <Settings />
+            }
+            disabled={loading}
+            onClick={handleRepair}
             fullWidth
           >
             開始修復
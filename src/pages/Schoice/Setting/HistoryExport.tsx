@@ -0,0 +1,104 @@
+import { Storage } from "@mui/icons-material";
+import {
+  Button,
+  Card,
+  CardActions,
+  CardContent,
+  CircularProgress,
+  FormControlLabel,
+  Grid,
+  Radio,
+  RadioGroup,
+  Stack,
+  Typography,
+} from "@mui/material";
+import { invoke } from "@tauri-apps/api/core";
+import { error } from "@tauri-apps/plugin-log";
+import { useCallback, useState } from "react";
+import { toast } from "react-toastify";
+
+type ColumnarFormat = "parquet" | "arrow";
+
+/** 對應 Rust `columnar::DatasetReport` */
+type DatasetReport = {
+  dir: string;
+  rows: number;
+  files: { timeframe: string; year: number; path: string; rows: number }[];
+};
+
+export default function HistoryExport() {
+  const [format, setFormat] = useState<ColumnarFormat>("parquet");
+  const [loading, setLoading] = useState(false);
+
+  const handleExport = useCallback(async () => {
+    setLoading(true);
+    try {
+      const report = await invoke<DatasetReport | null>("export_history", {
+        options: {
+          format,
+          timeframes: ["daily", "weekly", "hourly"],
+          from_year: null,
+          to_year: null,
+        },
+      });
+      if (report) {
+        toast.success(
+          `已匯出 ${report.rows} 筆 (${report.files.length} 個檔案) 至 ${report.dir}`,
+        );
+      }
+    } catch (e) {
+      error(`[HistoryExport] ${e}`);
+      toast.error(`匯出失敗: ${e}`);
+    } finally {
+      setLoading(false);
+    }
+  }, [format]);
+
+  return (
+    <Grid size={{ xs: 12, md: 6 }}>
+      <Card sx={{ height: "100%" }}>
+        <CardContent>
+          <Stack direction="row" alignItems="center" spacing={1} mb={1}>
+            <Storage color="primary" />
+            <Typography variant="h6" fontWeight="bold">
+              歷史資料匯出
+            </Typography>
+          </Stack>
+          <Typography variant="body2" color="text.secondary" mb={1}>
+            將 K 線與技術指標依時框、年度分區匯出，可直接以 pandas / polars
+            讀取整個資料夾。
+          </Typography>
+          <RadioGroup
+            row
+            value={format}
+            onChange={(e) => setFormat(e.target.value as ColumnarFormat)}
+          >
+            <FormControlLabel
+              value="parquet"
+              control={<Radio size="small" />}
+              label="Parquet"
+            />
+            <FormControlLabel
+              value="arrow"
+              control={<Radio size="small" />}
+              label="Arrow IPC"
+            />
+          </RadioGroup>
+        </CardContent>
+        <CardActions>
+          <Button
+            variant="contained"
+            startIcon={
+              loading ? <CircularProgress size={16} color="inherit" /> : null
+            }
+            disabled={loading}
+            onClick={handleExport}
+            fullWidth
+          >
+            選擇資料夾並匯出
+          </Button>
+        </CardActions>
+      </Card>
+    </Grid>
+  );
+}
@@ -1,5 +1,6 @@
 import { Settings } from "@mui/icons-material";
 import {
+  Button,
   Card,
   CardContent,
   Grid,
@@ -7,17 +8,83 @@ import {
   Switch,
   Typography,
 } from "@mui/material";
+import { invoke } from "@tauri-apps/api/core";
+import { error } from "@tauri-apps/plugin-log";
+import { useEffect, useState } from "react";
+import { toast } from "react-toastify";
 import LanguageSwitcher from "../../../components/LanguageSwitcher";
 import useSchoiceStore from "../../../store/Schoice.store";
+import { getStore } from "../../../store/Setting.store";
+
+type ApiInfo = { enabled: boolean; port: number; token: string };
 
 export default function OtherSettings() {
   const { theme, changeTheme } = useSchoiceStore();
 
+  const [adjusted, setAdjusted] = useState(false);
+  const [adjusting, setAdjusting] = useState(false);
+  const [watchlistSync, setWatchlistSync] = useState(false);
+  const [apiInfo, setApiInfo] = useState<ApiInfo | null>(null);
+  const [apiEnabled, setApiEnabled] = useState(false);
+
+  useEffect(() => {
+    invoke<string>("get_price_mode").then((mode) =>
+      setAdjusted(mode === "adjusted"),
+    );
+    getStore()
+      .then((store) => store.get<boolean>("watchlistCloudSync"))
+      .then((value) => setWatchlistSync(!!value));
+    getStore()
+      .then((store) => store.get<boolean>("apiEnabled"))
+      .then((value) => setApiEnabled(!!value));
+    invoke<ApiInfo>("get_api_info").then(setApiInfo);
+  }, []);
+
   const onThemeChange = () => {
     if (theme === "light") changeTheme("dark");
     else changeTheme("light");
   };
 
+  const onPriceModeChange = async () => {
+    const mode = adjusted ? "raw" : "adjusted";
+    setAdjusting(true);
+    try {
+      const count = await invoke<number>("set_price_mode", { mode });
+      setAdjusted(!adjusted);
+      toast.success(`已重新計算 ${count} 檔除權息股票的技術指標`);
+    } catch (e) {
+      error(`[OtherSettings] set_price_mode: ${e}`);
+      toast.error(`切換失敗: ${e}`);
+    } finally {
+      setAdjusting(false);
+    }
+  };
+
+  const onWatchlistSyncChange = async () => {
+    const store = await getStore();
+    await store.set("watchlistCloudSync", !watchlistSync);
+    await store.save();
+    setWatchlistSync(!watchlistSync);
+  };
+
+  // 伺服器只在啟動時讀取設定，切換後需重新啟動 App
+  const onApiEnabledChange = async () => {
+    const store = await getStore();
+    await store.set("apiEnabled", !apiEnabled);
+    await store.save();
+    setApiEnabled(!apiEnabled);
+    toast.info("重新啟動後生效");
+  };
+
+  const onResetApiToken = async () => {
+    try {
+      setApiInfo(await invoke<ApiInfo>("reset_api_token"));
+      toast.info("已重新產生權杖，重新啟動後生效");
+    } catch (e) {
+      error(`[OtherSettings] reset_api_token: ${e}`);
+    }
+  };
+
   return (
     <Grid size={{ xs: 12, md: 6 }}>
       <Card sx={{ height: "100%" }}>
@@ -50,6 +117,51 @@ export default function OtherSettings() {
                 <Typography variant="body2">語言</Typography>
               </Stack>
             </Stack>
+            <Stack direction="row" alignItems="center" spacing={1}>
+              <Switch
+                checked={adjusted}
+                onChange={onPriceModeChange}
+                disabled={adjusting}
+                color="success"
+              />
+              <Typography variant="body2">
+                使用還原權值價格選股 (日線除權息 / 減資調整)
+              </Typography>
+            </Stack>
+            <Stack direction="row" alignItems="center" spacing={1}>
+              <Switch
+                checked={watchlistSync}
+                onChange={onWatchlistSyncChange}
+                color="success"
+              />
+              <Typography variant="body2">
+                本地自選股清單與雲端同步 (登入時合併)
+              </Typography>
+            </Stack>
+            <Stack direction="row" alignItems="center" spacing={1}>
+              <Switch
+                checked={apiEnabled}
+                onChange={onApiEnabledChange}
+                color="success"
+              />
+              <Typography variant="body2">
+                啟用本地 API (僅限 127.0.0.1)
+              </Typography>
+            </Stack>
+            {apiEnabled && apiInfo && (
+              <Stack direction="row" alignItems="center" spacing={1} pl={1}>
+                <Typography
+                  variant="caption"
+                  color="text.secondary"
+                  sx={{ wordBreak: "break-all" }}
+                >
+                  http://127.0.0.1:{apiInfo.port} ・ Bearer {apiInfo.token}
+                </Typography>
+                <Button size="small" onClick={onResetApiToken}>
+                  重新產生
+                </Button>
+              </Stack>
+            )}
           </Stack>
         </CardContent>
       </Card>
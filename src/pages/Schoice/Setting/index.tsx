@@ -3,6 +3,7 @@ import CheckUpdate from "./CheckUpdate";
 import DatabaseInitialization from "./DatabaseInitialization";
 import DatabaseSettings from "./DatabaseSettings";
 import ExampleSelector from "./ExampleSelector";
+import HistoryExport from "./HistoryExport";
 import OtherSettings from "./OtherSettings";
 import StockMenuSettings from "./StockMenuSettings";
 import SystemStatus from "./SystemStatus";
@@ -29,6 +30,7 @@ export default function Setting() {
             <StockMenuSettings />
           </Grid>
           <OtherSettings />
+          <HistoryExport />
           <ExampleSelector />
           <CheckUpdate />
         </Grid>
@@ -1,6 +1,8 @@
 import { alpha, AppBar, Box, Stack, styled } from "@mui/material";
 import GlobalSyncIndicator from "../../../../components/SyncEngine/GlobalSyncIndicator";
+import useAlertRules from "../../../../hooks/useAlertRules";
 import useInitFilterStock from "../../../../hooks/useInitFilterStock";
+import usePromptTemplates from "../../../../hooks/usePromptTemplates";
 import Actions from "./BottomBar/Actions";
 import Breadcrumb from "./BottomBar/Breadcrumb";
 import LatestDate from "./TopBar/LatestDate";
@@ -46,6 +48,8 @@ const VerticalDivider = styled(Box)(({ theme }) => ({
 
 export default function Header() {
   useInitFilterStock();
+  useAlertRules();
+  usePromptTemplates();
 
   return (
     <HeaderContainer>
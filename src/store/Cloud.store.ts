@@ -1,6 +1,8 @@
+import { invoke } from "@tauri-apps/api/core";
 import { nanoid } from "nanoid";
 import { create } from "zustand";
 import { handleError } from "../tools/error";
+import { getStore } from "./Setting.store";
 import { supabase } from "../tools/supabase";
 import {
   FundamentalPrompts,
@@ -49,6 +51,7 @@ interface CloudState {
   remove: (id: string, type: PromptType, userId: string) => Promise<void>;
   removeFromTrash: (index: number, id: string, userId: string) => Promise<void>;
   reload: (userId: string) => Promise<void>;
+  reconcileWatchlist: (userId: string) => Promise<void>;
 }
 
 const useCloudStore = create<CloudState>((set, get) => ({
@@ -487,10 +490,35 @@ const useCloudStore = create<CloudState>((set, get) => ({
           ? JSON.parse(fundamentalCondition)
           : null,
       }));
+
+      const store = await getStore();
+      if (await store.get<boolean>("watchlistCloudSync")) {
+        await get().reconcileWatchlist(userId);
+      }
     } catch (error) {
       handleError(error, "reload");
     }
   },
+  reconcileWatchlist: async (userId: string) => {
+    try {
+      // 以上次對帳快照與本地 watchlist 雙向同步：雲端的新增與刪除由 Rust 套用到本地，
+      // 本地的新增上傳到雲端、刪除則自雲端移除
+      const { missing_in_cloud, removed_locally } = await invoke<{
+        imported: number;
+        removed: number;
+        missing_in_cloud: string[];
+        removed_locally: string[];
+      }>("reconcile_watchlist", { cloud: get().watchStocks });
+      for (const stockId of missing_in_cloud) {
+        await get().addToWatchList(stockId, userId);
+      }
+      for (const stockId of removed_locally) {
+        await get().removeFromWatchList(stockId, userId);
+      }
+    } catch (err) {
+      handleError(err, "reconcileWatchlist");
+    }
+  },
 }));
 
 export default useCloudStore;
@@ -14,6 +14,18 @@ export async function getStore() {
     if (!(await storeInstance.has("autoUpdate"))) {
       await storeInstance.set("autoUpdate", false);
     }
+    if (!(await storeInstance.has("priceAdjustment"))) {
+      await storeInstance.set("priceAdjustment", "raw");
+    }
+    if (!(await storeInstance.has("watchlistCloudSync"))) {
+      await storeInstance.set("watchlistCloudSync", false);
+    }
+    if (!(await storeInstance.has("apiEnabled"))) {
+      await storeInstance.set("apiEnabled", false);
+    }
+    if (!(await storeInstance.has("apiPort"))) {
+      await storeInstance.set("apiPort", 17878);
+    }
     if (!(await storeInstance.has("lastMenuUpdate"))) {
       await storeInstance.set("lastMenuUpdate", 0);
     }
@@ -60,12 +60,24 @@ export type QueryBuilderMappingItem = {
 /****
  * Supabase
  ****/
+export type SecurityType =
+  | "stock"
+  | "etf"
+  | "etn"
+  | "warrant"
+  | "tdr"
+  | "preferred"
+  | "reit"
+  | "other";
+
 export type StockTableType = {
   stock_id: string;
   stock_name: string;
   industry_group: string;
   market_type: string;
   issued_shares?: number;
+  /** 對應 Rust `listing::SecurityType`，舊資料沒有此欄位時視為 stock */
+  security_type?: SecurityType;
   options?: Map<string, WatchStockItem>;
 };
 
@@ -247,6 +259,14 @@ export type SkillsTableType = {
   cmf: number;
   cmf_ema5: number;
   turnover_rate?: number;
+  atr?: number | null;
+  cci?: number | null;
+  donchian_ub?: number | null;
+  donchian_ma?: number | null;
+  donchian_lb?: number | null;
+  supertrend?: number | null;
+  supertrend_dir?: number | null;
+  mss?: number | null;
 };
 
 export type TickDealsType = {
@@ -326,6 +346,14 @@ export type TimeSharingSkillsTableType = {
   cmf: number;
   cmf_ema5: number;
   turnover_rate?: number;
+  atr?: number | null;
+  cci?: number | null;
+  donchian_ub?: number | null;
+  donchian_ma?: number | null;
+  donchian_lb?: number | null;
+  supertrend?: number | null;
+  supertrend_dir?: number | null;
+  mss?: number | null;
 };
 
 /****
@@ -0,0 +1,16 @@
+import Database from "@tauri-apps/plugin-sql";
+
+/**
+ * 遞增資料表的寫入版本，Rust 端選股結果快取以此判斷是否過期。
+ * 每批寫入完成後呼叫一次，不以逐列觸發器遞增以免放大寫入量。
+ */
+export default async function bumpDataVersion(
+  db: Database,
+  tables: string[],
+) {
+  if (tables.length === 0) return;
+  await db.execute(
+    "UPDATE data_version SET version = version + 1 WHERE table_name IN (SELECT value FROM json_each($1))",
+    [JSON.stringify(tables)],
+  );
+}
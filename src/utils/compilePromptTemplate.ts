@@ -0,0 +1,49 @@
+import { stockDailyQueryBuilder } from "../classes/StockDailyQueryBuilder";
+import { stockHourlyQueryBuilder } from "../classes/StockHourlyQueryBuilder";
+import { stockWeeklyQueryBuilder } from "../classes/StockWeeklyQueryBuilder";
+import { PromptItem } from "../types";
+
+// 與 useFindStocksByPrompt 取用的日期數量一致，Rust 端 screen.rs 會以選定日期替換佔位符
+const placeholders = (prefix: string, count: number) =>
+  Array.from({ length: count }, (_, i) => `{{${prefix}:${i}}}`);
+
+/** 將策略編譯為含日期佔位符的 SQL 樣板，供警示評估與 CLI 選股 / 回測使用 */
+export default function compilePromptTemplate(
+  prompt: PromptItem,
+  adjusted: boolean,
+) {
+  const { daily, weekly, hourly } = prompt.conditions;
+  const sqls: string[] = [];
+  if (daily.length > 0) {
+    sqls.push(
+      stockDailyQueryBuilder.generateSqlQuery({
+        conditions: daily.map((p) =>
+          stockDailyQueryBuilder.generateExpression(p).join(" "),
+        ),
+        dates: placeholders("day", 30),
+        adjusted,
+      }),
+    );
+  }
+  if (weekly.length > 0) {
+    sqls.push(
+      stockWeeklyQueryBuilder.generateSqlQuery({
+        conditions: weekly.map((p) =>
+          stockWeeklyQueryBuilder.generateExpression(p).join(" "),
+        ),
+        dates: placeholders("week", 4),
+      }),
+    );
+  }
+  if (hourly?.length > 0) {
+    sqls.push(
+      stockHourlyQueryBuilder.generateSqlQuery({
+        conditions: hourly.map((p) =>
+          stockHourlyQueryBuilder.generateExpression(p).join(" "),
+        ),
+        dates: placeholders("hour", 24),
+      }),
+    );
+  }
+  return sqls.join("\nINTERSECT\n");
+}